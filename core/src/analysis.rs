@@ -0,0 +1,159 @@
+use alloc::vec::Vec;
+use crate::{Fork, Opcode};
+
+/// Basic block of legacy code: instructions always run in sequence, entered
+/// at the first and left after the last.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BasicBlock {
+	/// Position of the first instruction.
+	pub start: usize,
+	/// Number of instructions.
+	pub len: usize,
+	/// Stack height needed on entry for no instruction to underflow.
+	pub min_height: usize,
+	/// Maximum stack height reached above the height on entry.
+	pub max_growth: usize,
+}
+
+impl BasicBlock {
+	/// Whether the block runs without stack underflow or overflow from a stack
+	/// of `height` items limited to `limit`.
+	#[must_use]
+	pub const fn fits(&self, height: usize, limit: usize) -> bool {
+		height >= self.min_height && height + self.max_growth <= limit
+	}
+}
+
+/// Split legacy code into basic blocks, sorted by position.
+///
+/// A block starts at the beginning of the code, at each `JUMPDEST` and after
+/// each block end, and ends with a jump, a halt, an opcode trapping to the
+/// runtime or an undefined opcode.
+#[must_use]
+pub fn basic_blocks(code: &[u8]) -> Vec<BasicBlock> {
+	let mut blocks = Vec::new();
+	// Current block, and the stack height if it is entered with `min_height`.
+	let mut current: Option<(BasicBlock, usize)> = None;
+	let mut position = 0;
+
+	while position < code.len() {
+		let opcode = Opcode(code[position]);
+		if opcode == Opcode::JUMPDEST {
+			blocks.extend(current.take().map(|(block, _)| block));
+		}
+
+		let (mut block, mut height) = current.take().unwrap_or((
+			BasicBlock { start: position, len: 0, min_height: 0, max_growth: 0 },
+			0,
+		));
+
+		let info = opcode.info();
+		let (inputs, outputs) = info.map_or((0, 0), |info| (usize::from(info.inputs), usize::from(info.outputs)));
+		if height < inputs {
+			block.min_height += inputs - height;
+			height = inputs;
+		}
+		height = height - inputs + outputs;
+		block.max_growth = block.max_growth.max(height.saturating_sub(block.min_height));
+		block.len += 1;
+
+		if is_block_end(opcode) {
+			blocks.push(block);
+		} else {
+			current = Some((block, height));
+		}
+		position += 1 + info.map_or(0, |info| usize::from(info.immediate_size));
+	}
+
+	blocks.extend(current.map(|(block, _)| block));
+	blocks
+}
+
+/// Whether an opcode ends a basic block, either by leaving the sequence or by
+/// handing the stack over to the runtime.
+fn is_block_end(opcode: Opcode) -> bool {
+	match opcode {
+		Opcode::STOP | Opcode::JUMP | Opcode::JUMPI | Opcode::RETURN | Opcode::REVERT | Opcode::INVALID => true,
+		_ => opcode.info().is_none_or(|info| info.is_external || info.fork == Fork::Eof),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::vec::Vec;
+	use crate::{
+		assemble, basic_blocks, BasicBlock, Capture, Context, ExitError, ExitReason, ExitSucceed, Machine, Opcode, Trap,
+		H160, U256,
+	};
+
+	const CONTEXT: Context = Context {
+		address: H160::zero(),
+		caller: H160::zero(),
+		apparent_value: U256::zero(),
+		is_static: false,
+	};
+
+	fn run(source: &str, stack_limit: usize) -> (Machine, u64, Capture<ExitReason, Trap>) {
+		let (code, valids) = assemble(source).unwrap();
+		let mut machine = Machine::new(code, valids, Vec::new(), stack_limit, 10000);
+		let (steps, capture) = machine.run(1000, |_, _| Ok(()), &CONTEXT);
+		(machine, steps, capture)
+	}
+
+	#[test]
+	fn test_basic_blocks() {
+		let (code, _) = assemble("
+			PUSH1 3
+			loop: JUMPDEST
+			PUSH1 1
+			SWAP1
+			SUB
+			DUP1
+			PUSH1 @loop
+			JUMPI
+			POP
+			CALLER
+			STOP
+		").unwrap();
+
+		assert_eq!(basic_blocks(&code), [
+			BasicBlock { start: 0, len: 1, min_height: 0, max_growth: 1 },
+			BasicBlock { start: 2, len: 7, min_height: 1, max_growth: 2 },
+			BasicBlock { start: 11, len: 2, min_height: 1, max_growth: 0 },
+			BasicBlock { start: 13, len: 1, min_height: 0, max_growth: 0 },
+		]);
+	}
+
+	#[test]
+	fn test_run_blocks() {
+		let (machine, steps, capture) = run("
+			PUSH1 3
+			loop: JUMPDEST
+			PUSH1 1
+			SWAP1
+			SUB
+			DUP1
+			PUSH1 @loop
+			JUMPI
+			STOP
+		", 1024);
+		assert_eq!((steps, capture), (22, Capture::Exit(ExitSucceed::Stopped.into())));
+		assert_eq!(machine.stack().len(), 1);
+
+		// Blocks not fitting the stack run checked, failing at the same opcode.
+		let (_, steps, capture) = run("PUSH1 1\nPUSH1 2\nADD\nADD", 1024);
+		assert_eq!((steps, capture), (3, Capture::Exit(ExitError::StackUnderflow.into())));
+		let (_, steps, capture) = run("PUSH0\nPUSH0\nPUSH0\nSTOP", 2);
+		assert_eq!((steps, capture), (2, Capture::Exit(ExitError::StackOverflow.into())));
+
+		// Opcodes trapping to the runtime end a block, so that running resumes
+		// at the start of the next one.
+		let (mut machine, steps, capture) = run("PUSH1 1\nCALLER\nADD\nSTOP", 1024);
+		assert_eq!((steps, capture), (1, Capture::Trap(Opcode::CALLER)));
+		machine.stack_mut().push_u256(U256::from(2)).unwrap();
+		let (steps, capture) = machine.run(1000, |_, _| Ok(()), &CONTEXT);
+		assert_eq!((steps, capture), (1, Capture::Exit(ExitSucceed::Stopped.into())));
+		assert_eq!(machine.stack().peek(0), Ok(U256::from(3)));
+	}
+}
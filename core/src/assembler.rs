@@ -0,0 +1,216 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use crate::{Opcode, Valids, U256};
+
+/// Error of assembling source text, with the line it occurred on, starting
+/// at 1.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AssemblyError {
+	/// A mnemonic is unknown.
+	UnknownOpcode(usize),
+	/// A `PUSH` immediate is missing, malformed or does not fit.
+	InvalidImmediate(usize),
+	/// An instruction other than `PUSH1` to `PUSH32` has an operand.
+	UnexpectedOperand(usize),
+	/// A label is defined twice.
+	DuplicateLabel(usize),
+	/// A label is used but never defined.
+	UnknownLabel(usize),
+	/// The bytes of a `.data` directive are not hex.
+	InvalidData(usize),
+}
+
+/// Operand of a `PUSH`.
+enum Immediate<'a> {
+	/// Bytes, padded to the size of the `PUSH`.
+	Bytes(Vec<u8>),
+	/// Position of a label.
+	Label(&'a str),
+}
+
+/// Assembled item of a line.
+enum Item<'a> {
+	Instruction(Opcode, Option<Immediate<'a>>),
+	Data(Vec<u8>),
+}
+
+/// Assemble source text into code and its valid jump destinations, as
+/// taken by `Machine::new`.
+///
+/// Each line holds an optional `label:`, then an instruction or a
+/// `.data` directive, then an optional `;` comment. Instructions are
+/// mnemonics as printed by `Opcode`; `PUSH1` to `PUSH32` take an immediate
+/// in hex (`0x2a`, left-padded with zeros), in decimal, or as the position
+/// of a label (`@loop`). `.data` emits raw hex bytes. Disassembled
+/// instructions assemble back into the same code, except for a truncated
+/// trailing `PUSH`, which gets padded.
+pub fn assemble(source: &str) -> Result<(Vec<u8>, Vec<u8>), AssemblyError> {
+	let mut labels = BTreeMap::new();
+	let mut items = Vec::new();
+	let mut position = 0;
+
+	for (index, line) in source.lines().enumerate() {
+		let line_number = index + 1;
+		let mut line = line.split(';').next().unwrap_or_default().trim();
+
+		if let Some((label, rest)) = line.split_once(':') {
+			let label = label.trim();
+			if labels.insert(label, position).is_some() {
+				return Err(AssemblyError::DuplicateLabel(line_number))
+			}
+			line = rest.trim();
+		}
+
+		let mut tokens = line.split_whitespace();
+		let Some(first) = tokens.next() else { continue };
+		let operand = tokens.next();
+		if tokens.next().is_some() {
+			return Err(AssemblyError::UnexpectedOperand(line_number))
+		}
+
+		let item = if first == ".data" {
+			let data = operand.and_then(parse_hex).ok_or(AssemblyError::InvalidData(line_number))?;
+			position += data.len();
+			Item::Data(data)
+		} else {
+			let opcode: Opcode = first.parse().map_err(|_| AssemblyError::UnknownOpcode(line_number))?;
+			let size = usize::from(opcode.is_push().unwrap_or_default());
+			let immediate = match (size, operand) {
+				(0, None) => None,
+				(0, Some(_)) => return Err(AssemblyError::UnexpectedOperand(line_number)),
+				(_, None) => return Err(AssemblyError::InvalidImmediate(line_number)),
+				(_, Some(operand)) => Some(parse_immediate(operand, size).ok_or(AssemblyError::InvalidImmediate(line_number))?),
+			};
+			position += 1 + size;
+			Item::Instruction(opcode, immediate)
+		};
+		items.push((line_number, item));
+	}
+
+	let mut code = Vec::with_capacity(position);
+	for (line_number, item) in items {
+		match item {
+			Item::Instruction(opcode, immediate) => {
+				code.push(opcode.as_u8());
+				match immediate {
+					Some(Immediate::Bytes(bytes)) => code.extend_from_slice(&bytes),
+					Some(Immediate::Label(label)) => {
+						let target = *labels.get(label).ok_or(AssemblyError::UnknownLabel(line_number))?;
+						let size = usize::from(opcode.is_push().unwrap_or_default());
+						let bytes = pad(&U256::from(target), size).ok_or(AssemblyError::InvalidImmediate(line_number))?;
+						code.extend_from_slice(&bytes);
+					},
+					None => (),
+				}
+			},
+			Item::Data(data) => code.extend_from_slice(&data),
+		}
+	}
+
+	let valids = Valids::compute(&code);
+	Ok((code, valids))
+}
+
+/// Parse the immediate of a `PUSH` of `size` bytes.
+fn parse_immediate(operand: &str, size: usize) -> Option<Immediate<'_>> {
+	if let Some(label) = operand.strip_prefix('@') {
+		return Some(Immediate::Label(label))
+	}
+
+	let value = if operand.starts_with("0x") {
+		let bytes = parse_hex(operand)?;
+		if bytes.len() > size {
+			return None
+		}
+		U256::from_big_endian(&bytes)
+	} else {
+		U256::from_dec_str(operand).ok()?
+	};
+
+	pad(&value, size).map(Immediate::Bytes)
+}
+
+/// Big-endian bytes of `value` in `size` bytes, if it fits.
+fn pad(value: &U256, size: usize) -> Option<Vec<u8>> {
+	if value.bits() > size * 8 {
+		return None
+	}
+
+	let mut bytes = [0_u8; 32];
+	value.to_big_endian(&mut bytes);
+	Some(bytes[32 - size..].to_vec())
+}
+
+/// Parse hex bytes, with an optional `0x` prefix.
+fn parse_hex(s: &str) -> Option<Vec<u8>> {
+	let s = s.strip_prefix("0x").unwrap_or(s);
+	if !s.len().is_multiple_of(2) {
+		return None
+	}
+
+	(0..s.len())
+		.step_by(2)
+		.map(|i| s.get(i..i + 2).and_then(|byte| u8::from_str_radix(byte, 16).ok()))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::string::{String, ToString};
+	use alloc::vec::Vec;
+	use crate::{
+		assemble, AssemblyError, Capture, Context, Disassembler, ExitSucceed, Machine, Valids, H160, U256,
+	};
+
+	#[test]
+	fn test_assemble() {
+		let source = "
+			; Jump over the data to `end`.
+			PUSH1 @end
+			JUMP
+			data: .data 0x5b5b
+			end: JUMPDEST
+			PUSH2 300
+			PUSH1 @data ; position of the data
+			STOP
+		";
+		let (code, valids) = assemble(source).unwrap();
+		assert_eq!(code, [0x60, 0x05, 0x56, 0x5b, 0x5b, 0x5b, 0x61, 0x01, 0x2c, 0x60, 0x03, 0x00]);
+		assert_eq!(valids, Valids::compute(&code));
+
+		let context = Context {
+			address: H160::default(),
+			caller: H160::default(),
+			apparent_value: U256::zero(),
+			is_static: false,
+		};
+		let mut machine = Machine::new(code, valids, Vec::new(), 1024, 10000);
+		let (_, capture) = machine.run(1000, |_, _| Ok(()), &context);
+		assert_eq!(capture, Capture::Exit(ExitSucceed::Stopped.into()));
+		assert_eq!(machine.stack().peek(0), Ok(U256::from(3)));
+		assert_eq!(machine.stack().peek(1), Ok(U256::from(300)));
+	}
+
+	#[test]
+	fn test_round_trip() {
+		let code = [0x60, 0x2a, 0x5f, 0x01, 0x0c, 0x7f, 0xff, 0x00, 0x5b, 0xfe, 0x00];
+		let code = [&code[..], &[0x11; 29][..]].concat();
+		let source = Disassembler::new(&code)
+			.map(|instruction| instruction.to_string())
+			.collect::<Vec<String>>()
+			.join("\n");
+
+		assert_eq!(assemble(&source).unwrap().0, code);
+	}
+
+	#[test]
+	fn test_assemble_errors() {
+		assert_eq!(assemble("ADD\nFOO"), Err(AssemblyError::UnknownOpcode(2)));
+		assert_eq!(assemble("PUSH1 256"), Err(AssemblyError::InvalidImmediate(1)));
+		assert_eq!(assemble("PUSH2"), Err(AssemblyError::InvalidImmediate(1)));
+		assert_eq!(assemble("ADD 1"), Err(AssemblyError::UnexpectedOperand(1)));
+		assert_eq!(assemble("a: STOP\na: STOP"), Err(AssemblyError::DuplicateLabel(2)));
+		assert_eq!(assemble("PUSH1 @b"), Err(AssemblyError::UnknownLabel(1)));
+		assert_eq!(assemble(".data 0xz0"), Err(AssemblyError::InvalidData(1)));
+	}
+}
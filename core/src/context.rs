@@ -46,6 +46,9 @@ pub struct Context {
 	pub caller: H160,
 	/// Apparent value of the EVM.
 	pub apparent_value: U256,
+	/// Whether the frame runs in static mode, where state-changing
+	/// operations fail with `StaticModeViolation`. Inherited by subcalls.
+	pub is_static: bool,
 }
 
 
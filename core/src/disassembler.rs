@@ -0,0 +1,102 @@
+use core::fmt;
+use crate::Opcode;
+
+/// Instruction of disassembled code.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Instruction<'a> {
+	/// Position of the opcode in the code.
+	pub position: usize,
+	/// Opcode of the instruction.
+	pub opcode: Opcode,
+	/// Immediate bytes of a `PUSH`. Truncated if the code ends before them,
+	/// in which case `PUSH` takes only the bytes present.
+	pub immediate: &'a [u8],
+}
+
+/// Prints the mnemonic, followed by the immediate bytes in hex if any.
+impl fmt::Display for Instruction<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.opcode)?;
+		if !self.immediate.is_empty() {
+			f.write_str(" 0x")?;
+			for byte in self.immediate {
+				write!(f, "{byte:02x}")?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Iterator over the instructions of legacy code.
+#[derive(Clone, Debug)]
+pub struct Disassembler<'a> {
+	code: &'a [u8],
+	position: usize,
+}
+
+impl<'a> Disassembler<'a> {
+	/// Disassemble `code` from its start.
+	#[must_use]
+	pub const fn new(code: &'a [u8]) -> Self {
+		Self { code, position: 0 }
+	}
+}
+
+impl<'a> Iterator for Disassembler<'a> {
+	type Item = Instruction<'a>;
+
+	fn next(&mut self) -> Option<Instruction<'a>> {
+		let position = self.position;
+		let opcode = Opcode(*self.code.get(position)?);
+
+		let start = position + 1;
+		let end = opcode.is_push().map_or(start, |n| start + usize::from(n)).min(self.code.len());
+		self.position = end;
+
+		Some(Instruction {
+			position,
+			opcode,
+			immediate: &self.code[start..end],
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::string::ToString;
+	use alloc::vec::Vec;
+	use crate::{Disassembler, Opcode};
+
+	#[test]
+	fn test_disassemble() {
+		let code = [0x60, 0x2a, 0x5f, 0x01, 0x0c, 0x61, 0xff];
+		let instructions = Disassembler::new(&code)
+			.map(|instruction| (instruction.position, instruction.opcode, instruction.immediate))
+			.collect::<Vec<_>>();
+
+		assert_eq!(instructions, [
+			(0, Opcode::PUSH1, &[0x2a][..]),
+			(2, Opcode::PUSH0, &[][..]),
+			(3, Opcode::ADD, &[][..]),
+			(4, Opcode(0x0c), &[][..]),
+			(5, Opcode::PUSH2, &[0xff][..]),
+		]);
+
+		let text = Disassembler::new(&code).map(|instruction| instruction.to_string()).collect::<Vec<_>>();
+		assert_eq!(text, ["PUSH1 0x2a", "PUSH0", "ADD", "0x0c", "PUSH2 0xff"]);
+	}
+
+	#[test]
+	fn test_opcode_names() {
+		for byte in 0..=u8::MAX {
+			let opcode = Opcode(byte);
+			assert_eq!(opcode.to_string().parse(), Ok(opcode));
+		}
+
+		assert_eq!(Opcode::SHA3.name(), Some("SHA3"));
+		assert_eq!("keccak256".parse(), Ok(Opcode::SHA3));
+		assert_eq!("SELFDESTRUCT".parse(), Ok(Opcode::SUICIDE));
+		assert!("PUSH33".parse::<Opcode>().is_err());
+		assert_eq!(Opcode(0xef).name(), None);
+	}
+}
@@ -0,0 +1,532 @@
+use alloc::{vec, vec::Vec};
+use core::convert::TryFrom;
+use crate::Opcode;
+
+/// Magic prefix of an EOF container (EIP-3540).
+pub const EOF_MAGIC: [u8; 2] = [0xef, 0x00];
+/// The only supported EOF version.
+pub const EOF_VERSION: u8 = 0x01;
+
+const KIND_TYPE: u8 = 0x01;
+const KIND_CODE: u8 = 0x02;
+const KIND_DATA: u8 = 0x03;
+const TERMINATOR: u8 = 0x00;
+
+/// Maximum number of code sections of a container.
+const MAX_CODE_SECTIONS: usize = 1024;
+/// Maximum stack height of a code section (EIP-5450).
+const MAX_STACK_HEIGHT: usize = 1023;
+/// Maximum stack height a `CALLF` can reach in its callee (EIP-5450).
+const STACK_LIMIT: usize = 1024;
+/// Maximum number of inputs or outputs of a code section.
+const MAX_IO: u8 = 127;
+
+/// Error of EOF container parsing or validation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EofError {
+	/// The code does not start with the EOF magic.
+	InvalidMagic,
+	/// The container version is not supported.
+	UnknownVersion(u8),
+	/// The header is truncated, or its sections are missing or out of order.
+	InvalidHeader,
+	/// A section has an invalid size.
+	InvalidSectionSize,
+	/// The body does not match the sizes declared in the header.
+	InvalidContainerSize,
+	/// A type section entry is invalid.
+	InvalidTypeSection,
+	/// An opcode is undefined or not allowed in EOF code.
+	UndefinedInstruction(Opcode),
+	/// The immediate data of the last instruction is truncated.
+	TruncatedImmediate,
+	/// A relative jump targets outside of its section or into immediate data.
+	InvalidJumpDestination,
+	/// `CALLF` refers to a code section that does not exist.
+	InvalidCodeSectionIndex,
+	/// Execution can fall off the end of a code section.
+	MissingTerminatingInstruction,
+	/// An instruction can be reached with too few stack items.
+	StackUnderflow,
+	/// A `CALLF` can be reached with a stack height at which its callee
+	/// overflows the stack.
+	StackOverflow,
+	/// An instruction can be reached with different stack heights, or `RETF`
+	/// is reached with a stack height other than the section outputs.
+	StackHeightMismatch,
+	/// The maximum stack height does not match the type section.
+	InvalidMaxStackHeight,
+	/// An instruction can not be reached.
+	UnreachableCode,
+}
+
+/// Type of a code section: its stack inputs and outputs, and the maximum
+/// stack height reached while it runs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "with-codec", derive(codec::Encode, codec::Decode))]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(borsh::BorshSerialize, borsh::BorshDeserialize)]
+pub struct TypeSection {
+	/// Number of stack items the section takes.
+	pub inputs: u8,
+	/// Number of stack items the section returns.
+	pub outputs: u8,
+	/// Maximum stack height, including the inputs.
+	pub max_stack_height: u16,
+}
+
+/// EOF v1 container: a header followed by type, code and data sections
+/// (EIP-3540).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EofContainer {
+	/// Type of each code section.
+	pub types: Vec<TypeSection>,
+	/// Code sections. The first one is the entry point.
+	pub code: Vec<Vec<u8>>,
+	/// Data section.
+	pub data: Vec<u8>,
+}
+
+/// Execution state of a machine running an EOF container.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "with-codec", derive(codec::Encode, codec::Decode))]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(borsh::BorshSerialize, borsh::BorshDeserialize)]
+pub struct EofState {
+	/// Type of each code section.
+	pub types: Vec<TypeSection>,
+	/// Offset of each code section in the container.
+	pub offsets: Vec<usize>,
+	/// Return positions of the running `CALLF`s.
+	pub return_stack: Vec<usize>,
+}
+
+/// Cursor reading the big-endian fields of a container.
+struct Reader<'a> {
+	bytes: &'a [u8],
+	position: usize,
+}
+
+impl<'a> Reader<'a> {
+	fn u8(&mut self) -> Result<u8, EofError> {
+		let value = *self.bytes.get(self.position).ok_or(EofError::InvalidHeader)?;
+		self.position += 1;
+		Ok(value)
+	}
+
+	fn u16(&mut self) -> Result<u16, EofError> {
+		Ok(u16::from(self.u8()?) << 8 | u16::from(self.u8()?))
+	}
+
+	fn expect(&mut self, kind: u8) -> Result<(), EofError> {
+		if self.u8()? == kind {
+			Ok(())
+		} else {
+			Err(EofError::InvalidHeader)
+		}
+	}
+
+	fn bytes(&mut self, len: usize) -> Result<&'a [u8], EofError> {
+		let bytes = self.bytes.get(self.position..self.position + len)
+			.ok_or(EofError::InvalidContainerSize)?;
+		self.position += len;
+		Ok(bytes)
+	}
+}
+
+impl EofContainer {
+	/// Whether `code` starts with the EOF magic, and must therefore be
+	/// executed as an EOF container rather than as legacy code.
+	#[must_use]
+	pub fn is_eof(code: &[u8]) -> bool {
+		code.starts_with(&EOF_MAGIC)
+	}
+
+	/// Parse the header and sections of a container. The code sections are
+	/// not validated; see `validate`.
+	pub fn parse(code: &[u8]) -> Result<Self, EofError> {
+		if !Self::is_eof(code) {
+			return Err(EofError::InvalidMagic)
+		}
+
+		let mut reader = Reader { bytes: code, position: EOF_MAGIC.len() };
+		let version = reader.u8()?;
+		if version != EOF_VERSION {
+			return Err(EofError::UnknownVersion(version))
+		}
+
+		reader.expect(KIND_TYPE)?;
+		let type_size = usize::from(reader.u16()?);
+
+		reader.expect(KIND_CODE)?;
+		let num_code_sections = usize::from(reader.u16()?);
+		if num_code_sections == 0 || num_code_sections > MAX_CODE_SECTIONS ||
+			type_size != num_code_sections * 4
+		{
+			return Err(EofError::InvalidSectionSize)
+		}
+
+		let mut code_sizes = Vec::with_capacity(num_code_sections);
+		for _ in 0..num_code_sections {
+			let size = usize::from(reader.u16()?);
+			if size == 0 {
+				return Err(EofError::InvalidSectionSize)
+			}
+			code_sizes.push(size);
+		}
+
+		reader.expect(KIND_DATA)?;
+		let data_size = usize::from(reader.u16()?);
+		reader.expect(TERMINATOR)?;
+
+		let mut types = Vec::with_capacity(num_code_sections);
+		for _ in 0..num_code_sections {
+			let entry = reader.bytes(4)?;
+			types.push(TypeSection {
+				inputs: entry[0],
+				outputs: entry[1],
+				max_stack_height: u16::from(entry[2]) << 8 | u16::from(entry[3]),
+			});
+		}
+
+		let mut sections = Vec::with_capacity(num_code_sections);
+		for size in code_sizes {
+			sections.push(reader.bytes(size)?.to_vec());
+		}
+
+		let data = reader.bytes(data_size)?.to_vec();
+		if reader.position != code.len() {
+			return Err(EofError::InvalidContainerSize)
+		}
+
+		Ok(Self { types, code: sections, data })
+	}
+
+	/// Offset of code section `index` in the encoded container.
+	#[must_use]
+	pub fn code_offset(&self, index: usize) -> usize {
+		let header_len = 13 + 2 * self.code.len();
+		let types_len = 4 * self.types.len();
+
+		header_len + types_len + self.code[..index].iter().map(Vec::len).sum::<usize>()
+	}
+
+	/// Validate the type section and every code section: instructions
+	/// (EIP-3670), relative jumps (EIP-4200), functions (EIP-4750) and stack
+	/// heights (EIP-5450).
+	pub fn validate(&self) -> Result<(), EofError> {
+		if self.types.len() != self.code.len() || self.code.iter().any(Vec::is_empty) {
+			return Err(EofError::InvalidSectionSize)
+		}
+
+		let first = self.types.first().ok_or(EofError::InvalidSectionSize)?;
+		if first.inputs != 0 || first.outputs != 0 {
+			return Err(EofError::InvalidTypeSection)
+		}
+
+		for section in &self.types {
+			if section.inputs > MAX_IO || section.outputs > MAX_IO ||
+				usize::from(section.max_stack_height) > MAX_STACK_HEIGHT
+			{
+				return Err(EofError::InvalidTypeSection)
+			}
+		}
+
+		for index in 0..self.code.len() {
+			self.validate_instructions(index)?;
+			self.validate_stack(index)?;
+		}
+
+		Ok(())
+	}
+
+	/// Check that every instruction is defined, that immediates are not
+	/// truncated and that relative jumps land on an instruction.
+	fn validate_instructions(&self, index: usize) -> Result<(), EofError> {
+		let code = &self.code[index];
+		let mut is_instruction = vec![false; code.len()];
+		let mut targets = Vec::new();
+		let mut position = 0;
+		let mut last = Opcode::STOP;
+
+		while position < code.len() {
+			let opcode = Opcode(code[position]);
+			if stack_io(opcode).is_none() && opcode != Opcode::CALLF && opcode != Opcode::RETF {
+				return Err(EofError::UndefinedInstruction(opcode))
+			}
+			is_instruction[position] = true;
+
+			let immediate_len = immediate_len(code, position).ok_or(EofError::TruncatedImmediate)?;
+			let next = position + 1 + immediate_len;
+			if next > code.len() {
+				return Err(EofError::TruncatedImmediate)
+			}
+
+			if opcode == Opcode::CALLF && usize::from(read_u16(code, position + 1)) >= self.code.len() {
+				return Err(EofError::InvalidCodeSectionIndex)
+			}
+			for target in jump_targets(code, position, next) {
+				targets.push(target.ok_or(EofError::InvalidJumpDestination)?);
+			}
+
+			last = opcode;
+			position = next;
+		}
+
+		if !is_terminating(last) && last != Opcode::RJUMP {
+			return Err(EofError::MissingTerminatingInstruction)
+		}
+
+		for target in targets {
+			if !is_instruction.get(target).copied().unwrap_or(false) {
+				return Err(EofError::InvalidJumpDestination)
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Compute the stack height at every instruction, and check that it is
+	/// consistent, never underflows and matches the type section.
+	fn validate_stack(&self, index: usize) -> Result<(), EofError> {
+		let code = &self.code[index];
+		let section = self.types[index];
+		let mut heights: Vec<Option<usize>> = vec![None; code.len()];
+		let mut worklist = vec![0];
+		let mut max_height = usize::from(section.inputs);
+		heights[0] = Some(usize::from(section.inputs));
+
+		while let Some(position) = worklist.pop() {
+			let height = heights[position].expect("queued instructions have a height");
+			let opcode = Opcode(code[position]);
+
+			let (pops, pushes) = match opcode {
+				Opcode::CALLF => {
+					let callee = self.types[usize::from(read_u16(code, position + 1))];
+					let callee_growth = usize::from(callee.max_stack_height).saturating_sub(usize::from(callee.inputs));
+					if height + callee_growth > STACK_LIMIT {
+						return Err(EofError::StackOverflow)
+					}
+					(usize::from(callee.inputs), usize::from(callee.outputs))
+				},
+				Opcode::RETF => {
+					if height != usize::from(section.outputs) {
+						return Err(EofError::StackHeightMismatch)
+					}
+					continue
+				},
+				_ => stack_io(opcode).expect("instructions are validated"),
+			};
+
+			if height < pops {
+				return Err(EofError::StackUnderflow)
+			}
+			let next_height = height - pops + pushes;
+			max_height = max_height.max(next_height);
+
+			let next = position + 1 + immediate_len(code, position).expect("instructions are validated");
+			let mut successors = jump_targets(code, position, next)
+				.map(|target| target.expect("instructions are validated"))
+				.collect::<Vec<_>>();
+			if !is_terminating(opcode) && opcode != Opcode::RJUMP {
+				if next >= code.len() {
+					return Err(EofError::MissingTerminatingInstruction)
+				}
+				successors.push(next);
+			}
+
+			for successor in successors {
+				match heights[successor] {
+					Some(expected) if expected != next_height => {
+						return Err(EofError::StackHeightMismatch)
+					},
+					Some(_) => (),
+					None => {
+						heights[successor] = Some(next_height);
+						worklist.push(successor);
+					},
+				}
+			}
+		}
+
+		let mut position = 0;
+		while position < code.len() {
+			if heights[position].is_none() {
+				return Err(EofError::UnreachableCode)
+			}
+			position += 1 + immediate_len(code, position).expect("instructions are validated");
+		}
+
+		if max_height != usize::from(section.max_stack_height) {
+			return Err(EofError::InvalidMaxStackHeight)
+		}
+
+		Ok(())
+	}
+}
+
+/// Read a big-endian `u16` at `position`, which must be in range.
+pub fn read_u16(code: &[u8], position: usize) -> u16 {
+	u16::from(code[position]) << 8 | u16::from(code[position + 1])
+}
+
+/// Read a big-endian `i16` at `position`, which must be in range.
+#[allow(clippy::cast_possible_wrap)]
+pub fn read_i16(code: &[u8], position: usize) -> i16 {
+	read_u16(code, position) as i16
+}
+
+/// Length of the immediate data of the instruction at `position`, or
+/// `None` if `RJUMPV` is truncated before its table size.
+fn immediate_len(code: &[u8], position: usize) -> Option<usize> {
+	let opcode = Opcode(code[position]);
+	Some(match opcode {
+		Opcode::RJUMPV => 1 + 2 * (usize::from(*code.get(position + 1)?) + 1),
+		_ => opcode.info().map_or(0, |info| usize::from(info.immediate_size)),
+	})
+}
+
+/// Targets of the relative jump at `position`, whose next instruction is
+/// at `next`. A target out of range is `None`.
+fn jump_targets(code: &[u8], position: usize, next: usize) -> impl Iterator<Item = Option<usize>> + '_ {
+	let offsets: Vec<usize> = match Opcode(code[position]) {
+		Opcode::RJUMP | Opcode::RJUMPI => vec![position + 1],
+		Opcode::RJUMPV => (0..=usize::from(code[position + 1])).map(|i| position + 2 + 2 * i).collect(),
+		_ => Vec::new(),
+	};
+
+	offsets.into_iter().map(move |offset| {
+		let target = isize::try_from(next).ok()? + isize::from(read_i16(code, offset));
+		usize::try_from(target).ok().filter(|target| *target < code.len())
+	})
+}
+
+/// Whether an instruction ends the execution of its code section.
+const fn is_terminating(opcode: Opcode) -> bool {
+	matches!(opcode, Opcode::STOP | Opcode::RETURN | Opcode::REVERT | Opcode::INVALID | Opcode::RETF)
+}
+
+/// Number of stack items an instruction pops and pushes, or `None` if it is
+/// undefined or not allowed in EOF code. `CALLF` and `RETF` depend on the
+/// type section and are also `None`.
+fn stack_io(opcode: Opcode) -> Option<(usize, usize)> {
+	match opcode {
+		Opcode::JUMP | Opcode::JUMPI | Opcode::PC | Opcode::CALLCODE | Opcode::SUICIDE | Opcode::CALLF |
+			Opcode::RETF => None,
+		_ => opcode.info().map(|info| (usize::from(info.inputs), usize::from(info.outputs))),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use core::convert::TryFrom;
+	use alloc::vec::Vec;
+	use crate::{Capture, Context, EofContainer, EofError, ExitError, ExitSucceed, Machine, Opcode, H160, U256};
+
+	/// Encode a container from `(inputs, outputs, max_stack_height, code)`
+	/// sections and data.
+	pub fn encode(sections: &[(u8, u8, u16, &[u8])], data: &[u8]) -> Vec<u8> {
+		let mut out = vec![0xef, 0x00, 0x01, 0x01];
+		out.extend_from_slice(&u16::try_from(sections.len() * 4).unwrap().to_be_bytes());
+		out.push(0x02);
+		out.extend_from_slice(&u16::try_from(sections.len()).unwrap().to_be_bytes());
+		for (_, _, _, code) in sections {
+			out.extend_from_slice(&u16::try_from(code.len()).unwrap().to_be_bytes());
+		}
+		out.push(0x03);
+		out.extend_from_slice(&u16::try_from(data.len()).unwrap().to_be_bytes());
+		out.push(0x00);
+		for (inputs, outputs, max_stack_height, _) in sections {
+			out.push(*inputs);
+			out.push(*outputs);
+			out.extend_from_slice(&max_stack_height.to_be_bytes());
+		}
+		for (_, _, _, code) in sections {
+			out.extend_from_slice(code);
+		}
+		out.extend_from_slice(data);
+		out
+	}
+
+	fn validate(sections: &[(u8, u8, u16, &[u8])]) -> Result<(), EofError> {
+		EofContainer::parse(&encode(sections, &[]))?.validate()
+	}
+
+	#[test]
+	fn test_parse() {
+		let code = encode(&[(0, 0, 0, &[0x00])], &[0xaa, 0xbb]);
+		let container = EofContainer::parse(&code).unwrap();
+		assert_eq!(container.code, vec![vec![0x00]]);
+		assert_eq!(container.data, vec![0xaa, 0xbb]);
+		assert_eq!(&code[container.code_offset(0)..], &[0x00, 0xaa, 0xbb]);
+
+		assert_eq!(EofContainer::parse(&code[..code.len() - 1]), Err(EofError::InvalidContainerSize));
+		assert_eq!(EofContainer::parse(&[0xef, 0x00, 0x02]), Err(EofError::UnknownVersion(2)));
+		assert_eq!(EofContainer::parse(&[0x60, 0x00]), Err(EofError::InvalidMagic));
+	}
+
+	#[test]
+	fn test_validate() {
+		// PUSH1 1; RJUMPI +1; STOP; PUSH1 2; CALLF 1; POP; STOP
+		assert_eq!(validate(&[
+			(0, 0, 1, &[0x60, 0x01, 0xe1, 0x00, 0x01, 0x00, 0x60, 0x02, 0xe3, 0x00, 0x01, 0x50, 0x00]),
+			// DUP1; ADD; RETF
+			(1, 1, 2, &[0x80, 0x01, 0xe4]),
+		]), Ok(()));
+
+		assert_eq!(validate(&[(0, 0, 1, &[0x60, 0x00, 0x56])]), Err(EofError::UndefinedInstruction(Opcode::JUMP)));
+		assert_eq!(validate(&[(0, 0, 0, &[0x61, 0x00])]), Err(EofError::TruncatedImmediate));
+		assert_eq!(validate(&[(0, 0, 0, &[0xe0, 0x00, 0x05])]), Err(EofError::InvalidJumpDestination));
+		assert_eq!(validate(&[(0, 0, 1, &[0x60, 0x00])]), Err(EofError::MissingTerminatingInstruction));
+		assert_eq!(validate(&[(0, 0, 0, &[0x50, 0x00])]), Err(EofError::StackUnderflow));
+		assert_eq!(validate(&[(0, 0, 2, &[0x60, 0x00, 0x00])]), Err(EofError::InvalidMaxStackHeight));
+		assert_eq!(validate(&[(0, 0, 0, &[0x00, 0x00])]), Err(EofError::UnreachableCode));
+		// CALLF with two items on the stack of a callee growing it by 1023.
+		assert_eq!(validate(&[
+			(0, 0, 2, &[0x5f, 0x5f, 0xe3, 0x00, 0x01, 0x50, 0x50, 0x00]),
+			(0, 0, 1023, &[0xe4]),
+		]), Err(EofError::StackOverflow));
+		let empty = EofContainer { types: Vec::new(), code: Vec::new(), data: Vec::new() };
+		assert_eq!(empty.validate(), Err(EofError::InvalidSectionSize));
+		// RETF with one item left on the stack of a section without outputs.
+		assert_eq!(validate(&[
+			(0, 0, 0, &[0xe3, 0x00, 0x01, 0x00]),
+			(0, 0, 1, &[0x60, 0x00, 0xe4]),
+		]), Err(EofError::StackHeightMismatch));
+	}
+
+	#[test]
+	fn test_execute() {
+		let context = Context {
+			address: H160::default(),
+			caller: H160::default(),
+			apparent_value: U256::zero(),
+			is_static: false,
+		};
+		// PUSH1 3; CALLF 1; DUP1; RJUMPI +1; STOP;
+		// PUSH1 0; MSTORE; PUSH1 32; PUSH1 0; RETURN
+		let code = encode(&[
+			(0, 0, 2, &[
+				0x60, 0x03, 0xe3, 0x00, 0x01, 0x80, 0xe1, 0x00, 0x01, 0x00,
+				0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3,
+			]),
+			// DUP1; ADD; RETF
+			(1, 1, 2, &[0x80, 0x01, 0xe4]),
+		], &[]);
+
+		let mut machine = Machine::new_eof(code, Vec::new(), 1024, 10000).unwrap();
+		assert!(machine.is_eof());
+		let (_, capture) = machine.run(1000, |_, _| Ok(()), &context);
+		assert_eq!(capture, Capture::Exit(ExitSucceed::Returned.into()));
+		assert_eq!(U256::from_big_endian(&machine.return_value()), U256::from(6));
+
+		// The EOF opcodes are undefined in legacy code, even without their
+		// immediates.
+		let mut machine = Machine::new(vec![0xe0, 0x00, 0x00], Vec::new(), Vec::new(), 1024, 10000);
+		let (_, capture) = machine.run(1000, |_, _| Ok(()), &context);
+		assert_eq!(capture, Capture::Exit(ExitError::InvalidCode(Opcode::RJUMP).into()));
+		let mut machine = Machine::new(vec![0xe3], Vec::new(), Vec::new(), 1024, 10000);
+		let (_, capture) = machine.run(1000, |_, _| Ok(()), &context);
+		assert_eq!(capture, Capture::Exit(ExitError::InvalidCode(Opcode::CALLF).into()));
+	}
+}
@@ -117,6 +117,8 @@ pub enum ExitError {
 	InvalidRange,
 	/// Encountered the designated invalid opcode.
 	DesignatedInvalid,
+	/// Encountered an opcode that is not enabled by the active fork.
+	InvalidCode(Opcode),
 	/// Call stack is too deep (runtime).
 	CallTooDeep,
 	/// Create opcode encountered collision (runtime).
@@ -144,7 +146,9 @@ pub enum ExitError {
 	/// transfer is not allowed
 	TransferProhibited,
 	/// Delegate call id prohibited for ExtCall
-	DelegateCallProhibited
+	DelegateCallProhibited,
+	/// A precompiled contract rejected its input (runtime).
+	InvalidPrecompileInput,
 }
 
 impl From<ExitError> for ExitReason {
@@ -7,12 +7,19 @@ macro_rules! try_or_fail {
 	}
 }
 
+// The stack macros skip the stack height checks in functions generic over
+// `const CHECKED: bool` evaluated with `CHECKED = false`, see `eval_unchecked`.
+
 macro_rules! pop {
 	( $machine:expr, $( $x:ident ),* ) => (
 		$(
-			let $x = match $machine.stack.pop() {
-				Ok(value) => value,
-				Err(e) => return Control::Exit(e.into()),
+			let $x = if CHECKED {
+				match $machine.stack.pop() {
+					Ok(value) => value,
+					Err(e) => return Control::Exit(e.into()),
+				}
+			} else {
+				$machine.stack.pop_unchecked()
 			};
 		)*
 	);
@@ -21,9 +28,13 @@ macro_rules! pop {
 macro_rules! pop_u256 {
 	( $machine:expr, $( $x:ident ),* ) => (
 		$(
-			let $x = match $machine.stack.pop_u256() {
-				Ok(value) => value,
-				Err(e) => return Control::Exit(e.into()),
+			let $x = if CHECKED {
+				match $machine.stack.pop_u256() {
+					Ok(value) => value,
+					Err(e) => return Control::Exit(e.into()),
+				}
+			} else {
+				$machine.stack.pop_u256_unchecked()
 			};
 		)*
 	);
@@ -33,9 +44,10 @@ macro_rules! pop_u256 {
 macro_rules! push {
 	( $machine:expr, $( $x:expr ),* ) => (
 		$(
-			match $machine.stack.push($x) {
-				Ok(()) => (),
-				Err(e) => return Control::Exit(e.into()),
+			if !CHECKED {
+				$machine.stack.push_unchecked($x)
+			} else if let Err(e) = $machine.stack.push($x) {
+				return Control::Exit(e.into())
 			}
 		)*
 	)
@@ -45,9 +57,10 @@ macro_rules! push {
 macro_rules! push_u256 {
 	( $machine:expr, $( $x:expr ),* ) => (
 		$(
-			match $machine.stack.push_u256($x) {
-				Ok(()) => (),
-				Err(e) => return Control::Exit(e.into()),
+			if !CHECKED {
+				$machine.stack.push_u256_unchecked($x)
+			} else if let Err(e) = $machine.stack.push_u256($x) {
+				return Control::Exit(e.into())
 			}
 		)*
 	)
@@ -1,16 +1,20 @@
 use core::cmp::{min, max};
 use super::Control;
-use crate::{Machine, ExitError, ExitSucceed, ExitFatal, ExitRevert, H256, U256};
+use crate::eof::{read_i16, read_u16};
+use crate::{Machine, ExitError, ExitSucceed, ExitFatal, ExitRevert, Opcode, H256, U256};
+
+/// Maximum depth of the `CALLF` return stack (EIP-4750).
+const RETURN_STACK_LIMIT: usize = 1024;
 
 /// Get size of code running in current environment
-pub fn codesize(state: &mut Machine) -> Control {
+pub fn codesize<const CHECKED: bool>(state: &mut Machine) -> Control {
 	let size = U256::from(state.code.len());
 	push_u256!(state, size);
 	Control::Continue(1)
 }
 
 /// Copy code running in current environment to memory
-pub fn codecopy(state: &mut Machine) -> Control {
+pub fn codecopy<const CHECKED: bool>(state: &mut Machine) -> Control {
 	pop_u256!(state, memory_offset, code_offset, len);
 
 	let memory_offset = as_usize_or_fail!(memory_offset);
@@ -25,7 +29,7 @@ pub fn codecopy(state: &mut Machine) -> Control {
 }
 
 /// Get input data of current environment
-pub fn calldataload(state: &mut Machine) -> Control {
+pub fn calldataload<const CHECKED: bool>(state: &mut Machine) -> Control {
 	pop_u256!(state, index);
 
 	let index = as_usize_or_fail!(index);
@@ -41,14 +45,14 @@ pub fn calldataload(state: &mut Machine) -> Control {
 }
 
 /// Get size of input data in current environment
-pub fn calldatasize(state: &mut Machine) -> Control {
+pub fn calldatasize<const CHECKED: bool>(state: &mut Machine) -> Control {
 	let len = U256::from(state.data.len());
 	push_u256!(state, len);
 	Control::Continue(1)
 }
 
 /// Copy input data in current environment to memory
-pub fn calldatacopy(state: &mut Machine) -> Control {
+pub fn calldatacopy<const CHECKED: bool>(state: &mut Machine) -> Control {
 	pop_u256!(state, memory_offset, data_offset, len);
 
 	let memory_offset = as_usize_or_fail!(memory_offset);
@@ -67,13 +71,13 @@ pub fn calldatacopy(state: &mut Machine) -> Control {
 }
 
 /// Remove item from stack
-pub fn pop(state: &mut Machine) -> Control {
+pub fn pop<const CHECKED: bool>(state: &mut Machine) -> Control {
 	pop_u256!(state, _val);
 	Control::Continue(1)
 }
 
 /// Load word from memory
-pub fn mload(state: &mut Machine) -> Control {
+pub fn mload<const CHECKED: bool>(state: &mut Machine) -> Control {
 	pop_u256!(state, index);
 	let index = as_usize_or_fail!(index);
 	try_or_fail!(state.memory.resize_offset(index, 32));
@@ -83,7 +87,7 @@ pub fn mload(state: &mut Machine) -> Control {
 }
 
 /// Save word to memory
-pub fn mstore(state: &mut Machine) -> Control {
+pub fn mstore<const CHECKED: bool>(state: &mut Machine) -> Control {
 	pop_u256!(state, index);
 	pop!(state, value);
 	let index = as_usize_or_fail!(index);
@@ -95,7 +99,7 @@ pub fn mstore(state: &mut Machine) -> Control {
 }
 
 /// Copy memory areas
-pub fn mcopy(state: &mut Machine) -> Control {
+pub fn mcopy<const CHECKED: bool>(state: &mut Machine) -> Control {
 	pop_u256!(state, dst_offset, src_offset, size);
 
 	let dst_offset = as_usize_or_fail!(dst_offset);
@@ -112,7 +116,7 @@ pub fn mcopy(state: &mut Machine) -> Control {
 }
 
 /// Save byte to memory
-pub fn mstore8(state: &mut Machine) -> Control {
+pub fn mstore8<const CHECKED: bool>(state: &mut Machine) -> Control {
 	pop_u256!(state, index, value);
 	let index = as_usize_or_fail!(index);
 	try_or_fail!(state.memory.resize_offset(index, 1));
@@ -125,7 +129,7 @@ pub fn mstore8(state: &mut Machine) -> Control {
 }
 
 /// Alter the program counter
-pub fn jump(state: &mut Machine) -> Control {
+pub fn jump<const CHECKED: bool>(state: &mut Machine) -> Control {
 	pop_u256!(state, dest);
 	let dest = as_usize_or_fail!(dest, ExitError::InvalidJump);
 
@@ -137,7 +141,7 @@ pub fn jump(state: &mut Machine) -> Control {
 }
 
 /// Conditionally alter the program counter
-pub fn jumpi(state: &mut Machine) -> Control {
+pub fn jumpi<const CHECKED: bool>(state: &mut Machine) -> Control {
 	pop_u256!(state, dest, value);
 	let dest = as_usize_or_fail!(dest, ExitError::InvalidJump);
 
@@ -153,19 +157,19 @@ pub fn jumpi(state: &mut Machine) -> Control {
 }
 
 /// Get the value of the program counter prior to the increment corresponding to this instruction
-pub fn pc(state: &mut Machine, position: usize) -> Control {
+pub fn pc<const CHECKED: bool>(state: &mut Machine, position: usize) -> Control {
 	push_u256!(state, U256::from(position));
 	Control::Continue(1)
 }
 
 /// Get the size of active memory in bytes
-pub fn msize(state: &mut Machine) -> Control {
+pub fn msize<const CHECKED: bool>(state: &mut Machine) -> Control {
 	push_u256!(state, U256::from(state.memory.effective_len()));
 	Control::Continue(1)
 }
 
 /// Place byte item on a stack
-pub fn push(state: &mut Machine, n: usize, position: usize) -> Control {
+pub fn push<const CHECKED: bool>(state: &mut Machine, n: usize, position: usize) -> Control {
 	let end = min(position + 1 + n, state.code.len());
 	let val = U256::from_big_endian_fast(&state.code[(position + 1)..end]);
 
@@ -174,14 +178,16 @@ pub fn push(state: &mut Machine, n: usize, position: usize) -> Control {
 }
 
 /// Place value 0 on stack
-pub fn push0(state: &mut Machine) -> Control {
+pub fn push0<const CHECKED: bool>(state: &mut Machine) -> Control {
 	push_u256!(state, U256::zero());
 	Control::Continue(1)
 }
 
 /// Duplicate stack item
-pub fn dup(state: &mut Machine, n: usize) -> Control {
-	if let Err(e) = state.stack.dup(n - 1) {
+pub fn dup<const CHECKED: bool>(state: &mut Machine, n: usize) -> Control {
+	if !CHECKED {
+		state.stack.dup_unchecked(n - 1);
+	} else if let Err(e) = state.stack.dup(n - 1) {
 		return Control::Exit(e.into());
 	};
 
@@ -189,8 +195,10 @@ pub fn dup(state: &mut Machine, n: usize) -> Control {
 }
 
 // Exchange stack items
-pub fn swap(state: &mut Machine, n: usize) -> Control {
-	if let Err(e) = state.stack.swap(n) {
+pub fn swap<const CHECKED: bool>(state: &mut Machine, n: usize) -> Control {
+	if !CHECKED {
+		state.stack.swap_unchecked(n);
+	} else if let Err(e) = state.stack.swap(n) {
 		return Control::Exit(e.into());
 	};
 
@@ -198,7 +206,7 @@ pub fn swap(state: &mut Machine, n: usize) -> Control {
 }
 
 /// Halt execution returning output data
-pub fn ret(state: &mut Machine) -> Control {
+pub fn ret<const CHECKED: bool>(state: &mut Machine) -> Control {
 	pop_u256!(state, start, len);
 	let start = as_usize_or_fail!(start);
 	let len = as_usize_or_fail!(len);
@@ -208,7 +216,7 @@ pub fn ret(state: &mut Machine) -> Control {
 }
 
 /// Halt execution reverting state changes but returning data and remaining gas
-pub fn revert(state: &mut Machine) -> Control {
+pub fn revert<const CHECKED: bool>(state: &mut Machine) -> Control {
 	pop_u256!(state, start, len);
 	let start = as_usize_or_fail!(start);
 	let len = as_usize_or_fail!(len);
@@ -216,3 +224,83 @@ pub fn revert(state: &mut Machine) -> Control {
 	state.return_range = (start, len);
 	Control::Exit(ExitRevert::Reverted.into())
 }
+
+/// Offset of a relative jump target from the instruction after it.
+fn relative(next: usize, offset: i16) -> usize {
+	next.wrapping_add_signed(isize::from(offset))
+}
+
+/// Jump by a relative offset (EIP-4200)
+pub fn rjump(state: &Machine, opcode: Opcode, position: usize) -> Control {
+	if state.eof.is_none() {
+		return Control::Exit(ExitError::InvalidCode(opcode).into())
+	}
+
+	Control::Jump(relative(position + 3, read_i16(&state.code, position + 1)))
+}
+
+/// Conditionally jump by a relative offset (EIP-4200)
+pub fn rjumpi<const CHECKED: bool>(state: &mut Machine, opcode: Opcode, position: usize) -> Control {
+	if state.eof.is_none() {
+		return Control::Exit(ExitError::InvalidCode(opcode).into())
+	}
+
+	pop_u256!(state, value);
+	if value == U256::zero() {
+		Control::Continue(3)
+	} else {
+		Control::Jump(relative(position + 3, read_i16(&state.code, position + 1)))
+	}
+}
+
+/// Jump by the relative offset at the popped index of a jump table, or
+/// continue if it is out of range (EIP-4200)
+pub fn rjumpv<const CHECKED: bool>(state: &mut Machine, opcode: Opcode, position: usize) -> Control {
+	if state.eof.is_none() {
+		return Control::Exit(ExitError::InvalidCode(opcode).into())
+	}
+
+	pop_u256!(state, case);
+	let max_index = usize::from(state.code[position + 1]);
+	let len = 2 + 2 * (max_index + 1);
+
+	if case > U256::from(max_index) {
+		Control::Continue(len)
+	} else {
+		let offset = read_i16(&state.code, position + 2 + 2 * case.as_usize());
+		Control::Jump(relative(position + len, offset))
+	}
+}
+
+/// Call a code section (EIP-4750)
+pub fn callf(state: &mut Machine, opcode: Opcode, position: usize) -> Control {
+	if state.eof.is_none() {
+		return Control::Exit(ExitError::InvalidCode(opcode).into())
+	}
+
+	let index = usize::from(read_u16(&state.code, position + 1));
+	let stack_len = state.stack.len();
+	let stack_limit = state.stack.limit();
+	let Some(eof) = state.eof.as_mut() else {
+		return Control::Exit(ExitError::InvalidCode(opcode).into())
+	};
+
+	let section = eof.types[index];
+	let height = stack_len - usize::from(section.inputs) + usize::from(section.max_stack_height);
+	if height > stack_limit || eof.return_stack.len() >= RETURN_STACK_LIMIT {
+		return Control::Exit(ExitError::StackOverflow.into())
+	}
+
+	eof.return_stack.push(position + 3);
+	Control::Jump(eof.offsets[index])
+}
+
+/// Return from a code section (EIP-4750). Returning from the first section
+/// stops the execution.
+pub fn retf(state: &mut Machine, opcode: Opcode) -> Control {
+	let Some(eof) = state.eof.as_mut() else {
+		return Control::Exit(ExitError::InvalidCode(opcode).into())
+	};
+
+	eof.return_stack.pop().map_or_else(|| Control::Exit(ExitSucceed::Stopped.into()), Control::Jump)
+}
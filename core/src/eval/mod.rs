@@ -21,432 +21,452 @@ fn eval_stop(_state: &mut Machine, _opcode: Opcode, _position: usize) -> Control
 	Control::Exit(ExitSucceed::Stopped.into())
 }
 
-fn eval_add(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_add<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_tuple!(state, overflowing_add)
 }
 
-fn eval_mul(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_mul<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_tuple!(state, overflowing_mul)
 }
 
-fn eval_sub(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_sub<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_tuple!(state, overflowing_sub)
 }
 
-fn eval_div(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_div<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_fn!(state, self::arithmetic::div)
 }
 
-fn eval_sdiv(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_sdiv<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_fn!(state, self::arithmetic::sdiv)
 }
 
-fn eval_mod(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_mod<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_fn!(state, self::arithmetic::rem)
 }
 
-fn eval_smod(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_smod<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_fn!(state, self::arithmetic::srem)
 }
 
-fn eval_addmod(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_addmod<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op3_u256_fn!(state, self::arithmetic::addmod)
 }
 
-fn eval_mulmod(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_mulmod<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op3_u256_fn!(state, self::arithmetic::mulmod)
 }
 
-fn eval_exp(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_exp<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_fn!(state, self::arithmetic::exp)
 }
 
-fn eval_signextend(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_signextend<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_fn!(state, self::arithmetic::signextend)
 }
 
-fn eval_lt(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_lt<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_bool_ref!(state, lt)
 }
 
-fn eval_gt(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_gt<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_bool_ref!(state, gt)
 }
 
-fn eval_slt(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_slt<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_fn!(state, self::bitwise::slt)
 }
 
-fn eval_sgt(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_sgt<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_fn!(state, self::bitwise::sgt)
 }
 
-fn eval_eq(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_eq<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_bool_ref!(state, eq)
 }
 
-fn eval_iszero(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_iszero<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op1_u256_fn!(state, self::bitwise::iszero)
 }
 
-fn eval_and(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_and<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256!(state, bitand)
 }
 
-fn eval_or(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_or<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256!(state, bitor)
 }
 
-fn eval_xor(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_xor<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256!(state, bitxor)
 }
 
-fn eval_not(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_not<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op1_u256_fn!(state, self::bitwise::not)
 }
 
-fn eval_byte(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_byte<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_fn!(state, self::bitwise::byte)
 }
 
-fn eval_shl(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_shl<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_fn!(state, self::bitwise::shl)
 }
 
-fn eval_shr(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_shr<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_fn!(state, self::bitwise::shr)
 }
 
-fn eval_sar(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+fn eval_sar<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	op2_u256_fn!(state, self::bitwise::sar)
 }
 
-fn eval_codesize(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::codesize(state)
+fn eval_codesize<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::codesize::<CHECKED>(state)
 }
 
-fn eval_codecopy(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::codecopy(state)
+fn eval_codecopy<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::codecopy::<CHECKED>(state)
 }
 
-fn eval_calldataload(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::calldataload(state)
+fn eval_calldataload<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::calldataload::<CHECKED>(state)
 }
 
-fn eval_calldatasize(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::calldatasize(state)
+fn eval_calldatasize<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::calldatasize::<CHECKED>(state)
 }
 
-fn eval_calldatacopy(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::calldatacopy(state)
+fn eval_calldatacopy<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::calldatacopy::<CHECKED>(state)
 }
 
-fn eval_pop(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::pop(state)
+fn eval_pop<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::pop::<CHECKED>(state)
 }
 
-fn eval_mload(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::mload(state)
+fn eval_mload<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::mload::<CHECKED>(state)
 }
 
-fn eval_mstore(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::mstore(state)
+fn eval_mstore<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::mstore::<CHECKED>(state)
 }
 
-fn eval_mstore8(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::mstore8(state)
+fn eval_mstore8<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::mstore8::<CHECKED>(state)
 }
 
-fn eval_jump(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::jump(state)
+fn eval_jump<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::jump::<CHECKED>(state)
 }
 
-fn eval_jumpi(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::jumpi(state)
+fn eval_jumpi<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::jumpi::<CHECKED>(state)
 }
 
-fn eval_pc(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::pc(state, position)
+fn eval_pc<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::pc::<CHECKED>(state, position)
 }
 
-fn eval_msize(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::msize(state)
+fn eval_rjump(state: &mut Machine, opcode: Opcode, position: usize) -> Control {
+	self::misc::rjump(state, opcode, position)
+}
+
+fn eval_rjumpi<const CHECKED: bool>(state: &mut Machine, opcode: Opcode, position: usize) -> Control {
+	self::misc::rjumpi::<CHECKED>(state, opcode, position)
+}
+
+fn eval_rjumpv<const CHECKED: bool>(state: &mut Machine, opcode: Opcode, position: usize) -> Control {
+	self::misc::rjumpv::<CHECKED>(state, opcode, position)
+}
+
+fn eval_callf(state: &mut Machine, opcode: Opcode, position: usize) -> Control {
+	self::misc::callf(state, opcode, position)
+}
+
+fn eval_retf(state: &mut Machine, opcode: Opcode, _position: usize) -> Control {
+	self::misc::retf(state, opcode)
+}
+
+fn eval_msize<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::msize::<CHECKED>(state)
 }
 
 fn eval_jumpdest(_state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
 	Control::Continue(1)
 }
 
-fn eval_mcopy(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::mcopy(state)
+fn eval_mcopy<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::mcopy::<CHECKED>(state)
 }
 
-fn eval_push0(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::push0(state)
+fn eval_push0<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::push0::<CHECKED>(state)
 }
 
-fn eval_push1(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 1, position)
+fn eval_push1<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 1, position)
 }
 
-fn eval_push2(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 2, position)
+fn eval_push2<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 2, position)
 }
 
-fn eval_push3(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 3, position)
+fn eval_push3<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 3, position)
 }
 
-fn eval_push4(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 4, position)
+fn eval_push4<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 4, position)
 }
 
-fn eval_push5(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 5, position)
+fn eval_push5<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 5, position)
 }
 
-fn eval_push6(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 6, position)
+fn eval_push6<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 6, position)
 }
 
-fn eval_push7(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 7, position)
+fn eval_push7<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 7, position)
 }
 
-fn eval_push8(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 8, position)
+fn eval_push8<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 8, position)
 }
 
-fn eval_push9(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 9, position)
+fn eval_push9<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 9, position)
 }
 
-fn eval_push10(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 10, position)
+fn eval_push10<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 10, position)
 }
 
-fn eval_push11(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 11, position)
+fn eval_push11<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 11, position)
 }
 
-fn eval_push12(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 12, position)
+fn eval_push12<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 12, position)
 }
 
-fn eval_push13(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 13, position)
+fn eval_push13<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 13, position)
 }
 
-fn eval_push14(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 14, position)
+fn eval_push14<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 14, position)
 }
 
-fn eval_push15(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 15, position)
+fn eval_push15<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 15, position)
 }
 
-fn eval_push16(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 16, position)
+fn eval_push16<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 16, position)
 }
 
-fn eval_push17(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 17, position)
+fn eval_push17<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 17, position)
 }
 
-fn eval_push18(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 18, position)
+fn eval_push18<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 18, position)
 }
 
-fn eval_push19(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 19, position)
+fn eval_push19<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 19, position)
 }
 
-fn eval_push20(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 20, position)
+fn eval_push20<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 20, position)
 }
 
-fn eval_push21(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 21, position)
+fn eval_push21<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 21, position)
 }
 
-fn eval_push22(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 22, position)
+fn eval_push22<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 22, position)
 }
 
-fn eval_push23(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 23, position)
+fn eval_push23<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 23, position)
 }
 
-fn eval_push24(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 24, position)
+fn eval_push24<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 24, position)
 }
 
-fn eval_push25(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 25, position)
+fn eval_push25<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 25, position)
 }
 
-fn eval_push26(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 26, position)
+fn eval_push26<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 26, position)
 }
 
-fn eval_push27(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 27, position)
+fn eval_push27<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 27, position)
 }
 
-fn eval_push28(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 28, position)
+fn eval_push28<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 28, position)
 }
 
-fn eval_push29(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 29, position)
+fn eval_push29<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 29, position)
 }
 
-fn eval_push30(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 30, position)
+fn eval_push30<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 30, position)
 }
 
-fn eval_push31(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 31, position)
+fn eval_push31<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 31, position)
 }
 
-fn eval_push32(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
-	self::misc::push(state, 32, position)
+fn eval_push32<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, position: usize) -> Control {
+	self::misc::push::<CHECKED>(state, 32, position)
 }
 
-fn eval_dup1(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::dup(state, 1)
+fn eval_dup1<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::dup::<CHECKED>(state, 1)
 }
 
-fn eval_dup2(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::dup(state, 2)
+fn eval_dup2<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::dup::<CHECKED>(state, 2)
 }
 
-fn eval_dup3(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::dup(state, 3)
+fn eval_dup3<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::dup::<CHECKED>(state, 3)
 }
 
-fn eval_dup4(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::dup(state, 4)
+fn eval_dup4<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::dup::<CHECKED>(state, 4)
 }
 
-fn eval_dup5(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::dup(state, 5)
+fn eval_dup5<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::dup::<CHECKED>(state, 5)
 }
 
-fn eval_dup6(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::dup(state, 6)
+fn eval_dup6<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::dup::<CHECKED>(state, 6)
 }
 
-fn eval_dup7(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::dup(state, 7)
+fn eval_dup7<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::dup::<CHECKED>(state, 7)
 }
 
-fn eval_dup8(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::dup(state, 8)
+fn eval_dup8<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::dup::<CHECKED>(state, 8)
 }
 
-fn eval_dup9(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::dup(state, 9)
+fn eval_dup9<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::dup::<CHECKED>(state, 9)
 }
 
-fn eval_dup10(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::dup(state, 10)
+fn eval_dup10<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::dup::<CHECKED>(state, 10)
 }
 
-fn eval_dup11(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::dup(state, 11)
+fn eval_dup11<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::dup::<CHECKED>(state, 11)
 }
 
-fn eval_dup12(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::dup(state, 12)
+fn eval_dup12<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::dup::<CHECKED>(state, 12)
 }
 
-fn eval_dup13(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::dup(state, 13)
+fn eval_dup13<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::dup::<CHECKED>(state, 13)
 }
 
-fn eval_dup14(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::dup(state, 14)
+fn eval_dup14<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::dup::<CHECKED>(state, 14)
 }
 
-fn eval_dup15(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::dup(state, 15)
+fn eval_dup15<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::dup::<CHECKED>(state, 15)
 }
 
-fn eval_dup16(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::dup(state, 16)
+fn eval_dup16<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::dup::<CHECKED>(state, 16)
 }
 
-fn eval_swap1(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::swap(state, 1)
+fn eval_swap1<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::swap::<CHECKED>(state, 1)
 }
 
-fn eval_swap2(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::swap(state, 2)
+fn eval_swap2<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::swap::<CHECKED>(state, 2)
 }
 
-fn eval_swap3(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::swap(state, 3)
+fn eval_swap3<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::swap::<CHECKED>(state, 3)
 }
 
-fn eval_swap4(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::swap(state, 4)
+fn eval_swap4<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::swap::<CHECKED>(state, 4)
 }
 
-fn eval_swap5(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::swap(state, 5)
+fn eval_swap5<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::swap::<CHECKED>(state, 5)
 }
 
-fn eval_swap6(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::swap(state, 6)
+fn eval_swap6<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::swap::<CHECKED>(state, 6)
 }
 
-fn eval_swap7(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::swap(state, 7)
+fn eval_swap7<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::swap::<CHECKED>(state, 7)
 }
 
-fn eval_swap8(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::swap(state, 8)
+fn eval_swap8<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::swap::<CHECKED>(state, 8)
 }
 
-fn eval_swap9(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::swap(state, 9)
+fn eval_swap9<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::swap::<CHECKED>(state, 9)
 }
 
-fn eval_swap10(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::swap(state, 10)
+fn eval_swap10<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::swap::<CHECKED>(state, 10)
 }
 
-fn eval_swap11(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::swap(state, 11)
+fn eval_swap11<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::swap::<CHECKED>(state, 11)
 }
 
-fn eval_swap12(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::swap(state, 12)
+fn eval_swap12<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::swap::<CHECKED>(state, 12)
 }
 
-fn eval_swap13(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::swap(state, 13)
+fn eval_swap13<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::swap::<CHECKED>(state, 13)
 }
 
-fn eval_swap14(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::swap(state, 14)
+fn eval_swap14<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::swap::<CHECKED>(state, 14)
 }
 
-fn eval_swap15(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::swap(state, 15)
+fn eval_swap15<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::swap::<CHECKED>(state, 15)
 }
 
-fn eval_swap16(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::swap(state, 16)
+fn eval_swap16<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::swap::<CHECKED>(state, 16)
 }
 
-fn eval_return(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::ret(state)
+fn eval_return<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::ret::<CHECKED>(state)
 }
 
-fn eval_revert(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
-	self::misc::revert(state)
+fn eval_revert<const CHECKED: bool>(state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
+	self::misc::revert::<CHECKED>(state)
 }
 
 fn eval_invalid(_state: &mut Machine, _opcode: Opcode, _position: usize) -> Control {
@@ -458,127 +478,147 @@ fn eval_external(_state: &mut Machine, opcode: Opcode, _position: usize) -> Cont
 }
 
 /// process non-external opcodes
-#[allow(clippy::too_many_lines)]
 pub fn eval(state: &mut Machine, opcode: Opcode, position: usize) -> Control {
-	static TABLE: [fn(state: &mut Machine, opcode: Opcode, position: usize) -> Control; 256] = {
-		let mut table = [eval_external as _; 256];
-
-		table[Opcode::STOP.as_usize()] = eval_stop as _;
-		table[Opcode::ADD.as_usize()] = eval_add as _;
-		table[Opcode::MUL.as_usize()] = eval_mul as _;
-		table[Opcode::SUB.as_usize()] = eval_sub as _;
-		table[Opcode::DIV.as_usize()] = eval_div as _;
-		table[Opcode::SDIV.as_usize()] = eval_sdiv as _;
-		table[Opcode::MOD.as_usize()] = eval_mod as _;
-		table[Opcode::SMOD.as_usize()] = eval_smod as _;
-		table[Opcode::ADDMOD.as_usize()] = eval_addmod as _;
-		table[Opcode::MULMOD.as_usize()] = eval_mulmod as _;
-		table[Opcode::EXP.as_usize()] = eval_exp as _;
-		table[Opcode::SIGNEXTEND.as_usize()] = eval_signextend as _;
-		table[Opcode::LT.as_usize()] = eval_lt as _;
-		table[Opcode::GT.as_usize()] = eval_gt as _;
-		table[Opcode::SLT.as_usize()] = eval_slt as _;
-		table[Opcode::SGT.as_usize()] = eval_sgt as _;
-		table[Opcode::EQ.as_usize()] = eval_eq as _;
-		table[Opcode::ISZERO.as_usize()] = eval_iszero as _;
-		table[Opcode::AND.as_usize()] = eval_and as _;
-		table[Opcode::OR.as_usize()] = eval_or as _;
-		table[Opcode::XOR.as_usize()] = eval_xor as _;
-		table[Opcode::NOT.as_usize()] = eval_not as _;
-		table[Opcode::BYTE.as_usize()] = eval_byte as _;
-		table[Opcode::SHL.as_usize()] = eval_shl as _;
-		table[Opcode::SHR.as_usize()] = eval_shr as _;
-		table[Opcode::SAR.as_usize()] = eval_sar as _;
-		table[Opcode::CODESIZE.as_usize()] = eval_codesize as _;
-		table[Opcode::CODECOPY.as_usize()] = eval_codecopy as _;
-		table[Opcode::CALLDATALOAD.as_usize()] = eval_calldataload as _;
-		table[Opcode::CALLDATASIZE.as_usize()] = eval_calldatasize as _;
-		table[Opcode::CALLDATACOPY.as_usize()] = eval_calldatacopy as _;
-		table[Opcode::POP.as_usize()] = eval_pop as _;
-		table[Opcode::MLOAD.as_usize()] = eval_mload as _;
-		table[Opcode::MSTORE.as_usize()] = eval_mstore as _;
-		table[Opcode::MSTORE8.as_usize()] = eval_mstore8 as _;
-		table[Opcode::JUMP.as_usize()] = eval_jump as _;
-		table[Opcode::JUMPI.as_usize()] = eval_jumpi as _;
-		table[Opcode::PC.as_usize()] = eval_pc as _;
-		table[Opcode::MSIZE.as_usize()] = eval_msize as _;
-		table[Opcode::JUMPDEST.as_usize()] = eval_jumpdest as _;
-		table[Opcode::MCOPY.as_usize()] = eval_mcopy as _;
-
-		table[Opcode::PUSH0.as_usize()] = eval_push0 as _;
-		table[Opcode::PUSH1.as_usize()] = eval_push1 as _;
-		table[Opcode::PUSH2.as_usize()] = eval_push2 as _;
-		table[Opcode::PUSH3.as_usize()] = eval_push3 as _;
-		table[Opcode::PUSH4.as_usize()] = eval_push4 as _;
-		table[Opcode::PUSH5.as_usize()] = eval_push5 as _;
-		table[Opcode::PUSH6.as_usize()] = eval_push6 as _;
-		table[Opcode::PUSH7.as_usize()] = eval_push7 as _;
-		table[Opcode::PUSH8.as_usize()] = eval_push8 as _;
-		table[Opcode::PUSH9.as_usize()] = eval_push9 as _;
-		table[Opcode::PUSH10.as_usize()] = eval_push10 as _;
-		table[Opcode::PUSH11.as_usize()] = eval_push11 as _;
-		table[Opcode::PUSH12.as_usize()] = eval_push12 as _;
-		table[Opcode::PUSH13.as_usize()] = eval_push13 as _;
-		table[Opcode::PUSH14.as_usize()] = eval_push14 as _;
-		table[Opcode::PUSH15.as_usize()] = eval_push15 as _;
-		table[Opcode::PUSH16.as_usize()] = eval_push16 as _;
-		table[Opcode::PUSH17.as_usize()] = eval_push17 as _;
-		table[Opcode::PUSH18.as_usize()] = eval_push18 as _;
-		table[Opcode::PUSH19.as_usize()] = eval_push19 as _;
-		table[Opcode::PUSH20.as_usize()] = eval_push20 as _;
-		table[Opcode::PUSH21.as_usize()] = eval_push21 as _;
-		table[Opcode::PUSH22.as_usize()] = eval_push22 as _;
-		table[Opcode::PUSH23.as_usize()] = eval_push23 as _;
-		table[Opcode::PUSH24.as_usize()] = eval_push24 as _;
-		table[Opcode::PUSH25.as_usize()] = eval_push25 as _;
-		table[Opcode::PUSH26.as_usize()] = eval_push26 as _;
-		table[Opcode::PUSH27.as_usize()] = eval_push27 as _;
-		table[Opcode::PUSH28.as_usize()] = eval_push28 as _;
-		table[Opcode::PUSH29.as_usize()] = eval_push29 as _;
-		table[Opcode::PUSH30.as_usize()] = eval_push30 as _;
-		table[Opcode::PUSH31.as_usize()] = eval_push31 as _;
-		table[Opcode::PUSH32.as_usize()] = eval_push32 as _;
-
-		table[Opcode::DUP1.as_usize()] = eval_dup1 as _;
-		table[Opcode::DUP2.as_usize()] = eval_dup2 as _;
-		table[Opcode::DUP3.as_usize()] = eval_dup3 as _;
-		table[Opcode::DUP4.as_usize()] = eval_dup4 as _;
-		table[Opcode::DUP5.as_usize()] = eval_dup5 as _;
-		table[Opcode::DUP6.as_usize()] = eval_dup6 as _;
-		table[Opcode::DUP7.as_usize()] = eval_dup7 as _;
-		table[Opcode::DUP8.as_usize()] = eval_dup8 as _;
-		table[Opcode::DUP9.as_usize()] = eval_dup9 as _;
-		table[Opcode::DUP10.as_usize()] = eval_dup10 as _;
-		table[Opcode::DUP11.as_usize()] = eval_dup11 as _;
-		table[Opcode::DUP12.as_usize()] = eval_dup12 as _;
-		table[Opcode::DUP13.as_usize()] = eval_dup13 as _;
-		table[Opcode::DUP14.as_usize()] = eval_dup14 as _;
-		table[Opcode::DUP15.as_usize()] = eval_dup15 as _;
-		table[Opcode::DUP16.as_usize()] = eval_dup16 as _;
-
-		table[Opcode::SWAP1.as_usize()] = eval_swap1 as _;
-		table[Opcode::SWAP2.as_usize()] = eval_swap2 as _;
-		table[Opcode::SWAP3.as_usize()] = eval_swap3 as _;
-		table[Opcode::SWAP4.as_usize()] = eval_swap4 as _;
-		table[Opcode::SWAP5.as_usize()] = eval_swap5 as _;
-		table[Opcode::SWAP6.as_usize()] = eval_swap6 as _;
-		table[Opcode::SWAP7.as_usize()] = eval_swap7 as _;
-		table[Opcode::SWAP8.as_usize()] = eval_swap8 as _;
-		table[Opcode::SWAP9.as_usize()] = eval_swap9 as _;
-		table[Opcode::SWAP10.as_usize()] = eval_swap10 as _;
-		table[Opcode::SWAP11.as_usize()] = eval_swap11 as _;
-		table[Opcode::SWAP12.as_usize()] = eval_swap12 as _;
-		table[Opcode::SWAP13.as_usize()] = eval_swap13 as _;
-		table[Opcode::SWAP14.as_usize()] = eval_swap14 as _;
-		table[Opcode::SWAP15.as_usize()] = eval_swap15 as _;
-		table[Opcode::SWAP16.as_usize()] = eval_swap16 as _;
-
-		table[Opcode::RETURN.as_usize()] = eval_return as _;
-		table[Opcode::REVERT.as_usize()] = eval_revert as _;
-		table[Opcode::INVALID.as_usize()] = eval_invalid as _;
-
-		table
-	};
+	static TABLE: [EvalFn; 256] = table::<true>();
+
+	TABLE[opcode.as_usize()](state, opcode, position)
+}
+
+/// Process non-external opcodes without checking the stack height. The
+/// caller must have checked that the stack has enough items and room for the
+/// opcode, otherwise missing items are read as zero and the limit is ignored.
+pub fn eval_unchecked(state: &mut Machine, opcode: Opcode, position: usize) -> Control {
+	static TABLE: [EvalFn; 256] = table::<false>();
 
 	TABLE[opcode.as_usize()](state, opcode, position)
 }
+
+/// Handler of an opcode.
+pub type EvalFn = fn(state: &mut Machine, opcode: Opcode, position: usize) -> Control;
+
+/// Table of the opcode handlers, with or without stack height checks.
+#[allow(clippy::too_many_lines)]
+const fn table<const CHECKED: bool>() -> [EvalFn; 256] {
+	let mut table = [eval_external as _; 256];
+
+	table[Opcode::STOP.as_usize()] = eval_stop as _;
+	table[Opcode::ADD.as_usize()] = eval_add::<CHECKED> as _;
+	table[Opcode::MUL.as_usize()] = eval_mul::<CHECKED> as _;
+	table[Opcode::SUB.as_usize()] = eval_sub::<CHECKED> as _;
+	table[Opcode::DIV.as_usize()] = eval_div::<CHECKED> as _;
+	table[Opcode::SDIV.as_usize()] = eval_sdiv::<CHECKED> as _;
+	table[Opcode::MOD.as_usize()] = eval_mod::<CHECKED> as _;
+	table[Opcode::SMOD.as_usize()] = eval_smod::<CHECKED> as _;
+	table[Opcode::ADDMOD.as_usize()] = eval_addmod::<CHECKED> as _;
+	table[Opcode::MULMOD.as_usize()] = eval_mulmod::<CHECKED> as _;
+	table[Opcode::EXP.as_usize()] = eval_exp::<CHECKED> as _;
+	table[Opcode::SIGNEXTEND.as_usize()] = eval_signextend::<CHECKED> as _;
+	table[Opcode::LT.as_usize()] = eval_lt::<CHECKED> as _;
+	table[Opcode::GT.as_usize()] = eval_gt::<CHECKED> as _;
+	table[Opcode::SLT.as_usize()] = eval_slt::<CHECKED> as _;
+	table[Opcode::SGT.as_usize()] = eval_sgt::<CHECKED> as _;
+	table[Opcode::EQ.as_usize()] = eval_eq::<CHECKED> as _;
+	table[Opcode::ISZERO.as_usize()] = eval_iszero::<CHECKED> as _;
+	table[Opcode::AND.as_usize()] = eval_and::<CHECKED> as _;
+	table[Opcode::OR.as_usize()] = eval_or::<CHECKED> as _;
+	table[Opcode::XOR.as_usize()] = eval_xor::<CHECKED> as _;
+	table[Opcode::NOT.as_usize()] = eval_not::<CHECKED> as _;
+	table[Opcode::BYTE.as_usize()] = eval_byte::<CHECKED> as _;
+	table[Opcode::SHL.as_usize()] = eval_shl::<CHECKED> as _;
+	table[Opcode::SHR.as_usize()] = eval_shr::<CHECKED> as _;
+	table[Opcode::SAR.as_usize()] = eval_sar::<CHECKED> as _;
+	table[Opcode::CODESIZE.as_usize()] = eval_codesize::<CHECKED> as _;
+	table[Opcode::CODECOPY.as_usize()] = eval_codecopy::<CHECKED> as _;
+	table[Opcode::CALLDATALOAD.as_usize()] = eval_calldataload::<CHECKED> as _;
+	table[Opcode::CALLDATASIZE.as_usize()] = eval_calldatasize::<CHECKED> as _;
+	table[Opcode::CALLDATACOPY.as_usize()] = eval_calldatacopy::<CHECKED> as _;
+	table[Opcode::POP.as_usize()] = eval_pop::<CHECKED> as _;
+	table[Opcode::MLOAD.as_usize()] = eval_mload::<CHECKED> as _;
+	table[Opcode::MSTORE.as_usize()] = eval_mstore::<CHECKED> as _;
+	table[Opcode::MSTORE8.as_usize()] = eval_mstore8::<CHECKED> as _;
+	table[Opcode::JUMP.as_usize()] = eval_jump::<CHECKED> as _;
+	table[Opcode::JUMPI.as_usize()] = eval_jumpi::<CHECKED> as _;
+	table[Opcode::RJUMP.as_usize()] = eval_rjump as _;
+	table[Opcode::RJUMPI.as_usize()] = eval_rjumpi::<CHECKED> as _;
+	table[Opcode::RJUMPV.as_usize()] = eval_rjumpv::<CHECKED> as _;
+	table[Opcode::CALLF.as_usize()] = eval_callf as _;
+	table[Opcode::RETF.as_usize()] = eval_retf as _;
+	table[Opcode::PC.as_usize()] = eval_pc::<CHECKED> as _;
+	table[Opcode::MSIZE.as_usize()] = eval_msize::<CHECKED> as _;
+	table[Opcode::JUMPDEST.as_usize()] = eval_jumpdest as _;
+	table[Opcode::MCOPY.as_usize()] = eval_mcopy::<CHECKED> as _;
+
+	table[Opcode::PUSH0.as_usize()] = eval_push0::<CHECKED> as _;
+	table[Opcode::PUSH1.as_usize()] = eval_push1::<CHECKED> as _;
+	table[Opcode::PUSH2.as_usize()] = eval_push2::<CHECKED> as _;
+	table[Opcode::PUSH3.as_usize()] = eval_push3::<CHECKED> as _;
+	table[Opcode::PUSH4.as_usize()] = eval_push4::<CHECKED> as _;
+	table[Opcode::PUSH5.as_usize()] = eval_push5::<CHECKED> as _;
+	table[Opcode::PUSH6.as_usize()] = eval_push6::<CHECKED> as _;
+	table[Opcode::PUSH7.as_usize()] = eval_push7::<CHECKED> as _;
+	table[Opcode::PUSH8.as_usize()] = eval_push8::<CHECKED> as _;
+	table[Opcode::PUSH9.as_usize()] = eval_push9::<CHECKED> as _;
+	table[Opcode::PUSH10.as_usize()] = eval_push10::<CHECKED> as _;
+	table[Opcode::PUSH11.as_usize()] = eval_push11::<CHECKED> as _;
+	table[Opcode::PUSH12.as_usize()] = eval_push12::<CHECKED> as _;
+	table[Opcode::PUSH13.as_usize()] = eval_push13::<CHECKED> as _;
+	table[Opcode::PUSH14.as_usize()] = eval_push14::<CHECKED> as _;
+	table[Opcode::PUSH15.as_usize()] = eval_push15::<CHECKED> as _;
+	table[Opcode::PUSH16.as_usize()] = eval_push16::<CHECKED> as _;
+	table[Opcode::PUSH17.as_usize()] = eval_push17::<CHECKED> as _;
+	table[Opcode::PUSH18.as_usize()] = eval_push18::<CHECKED> as _;
+	table[Opcode::PUSH19.as_usize()] = eval_push19::<CHECKED> as _;
+	table[Opcode::PUSH20.as_usize()] = eval_push20::<CHECKED> as _;
+	table[Opcode::PUSH21.as_usize()] = eval_push21::<CHECKED> as _;
+	table[Opcode::PUSH22.as_usize()] = eval_push22::<CHECKED> as _;
+	table[Opcode::PUSH23.as_usize()] = eval_push23::<CHECKED> as _;
+	table[Opcode::PUSH24.as_usize()] = eval_push24::<CHECKED> as _;
+	table[Opcode::PUSH25.as_usize()] = eval_push25::<CHECKED> as _;
+	table[Opcode::PUSH26.as_usize()] = eval_push26::<CHECKED> as _;
+	table[Opcode::PUSH27.as_usize()] = eval_push27::<CHECKED> as _;
+	table[Opcode::PUSH28.as_usize()] = eval_push28::<CHECKED> as _;
+	table[Opcode::PUSH29.as_usize()] = eval_push29::<CHECKED> as _;
+	table[Opcode::PUSH30.as_usize()] = eval_push30::<CHECKED> as _;
+	table[Opcode::PUSH31.as_usize()] = eval_push31::<CHECKED> as _;
+	table[Opcode::PUSH32.as_usize()] = eval_push32::<CHECKED> as _;
+
+	table[Opcode::DUP1.as_usize()] = eval_dup1::<CHECKED> as _;
+	table[Opcode::DUP2.as_usize()] = eval_dup2::<CHECKED> as _;
+	table[Opcode::DUP3.as_usize()] = eval_dup3::<CHECKED> as _;
+	table[Opcode::DUP4.as_usize()] = eval_dup4::<CHECKED> as _;
+	table[Opcode::DUP5.as_usize()] = eval_dup5::<CHECKED> as _;
+	table[Opcode::DUP6.as_usize()] = eval_dup6::<CHECKED> as _;
+	table[Opcode::DUP7.as_usize()] = eval_dup7::<CHECKED> as _;
+	table[Opcode::DUP8.as_usize()] = eval_dup8::<CHECKED> as _;
+	table[Opcode::DUP9.as_usize()] = eval_dup9::<CHECKED> as _;
+	table[Opcode::DUP10.as_usize()] = eval_dup10::<CHECKED> as _;
+	table[Opcode::DUP11.as_usize()] = eval_dup11::<CHECKED> as _;
+	table[Opcode::DUP12.as_usize()] = eval_dup12::<CHECKED> as _;
+	table[Opcode::DUP13.as_usize()] = eval_dup13::<CHECKED> as _;
+	table[Opcode::DUP14.as_usize()] = eval_dup14::<CHECKED> as _;
+	table[Opcode::DUP15.as_usize()] = eval_dup15::<CHECKED> as _;
+	table[Opcode::DUP16.as_usize()] = eval_dup16::<CHECKED> as _;
+
+	table[Opcode::SWAP1.as_usize()] = eval_swap1::<CHECKED> as _;
+	table[Opcode::SWAP2.as_usize()] = eval_swap2::<CHECKED> as _;
+	table[Opcode::SWAP3.as_usize()] = eval_swap3::<CHECKED> as _;
+	table[Opcode::SWAP4.as_usize()] = eval_swap4::<CHECKED> as _;
+	table[Opcode::SWAP5.as_usize()] = eval_swap5::<CHECKED> as _;
+	table[Opcode::SWAP6.as_usize()] = eval_swap6::<CHECKED> as _;
+	table[Opcode::SWAP7.as_usize()] = eval_swap7::<CHECKED> as _;
+	table[Opcode::SWAP8.as_usize()] = eval_swap8::<CHECKED> as _;
+	table[Opcode::SWAP9.as_usize()] = eval_swap9::<CHECKED> as _;
+	table[Opcode::SWAP10.as_usize()] = eval_swap10::<CHECKED> as _;
+	table[Opcode::SWAP11.as_usize()] = eval_swap11::<CHECKED> as _;
+	table[Opcode::SWAP12.as_usize()] = eval_swap12::<CHECKED> as _;
+	table[Opcode::SWAP13.as_usize()] = eval_swap13::<CHECKED> as _;
+	table[Opcode::SWAP14.as_usize()] = eval_swap14::<CHECKED> as _;
+	table[Opcode::SWAP15.as_usize()] = eval_swap15::<CHECKED> as _;
+	table[Opcode::SWAP16.as_usize()] = eval_swap16::<CHECKED> as _;
+
+	table[Opcode::RETURN.as_usize()] = eval_return::<CHECKED> as _;
+	table[Opcode::REVERT.as_usize()] = eval_revert::<CHECKED> as _;
+	table[Opcode::INVALID.as_usize()] = eval_invalid as _;
+
+	table
+}
@@ -20,22 +20,33 @@ mod memory;
 mod stack;
 mod valids;
 mod opcode;
+mod opcode_info;
 mod error;
 mod eval;
 mod utils;
 mod primitive_types;
 mod context;
+mod eof;
+mod disassembler;
+mod assembler;
+mod analysis;
 
-pub use crate::memory::Memory;
+pub use crate::memory::{Memory, MemoryExpansion};
 pub use crate::stack::Stack;
 pub use crate::valids::Valids;
-pub use crate::opcode::Opcode;
+pub use crate::opcode::{Opcode, ParseOpcodeError};
+pub use crate::opcode_info::{Fork, OpcodeInfo, OPCODE_INFO};
 pub use crate::error::{Trap, Capture, ExitReason, ExitSucceed, ExitError, ExitRevert, ExitFatal};
 pub use crate::primitive_types::{H160, H256, U256, U512};
 pub use crate::context::{Context, CreateScheme, CallScheme, Transfer};
+pub use crate::disassembler::{Disassembler, Instruction};
+pub use crate::assembler::{assemble, AssemblyError};
+pub use crate::analysis::{basic_blocks, BasicBlock};
+pub use crate::eof::{EofContainer, EofError, TypeSection, EOF_MAGIC, EOF_VERSION};
 
 use alloc::vec::Vec;
-use crate::eval::{eval, Control};
+use crate::eof::EofState;
+use crate::eval::{eval, eval_unchecked, Control, EvalFn};
 
 /// Core execution layer for EVM.
 #[cfg_attr(feature = "with-codec", derive(codec::Encode, codec::Decode))]
@@ -58,6 +69,13 @@ pub struct Machine {
 	memory: Memory,
 	/// Stack.
 	stack: Stack,
+	/// Code sections and return stack, if the code is an EOF container.
+	eof: Option<EofState>,
+	/// Basic blocks of legacy code, computed by the first `run`.
+	#[cfg_attr(feature = "with-codec", codec(skip))]
+	#[cfg_attr(feature = "with-serde", serde(skip))]
+	#[borsh(skip)]
+	blocks: Option<Vec<BasicBlock>>,
 }
 
 impl Machine {
@@ -96,9 +114,41 @@ impl Machine {
 			valids,
 			memory: Memory::new(memory_limit),
 			stack: Stack::new(stack_limit),
+			eof: None,
+			blocks: None,
 		}
 	}
 
+	/// Create a new machine running an EOF container, starting at its first
+	/// code section. Fails if the container is malformed or its code is
+	/// invalid.
+	pub fn new_eof(
+		code: Vec<u8>,
+		data: Vec<u8>,
+		stack_limit: usize,
+		memory_limit: usize
+	) -> Result<Self, EofError> {
+		let container = EofContainer::parse(&code)?;
+		container.validate()?;
+
+		let offsets = (0..container.code.len()).map(|index| container.code_offset(index)).collect();
+		let mut machine = Self::new(code, Vec::new(), data, stack_limit, memory_limit);
+		machine.position = Ok(container.code_offset(0));
+		machine.eof = Some(EofState {
+			types: container.types,
+			offsets,
+			return_stack: Vec::new(),
+		});
+
+		Ok(machine)
+	}
+
+	/// Whether the machine runs an EOF container.
+	#[must_use]
+	pub const fn is_eof(&self) -> bool {
+		self.eof.is_some()
+	}
+
 	/// Explicit exit of the machine. Further step will return error.
 	pub fn exit(&mut self, reason: ExitReason) {
 		self.position = Err(reason);
@@ -130,6 +180,9 @@ impl Machine {
 	}
 
 	/// Loop stepping the machine, until it stops.
+	///
+	/// Legacy code runs by basic blocks: when the stack height fits a block on
+	/// entry, its opcodes run without further stack height checks.
 	pub fn run<F>(&mut self,
 				  max_steps: u64,
 				  mut pre_validate: F,
@@ -137,51 +190,105 @@ impl Machine {
 	) -> (u64, Capture<ExitReason, Trap>)
 		where F: FnMut(Opcode, &Stack) -> Result<(), ExitError>
 	{
-		for step in 0..max_steps {
-			let position = match self.position {
-				Ok(position) => position,
-				Err(reason) => return (step, Capture::Exit(reason))
-			};
-
-			let opcode = match self.code.get(position) {
-				Some(opcode) => Opcode(*opcode),
-				None => {
-					self.position = Err(ExitReason::Succeed(ExitSucceed::Stopped));
-					return (step, Capture::Exit(ExitReason::Succeed(ExitSucceed::Stopped)));
+		if self.eof.is_some() {
+			for step in 0..max_steps {
+				if let Err(capture) = self.step(&mut pre_validate) {
+					return (step, capture)
 				}
-			};
-
-			if let Err(error) = pre_validate(opcode, &self.stack()) {
-				let reason = ExitReason::from(error);
-				self.exit(reason);
-				return (step, Capture::Exit(reason));
 			}
 
-			let result = match eval(self, opcode, position) {
-				Control::Continue(p) => {
-					self.position = Ok(position + p);
-					Ok(())
-				},
-				Control::Exit(reason) => {
-					self.exit(reason);
-					Err(Capture::Exit(reason))
-				},
-				Control::Jump(p) => {
-					self.position = Ok(p);
-					Ok(())
-				},
-				Control::Trap(opcode) => {
-					self.position = Ok(position + 1);
-					Err(Capture::Trap(opcode))
-				},
-			};
-
-			if let Err(capture) = result {
-				return (step, capture)
+			return (max_steps, Capture::Exit(ExitReason::StepLimitReached))
+		}
+
+		let blocks = self.blocks.take().unwrap_or_else(|| basic_blocks(&self.code));
+		let result = self.run_blocks(&blocks, max_steps, &mut pre_validate);
+		self.blocks = Some(blocks);
+		result
+	}
+
+	fn run_blocks<F>(
+		&mut self,
+		blocks: &[BasicBlock],
+		max_steps: u64,
+		pre_validate: &mut F,
+	) -> (u64, Capture<ExitReason, Trap>)
+		where F: FnMut(Opcode, &Stack) -> Result<(), ExitError>
+	{
+		let mut step = 0;
+		while step < max_steps {
+			let block = self.position.ok()
+				.and_then(|position| blocks.binary_search_by_key(&position, |block| block.start).ok())
+				.map(|index| blocks[index])
+				.filter(|block| block.fits(self.stack.len(), self.stack.limit()));
+
+			// Outside of a block start, or if the stack does not fit, step once
+			// with checks so that errors are raised by the failing opcode.
+			let (end, eval) = block.map_or(
+				(step + 1, eval as EvalFn),
+				|block| (max_steps.min(step + block.len as u64), eval_unchecked),
+			);
+
+			while step < end {
+				if let Err(capture) = self.step_with(&mut *pre_validate, eval) {
+					return (step, capture)
+				}
+				step += 1;
 			}
 		}
 
 		(max_steps, Capture::Exit(ExitReason::StepLimitReached))
 	}
 
+	/// Step the machine once, executing one opcode after `pre_validate`
+	/// accepts it. Fails with the exit reason if the machine stops, or with
+	/// the opcode if it has to be handled externally.
+	pub fn step<F>(&mut self, pre_validate: F) -> Result<(), Capture<ExitReason, Trap>>
+		where F: FnOnce(Opcode, &Stack) -> Result<(), ExitError>
+	{
+		self.step_with(pre_validate, eval)
+	}
+
+	fn step_with<F>(
+		&mut self,
+		pre_validate: F,
+		eval: EvalFn,
+	) -> Result<(), Capture<ExitReason, Trap>>
+		where F: FnOnce(Opcode, &Stack) -> Result<(), ExitError>
+	{
+		let position = self.position.map_err(Capture::Exit)?;
+
+		let opcode = match self.code.get(position) {
+			Some(opcode) => Opcode(*opcode),
+			None => {
+				self.position = Err(ExitReason::Succeed(ExitSucceed::Stopped));
+				return Err(Capture::Exit(ExitReason::Succeed(ExitSucceed::Stopped)));
+			}
+		};
+
+		if let Err(error) = pre_validate(opcode, self.stack()) {
+			let reason = ExitReason::from(error);
+			self.exit(reason);
+			return Err(Capture::Exit(reason));
+		}
+
+		match eval(self, opcode, position) {
+			Control::Continue(p) => {
+				self.position = Ok(position + p);
+				Ok(())
+			},
+			Control::Exit(reason) => {
+				self.exit(reason);
+				Err(Capture::Exit(reason))
+			},
+			Control::Jump(p) => {
+				self.position = Ok(p);
+				Ok(())
+			},
+			Control::Trap(opcode) => {
+				self.position = Ok(position + 1);
+				Err(Capture::Trap(opcode))
+			},
+		}
+	}
+
 }
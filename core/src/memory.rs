@@ -2,6 +2,20 @@ use core::cmp::{min, max};
 use alloc::{vec,vec::Vec};
 use crate::{ExitError, ExitFatal};
 
+/// Gas paid per word of memory.
+const G_MEMORY: u64 = 3;
+/// Divisor of the quadratic part of the memory cost.
+const G_QUADDIVISOR: u64 = 512;
+
+/// Memory expansion caused by a resize.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MemoryExpansion {
+	/// Number of 32-byte words added to the effective memory.
+	pub new_words: usize,
+	/// Gas cost of the added words.
+	pub cost: u64,
+}
+
 /// A sequencial memory. It uses Rust's `Vec` for internal
 /// representation.
 #[derive(Clone, Debug)]
@@ -44,6 +58,12 @@ impl Memory {
 		self.effective_len
 	}
 
+	/// Get the effective length in 32-byte words.
+	#[must_use]
+	pub const fn effective_words(&self) -> usize {
+		self.effective_len / 32
+	}
+
 	/// Return true if current effective memory range is zero.
 	#[must_use]
 	pub fn is_empty(&self) -> bool {
@@ -54,35 +74,74 @@ impl Memory {
 		&self.data
 	}
 
+	/// Total gas cost of a memory of `words` 32-byte words,
+	/// `3 * words + words ^ 2 / 512`.
+	pub fn cost(words: usize) -> Result<u64, ExitError> {
+		let words = words as u64;
+		let linear = words.checked_mul(G_MEMORY).ok_or(ExitError::OutOfGas)?;
+		let quadratic = words.checked_mul(words).ok_or(ExitError::OutOfGas)? / G_QUADDIVISOR;
+
+		linear.checked_add(quadratic).ok_or(ExitError::OutOfGas)
+	}
+
+	/// Expansion that a resize to cover `offset..(offset + len)` would cause,
+	/// without touching the memory. If the length is zero, the expansion is
+	/// empty.
+	pub fn expansion(&self, offset: usize, len: usize) -> Result<MemoryExpansion, ExitError> {
+		if len == 0 {
+			return Ok(MemoryExpansion::default())
+		}
+
+		offset.checked_add(len).map_or(Err(ExitError::InvalidRange), |end| self.expansion_end(end))
+	}
+
+	/// Expansion that a resize to cover `end` would cause, without touching
+	/// the memory.
+	pub fn expansion_end(&self, end: usize) -> Result<MemoryExpansion, ExitError> {
+		let end = Self::align_end(end)?;
+		if end <= self.effective_len {
+			return Ok(MemoryExpansion::default())
+		}
+
+		let words = self.effective_words();
+		let new_words = end / 32;
+
+		Ok(MemoryExpansion {
+			new_words: new_words - words,
+			cost: Self::cost(new_words)? - Self::cost(words)?,
+		})
+	}
+
 	/// Resize the memory, making it cover the memory region of `offset..(offset
 	/// + len)`, with 32 bytes as the step. If the length is zero, this function
-	/// does nothing.
-	pub fn resize_offset(&mut self, offset: usize, len: usize) -> Result<(), ExitError> {
+	/// does nothing. Returns the expansion that took place.
+	pub fn resize_offset(&mut self, offset: usize, len: usize) -> Result<MemoryExpansion, ExitError> {
 		if len == 0 {
-			return Ok(())
+			return Ok(MemoryExpansion::default())
 		}
 
 		offset.checked_add(len).map_or(Err(ExitError::InvalidRange), |end| self.resize_end(end))
 	}
 
 	/// Resize the memory, making it cover to `end`, with 32 bytes as the step.
-	pub fn resize_end(&mut self, end: usize) -> Result<(), ExitError> {
-		let end = {
-			let modulo = end % 32;
-			if modulo == 0 {
-				end
-			} else {
-				// next closest value to `end` that is divisible by 32
-				// end = (end + 32) - (end % 32)
-				match end.checked_add(32) {
-					Some(end) => end - modulo,
-					None => return Err(ExitError::InvalidRange)
-				}
-			}
-		};
+	/// Returns the expansion that took place.
+	pub fn resize_end(&mut self, end: usize) -> Result<MemoryExpansion, ExitError> {
+		let expansion = self.expansion_end(end)?;
 
-		self.effective_len = max(self.effective_len, end);
-		Ok(())
+		self.effective_len = max(self.effective_len, Self::align_end(end)?);
+		Ok(expansion)
+	}
+
+	/// Round `end` up to the next multiple of 32.
+	fn align_end(end: usize) -> Result<usize, ExitError> {
+		let modulo = end % 32;
+		if modulo == 0 {
+			Ok(end)
+		} else {
+			// next closest value to `end` that is divisible by 32
+			// end = (end + 32) - (end % 32)
+			end.checked_add(32).map_or(Err(ExitError::InvalidRange), |end| Ok(end - modulo))
+		}
 	}
 
 	/// Get memory region at given offset.
@@ -159,3 +218,34 @@ impl Memory {
 		self.set(memory_offset, data_by_offset, Some(len))
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use crate::{Memory, MemoryExpansion};
+
+	#[test]
+	fn test_cost() {
+		assert_eq!(Memory::cost(0), Ok(0));
+		assert_eq!(Memory::cost(1), Ok(3));
+		assert_eq!(Memory::cost(32), Ok(98));
+		assert_eq!(Memory::cost(1024), Ok(5120));
+	}
+
+	#[test]
+	fn test_resize_expansion() {
+		let mut memory = Memory::new(usize::MAX);
+
+		assert_eq!(memory.resize_offset(0, 0), Ok(MemoryExpansion::default()));
+		assert_eq!(memory.expansion(0, 32), Ok(MemoryExpansion { new_words: 1, cost: 3 }));
+		assert_eq!(memory.effective_len(), 0);
+
+		assert_eq!(memory.resize_offset(0, 32), Ok(MemoryExpansion { new_words: 1, cost: 3 }));
+		assert_eq!(memory.resize_offset(31, 1), Ok(MemoryExpansion::default()));
+		assert_eq!(memory.resize_offset(32, 1), Ok(MemoryExpansion { new_words: 1, cost: 3 }));
+		assert_eq!(memory.effective_len(), 64);
+
+		// 2 -> 1024 words: 5120 - 6.
+		assert_eq!(memory.resize_end(32 * 1024), Ok(MemoryExpansion { new_words: 1022, cost: 5114 }));
+		assert_eq!(memory.effective_words(), 1024);
+	}
+}
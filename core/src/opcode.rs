@@ -1,6 +1,13 @@
 #![allow(clippy::use_self)]
+use core::fmt;
+use core::str::FromStr;
+use crate::opcode_info::{OpcodeInfo, OPCODE_INFO};
+
 /// Opcode enum. One-to-one corresponding to an `u8` value.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "with-codec", derive(codec::Encode, codec::Decode))]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(borsh::BorshSerialize, borsh::BorshDeserialize)]
 pub struct Opcode(pub u8);
 
 // Core opcodes.
@@ -169,6 +176,17 @@ impl Opcode {
 
 	/// `INVALID`
 	pub const INVALID: Opcode = Opcode(0xfe);
+
+	/// `RJUMP`, EOF only (EIP-4200)
+	pub const RJUMP: Opcode = Opcode(0xe0);
+	/// `RJUMPI`, EOF only (EIP-4200)
+	pub const RJUMPI: Opcode = Opcode(0xe1);
+	/// `RJUMPV`, EOF only (EIP-4200)
+	pub const RJUMPV: Opcode = Opcode(0xe2);
+	/// `CALLF`, EOF only (EIP-4750)
+	pub const CALLF: Opcode = Opcode(0xe3);
+	/// `RETF`, EOF only (EIP-4750)
+	pub const RETF: Opcode = Opcode(0xe4);
 }
 
 // External opcodes
@@ -183,6 +201,10 @@ impl Opcode {
 	pub const SELFBALANCE: Opcode = Opcode(0x47);
 	/// `BASEFEE`
 	pub const BASEFEE: Opcode = Opcode(0x48);
+	/// `BLOBHASH`
+	pub const BLOBHASH: Opcode = Opcode(0x49);
+	/// `BLOBBASEFEE`
+	pub const BLOBBASEFEE: Opcode = Opcode(0x4a);
 	/// `ORIGIN`
 	pub const ORIGIN: Opcode = Opcode(0x32);
 	/// `CALLER`
@@ -260,4 +282,76 @@ impl Opcode {
 	pub const fn as_usize(self) -> usize {
 		self.0 as usize
 	}
+
+	/// Number of immediate bytes pushed, if the opcode is one of `PUSH0` to
+	/// `PUSH32`.
+	#[must_use]
+	pub const fn is_push(self) -> Option<u8> {
+		if self.0 >= Self::PUSH0.0 && self.0 <= Self::PUSH32.0 {
+			Some(self.0 - Self::PUSH0.0)
+		} else {
+			None
+		}
+	}
+
+	/// Static metadata of the opcode, or `None` if it is undefined.
+	#[must_use]
+	pub const fn info(self) -> Option<OpcodeInfo> {
+		OPCODE_INFO[self.as_usize()]
+	}
+
+	/// Mnemonic of the opcode, or `None` if it is undefined.
+	#[must_use]
+	pub const fn name(self) -> Option<&'static str> {
+		match self.info() {
+			Some(info) => Some(info.name),
+			None => None,
+		}
+	}
+}
+
+/// Prints the mnemonic, or the byte in hex if the opcode is undefined.
+impl fmt::Display for Opcode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.name() {
+			Some(name) => f.write_str(name),
+			None => write!(f, "{:#04x}", self.0),
+		}
+	}
+}
+
+/// Error of parsing an unknown mnemonic.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ParseOpcodeError;
+
+impl fmt::Display for ParseOpcodeError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("unknown opcode")
+	}
+}
+
+/// Parses a mnemonic, case-insensitively, or a byte in hex as printed for
+/// undefined opcodes. `KECCAK256`, `PREVRANDAO` and `SELFDESTRUCT` are
+/// accepted as the newer names of `SHA3`, `DIFFICULTY` and `SUICIDE`.
+impl FromStr for Opcode {
+	type Err = ParseOpcodeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if let Some(hex) = s.strip_prefix("0x") {
+			return u8::from_str_radix(hex, 16).map(Self).map_err(|_| ParseOpcodeError)
+		}
+
+		let alias = match s.to_ascii_uppercase().as_str() {
+			"KECCAK256" => Some(Self::SHA3),
+			"PREVRANDAO" => Some(Self::DIFFICULTY),
+			"SELFDESTRUCT" => Some(Self::SUICIDE),
+			_ => None,
+		};
+
+		alias
+			.or_else(|| (0..=u8::MAX).map(Self).find(|opcode| {
+				opcode.name().is_some_and(|name| name.eq_ignore_ascii_case(s))
+			}))
+			.ok_or(ParseOpcodeError)
+	}
 }
@@ -0,0 +1,271 @@
+use crate::Opcode;
+
+/// Fork introducing an opcode, in activation order.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Fork {
+	/// Frontier.
+	Frontier,
+	/// Homestead.
+	Homestead,
+	/// Byzantium.
+	Byzantium,
+	/// Constantinople.
+	Constantinople,
+	/// Istanbul.
+	Istanbul,
+	/// Berlin.
+	Berlin,
+	/// London.
+	London,
+	/// Shanghai.
+	Shanghai,
+	/// Cancun.
+	Cancun,
+	/// Prague.
+	Prague,
+	/// EVM Object Format, not scheduled for a fork yet and enabled by
+	/// `Config::has_eof`.
+	Eof,
+}
+
+/// Static metadata of an opcode.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OpcodeInfo {
+	/// Mnemonic, as printed by `Opcode`.
+	pub name: &'static str,
+	/// Number of stack items popped. Zero for `CALLF` and `RETF`, whose
+	/// stack effect depends on the EOF type section.
+	pub inputs: u8,
+	/// Number of stack items pushed.
+	pub outputs: u8,
+	/// Number of immediate bytes following the opcode. `RJUMPV` is followed
+	/// by a jump table whose length its single immediate byte gives.
+	pub immediate_size: u8,
+	/// Whether the opcode traps to the runtime rather than being evaluated by
+	/// `Machine`.
+	pub is_external: bool,
+	/// Fork introducing the opcode.
+	pub fork: Fork,
+}
+
+const fn internal(name: &'static str, inputs: u8, outputs: u8, fork: Fork) -> OpcodeInfo {
+	OpcodeInfo { name, inputs, outputs, immediate_size: 0, is_external: false, fork }
+}
+
+const fn external(name: &'static str, inputs: u8, outputs: u8, fork: Fork) -> OpcodeInfo {
+	OpcodeInfo { name, inputs, outputs, immediate_size: 0, is_external: true, fork }
+}
+
+const fn push(name: &'static str, immediate_size: u8) -> OpcodeInfo {
+	OpcodeInfo { immediate_size, ..internal(name, 0, 1, Fork::Frontier) }
+}
+
+const fn eof(name: &'static str, inputs: u8, immediate_size: u8) -> OpcodeInfo {
+	OpcodeInfo { immediate_size, ..internal(name, inputs, 0, Fork::Eof) }
+}
+
+/// Metadata of every opcode, indexed by its byte. `None` for undefined
+/// opcodes.
+pub const OPCODE_INFO: [Option<OpcodeInfo>; 256] = {
+	let mut table = [None; 256];
+
+	table[Opcode::STOP.as_usize()] = Some(internal("STOP", 0, 0, Fork::Frontier));
+	table[Opcode::ADD.as_usize()] = Some(internal("ADD", 2, 1, Fork::Frontier));
+	table[Opcode::MUL.as_usize()] = Some(internal("MUL", 2, 1, Fork::Frontier));
+	table[Opcode::SUB.as_usize()] = Some(internal("SUB", 2, 1, Fork::Frontier));
+	table[Opcode::DIV.as_usize()] = Some(internal("DIV", 2, 1, Fork::Frontier));
+	table[Opcode::SDIV.as_usize()] = Some(internal("SDIV", 2, 1, Fork::Frontier));
+	table[Opcode::MOD.as_usize()] = Some(internal("MOD", 2, 1, Fork::Frontier));
+	table[Opcode::SMOD.as_usize()] = Some(internal("SMOD", 2, 1, Fork::Frontier));
+	table[Opcode::ADDMOD.as_usize()] = Some(internal("ADDMOD", 3, 1, Fork::Frontier));
+	table[Opcode::MULMOD.as_usize()] = Some(internal("MULMOD", 3, 1, Fork::Frontier));
+	table[Opcode::EXP.as_usize()] = Some(internal("EXP", 2, 1, Fork::Frontier));
+	table[Opcode::SIGNEXTEND.as_usize()] = Some(internal("SIGNEXTEND", 2, 1, Fork::Frontier));
+
+	table[Opcode::LT.as_usize()] = Some(internal("LT", 2, 1, Fork::Frontier));
+	table[Opcode::GT.as_usize()] = Some(internal("GT", 2, 1, Fork::Frontier));
+	table[Opcode::SLT.as_usize()] = Some(internal("SLT", 2, 1, Fork::Frontier));
+	table[Opcode::SGT.as_usize()] = Some(internal("SGT", 2, 1, Fork::Frontier));
+	table[Opcode::EQ.as_usize()] = Some(internal("EQ", 2, 1, Fork::Frontier));
+	table[Opcode::ISZERO.as_usize()] = Some(internal("ISZERO", 1, 1, Fork::Frontier));
+	table[Opcode::AND.as_usize()] = Some(internal("AND", 2, 1, Fork::Frontier));
+	table[Opcode::OR.as_usize()] = Some(internal("OR", 2, 1, Fork::Frontier));
+	table[Opcode::XOR.as_usize()] = Some(internal("XOR", 2, 1, Fork::Frontier));
+	table[Opcode::NOT.as_usize()] = Some(internal("NOT", 1, 1, Fork::Frontier));
+	table[Opcode::BYTE.as_usize()] = Some(internal("BYTE", 2, 1, Fork::Frontier));
+	table[Opcode::SHL.as_usize()] = Some(internal("SHL", 2, 1, Fork::Constantinople));
+	table[Opcode::SHR.as_usize()] = Some(internal("SHR", 2, 1, Fork::Constantinople));
+	table[Opcode::SAR.as_usize()] = Some(internal("SAR", 2, 1, Fork::Constantinople));
+
+	table[Opcode::SHA3.as_usize()] = Some(external("SHA3", 2, 1, Fork::Frontier));
+
+	table[Opcode::ADDRESS.as_usize()] = Some(external("ADDRESS", 0, 1, Fork::Frontier));
+	table[Opcode::BALANCE.as_usize()] = Some(external("BALANCE", 1, 1, Fork::Frontier));
+	table[Opcode::ORIGIN.as_usize()] = Some(external("ORIGIN", 0, 1, Fork::Frontier));
+	table[Opcode::CALLER.as_usize()] = Some(external("CALLER", 0, 1, Fork::Frontier));
+	table[Opcode::CALLVALUE.as_usize()] = Some(external("CALLVALUE", 0, 1, Fork::Frontier));
+	table[Opcode::CALLDATALOAD.as_usize()] = Some(internal("CALLDATALOAD", 1, 1, Fork::Frontier));
+	table[Opcode::CALLDATASIZE.as_usize()] = Some(internal("CALLDATASIZE", 0, 1, Fork::Frontier));
+	table[Opcode::CALLDATACOPY.as_usize()] = Some(internal("CALLDATACOPY", 3, 0, Fork::Frontier));
+	table[Opcode::CODESIZE.as_usize()] = Some(internal("CODESIZE", 0, 1, Fork::Frontier));
+	table[Opcode::CODECOPY.as_usize()] = Some(internal("CODECOPY", 3, 0, Fork::Frontier));
+	table[Opcode::GASPRICE.as_usize()] = Some(external("GASPRICE", 0, 1, Fork::Frontier));
+	table[Opcode::EXTCODESIZE.as_usize()] = Some(external("EXTCODESIZE", 1, 1, Fork::Frontier));
+	table[Opcode::EXTCODECOPY.as_usize()] = Some(external("EXTCODECOPY", 4, 0, Fork::Frontier));
+	table[Opcode::RETURNDATASIZE.as_usize()] = Some(external("RETURNDATASIZE", 0, 1, Fork::Byzantium));
+	table[Opcode::RETURNDATACOPY.as_usize()] = Some(external("RETURNDATACOPY", 3, 0, Fork::Byzantium));
+	table[Opcode::EXTCODEHASH.as_usize()] = Some(external("EXTCODEHASH", 1, 1, Fork::Constantinople));
+
+	table[Opcode::BLOCKHASH.as_usize()] = Some(external("BLOCKHASH", 1, 1, Fork::Frontier));
+	table[Opcode::COINBASE.as_usize()] = Some(external("COINBASE", 0, 1, Fork::Frontier));
+	table[Opcode::TIMESTAMP.as_usize()] = Some(external("TIMESTAMP", 0, 1, Fork::Frontier));
+	table[Opcode::NUMBER.as_usize()] = Some(external("NUMBER", 0, 1, Fork::Frontier));
+	table[Opcode::DIFFICULTY.as_usize()] = Some(external("DIFFICULTY", 0, 1, Fork::Frontier));
+	table[Opcode::GASLIMIT.as_usize()] = Some(external("GASLIMIT", 0, 1, Fork::Frontier));
+	table[Opcode::CHAINID.as_usize()] = Some(external("CHAINID", 0, 1, Fork::Istanbul));
+	table[Opcode::SELFBALANCE.as_usize()] = Some(external("SELFBALANCE", 0, 1, Fork::Istanbul));
+	table[Opcode::BASEFEE.as_usize()] = Some(external("BASEFEE", 0, 1, Fork::London));
+	table[Opcode::BLOBHASH.as_usize()] = Some(external("BLOBHASH", 1, 1, Fork::Cancun));
+	table[Opcode::BLOBBASEFEE.as_usize()] = Some(external("BLOBBASEFEE", 0, 1, Fork::Cancun));
+
+	table[Opcode::POP.as_usize()] = Some(internal("POP", 1, 0, Fork::Frontier));
+	table[Opcode::MLOAD.as_usize()] = Some(internal("MLOAD", 1, 1, Fork::Frontier));
+	table[Opcode::MSTORE.as_usize()] = Some(internal("MSTORE", 2, 0, Fork::Frontier));
+	table[Opcode::MSTORE8.as_usize()] = Some(internal("MSTORE8", 2, 0, Fork::Frontier));
+	table[Opcode::SLOAD.as_usize()] = Some(external("SLOAD", 1, 1, Fork::Frontier));
+	table[Opcode::SSTORE.as_usize()] = Some(external("SSTORE", 2, 0, Fork::Frontier));
+	table[Opcode::JUMP.as_usize()] = Some(internal("JUMP", 1, 0, Fork::Frontier));
+	table[Opcode::JUMPI.as_usize()] = Some(internal("JUMPI", 2, 0, Fork::Frontier));
+	table[Opcode::PC.as_usize()] = Some(internal("PC", 0, 1, Fork::Frontier));
+	table[Opcode::MSIZE.as_usize()] = Some(internal("MSIZE", 0, 1, Fork::Frontier));
+	table[Opcode::GAS.as_usize()] = Some(external("GAS", 0, 1, Fork::Frontier));
+	table[Opcode::JUMPDEST.as_usize()] = Some(internal("JUMPDEST", 0, 0, Fork::Frontier));
+	table[Opcode::TLOAD.as_usize()] = Some(external("TLOAD", 1, 1, Fork::Cancun));
+	table[Opcode::TSTORE.as_usize()] = Some(external("TSTORE", 2, 0, Fork::Cancun));
+	table[Opcode::MCOPY.as_usize()] = Some(internal("MCOPY", 3, 0, Fork::Cancun));
+
+	table[Opcode::PUSH0.as_usize()] = Some(internal("PUSH0", 0, 1, Fork::Shanghai));
+	table[Opcode::PUSH1.as_usize()] = Some(push("PUSH1", 1));
+	table[Opcode::PUSH2.as_usize()] = Some(push("PUSH2", 2));
+	table[Opcode::PUSH3.as_usize()] = Some(push("PUSH3", 3));
+	table[Opcode::PUSH4.as_usize()] = Some(push("PUSH4", 4));
+	table[Opcode::PUSH5.as_usize()] = Some(push("PUSH5", 5));
+	table[Opcode::PUSH6.as_usize()] = Some(push("PUSH6", 6));
+	table[Opcode::PUSH7.as_usize()] = Some(push("PUSH7", 7));
+	table[Opcode::PUSH8.as_usize()] = Some(push("PUSH8", 8));
+	table[Opcode::PUSH9.as_usize()] = Some(push("PUSH9", 9));
+	table[Opcode::PUSH10.as_usize()] = Some(push("PUSH10", 10));
+	table[Opcode::PUSH11.as_usize()] = Some(push("PUSH11", 11));
+	table[Opcode::PUSH12.as_usize()] = Some(push("PUSH12", 12));
+	table[Opcode::PUSH13.as_usize()] = Some(push("PUSH13", 13));
+	table[Opcode::PUSH14.as_usize()] = Some(push("PUSH14", 14));
+	table[Opcode::PUSH15.as_usize()] = Some(push("PUSH15", 15));
+	table[Opcode::PUSH16.as_usize()] = Some(push("PUSH16", 16));
+	table[Opcode::PUSH17.as_usize()] = Some(push("PUSH17", 17));
+	table[Opcode::PUSH18.as_usize()] = Some(push("PUSH18", 18));
+	table[Opcode::PUSH19.as_usize()] = Some(push("PUSH19", 19));
+	table[Opcode::PUSH20.as_usize()] = Some(push("PUSH20", 20));
+	table[Opcode::PUSH21.as_usize()] = Some(push("PUSH21", 21));
+	table[Opcode::PUSH22.as_usize()] = Some(push("PUSH22", 22));
+	table[Opcode::PUSH23.as_usize()] = Some(push("PUSH23", 23));
+	table[Opcode::PUSH24.as_usize()] = Some(push("PUSH24", 24));
+	table[Opcode::PUSH25.as_usize()] = Some(push("PUSH25", 25));
+	table[Opcode::PUSH26.as_usize()] = Some(push("PUSH26", 26));
+	table[Opcode::PUSH27.as_usize()] = Some(push("PUSH27", 27));
+	table[Opcode::PUSH28.as_usize()] = Some(push("PUSH28", 28));
+	table[Opcode::PUSH29.as_usize()] = Some(push("PUSH29", 29));
+	table[Opcode::PUSH30.as_usize()] = Some(push("PUSH30", 30));
+	table[Opcode::PUSH31.as_usize()] = Some(push("PUSH31", 31));
+	table[Opcode::PUSH32.as_usize()] = Some(push("PUSH32", 32));
+
+	table[Opcode::DUP1.as_usize()] = Some(internal("DUP1", 1, 2, Fork::Frontier));
+	table[Opcode::DUP2.as_usize()] = Some(internal("DUP2", 2, 3, Fork::Frontier));
+	table[Opcode::DUP3.as_usize()] = Some(internal("DUP3", 3, 4, Fork::Frontier));
+	table[Opcode::DUP4.as_usize()] = Some(internal("DUP4", 4, 5, Fork::Frontier));
+	table[Opcode::DUP5.as_usize()] = Some(internal("DUP5", 5, 6, Fork::Frontier));
+	table[Opcode::DUP6.as_usize()] = Some(internal("DUP6", 6, 7, Fork::Frontier));
+	table[Opcode::DUP7.as_usize()] = Some(internal("DUP7", 7, 8, Fork::Frontier));
+	table[Opcode::DUP8.as_usize()] = Some(internal("DUP8", 8, 9, Fork::Frontier));
+	table[Opcode::DUP9.as_usize()] = Some(internal("DUP9", 9, 10, Fork::Frontier));
+	table[Opcode::DUP10.as_usize()] = Some(internal("DUP10", 10, 11, Fork::Frontier));
+	table[Opcode::DUP11.as_usize()] = Some(internal("DUP11", 11, 12, Fork::Frontier));
+	table[Opcode::DUP12.as_usize()] = Some(internal("DUP12", 12, 13, Fork::Frontier));
+	table[Opcode::DUP13.as_usize()] = Some(internal("DUP13", 13, 14, Fork::Frontier));
+	table[Opcode::DUP14.as_usize()] = Some(internal("DUP14", 14, 15, Fork::Frontier));
+	table[Opcode::DUP15.as_usize()] = Some(internal("DUP15", 15, 16, Fork::Frontier));
+	table[Opcode::DUP16.as_usize()] = Some(internal("DUP16", 16, 17, Fork::Frontier));
+
+	table[Opcode::SWAP1.as_usize()] = Some(internal("SWAP1", 2, 2, Fork::Frontier));
+	table[Opcode::SWAP2.as_usize()] = Some(internal("SWAP2", 3, 3, Fork::Frontier));
+	table[Opcode::SWAP3.as_usize()] = Some(internal("SWAP3", 4, 4, Fork::Frontier));
+	table[Opcode::SWAP4.as_usize()] = Some(internal("SWAP4", 5, 5, Fork::Frontier));
+	table[Opcode::SWAP5.as_usize()] = Some(internal("SWAP5", 6, 6, Fork::Frontier));
+	table[Opcode::SWAP6.as_usize()] = Some(internal("SWAP6", 7, 7, Fork::Frontier));
+	table[Opcode::SWAP7.as_usize()] = Some(internal("SWAP7", 8, 8, Fork::Frontier));
+	table[Opcode::SWAP8.as_usize()] = Some(internal("SWAP8", 9, 9, Fork::Frontier));
+	table[Opcode::SWAP9.as_usize()] = Some(internal("SWAP9", 10, 10, Fork::Frontier));
+	table[Opcode::SWAP10.as_usize()] = Some(internal("SWAP10", 11, 11, Fork::Frontier));
+	table[Opcode::SWAP11.as_usize()] = Some(internal("SWAP11", 12, 12, Fork::Frontier));
+	table[Opcode::SWAP12.as_usize()] = Some(internal("SWAP12", 13, 13, Fork::Frontier));
+	table[Opcode::SWAP13.as_usize()] = Some(internal("SWAP13", 14, 14, Fork::Frontier));
+	table[Opcode::SWAP14.as_usize()] = Some(internal("SWAP14", 15, 15, Fork::Frontier));
+	table[Opcode::SWAP15.as_usize()] = Some(internal("SWAP15", 16, 16, Fork::Frontier));
+	table[Opcode::SWAP16.as_usize()] = Some(internal("SWAP16", 17, 17, Fork::Frontier));
+
+	table[Opcode::LOG0.as_usize()] = Some(external("LOG0", 2, 0, Fork::Frontier));
+	table[Opcode::LOG1.as_usize()] = Some(external("LOG1", 3, 0, Fork::Frontier));
+	table[Opcode::LOG2.as_usize()] = Some(external("LOG2", 4, 0, Fork::Frontier));
+	table[Opcode::LOG3.as_usize()] = Some(external("LOG3", 5, 0, Fork::Frontier));
+	table[Opcode::LOG4.as_usize()] = Some(external("LOG4", 6, 0, Fork::Frontier));
+
+	table[Opcode::RJUMP.as_usize()] = Some(eof("RJUMP", 0, 2));
+	table[Opcode::RJUMPI.as_usize()] = Some(eof("RJUMPI", 1, 2));
+	table[Opcode::RJUMPV.as_usize()] = Some(eof("RJUMPV", 1, 1));
+	table[Opcode::CALLF.as_usize()] = Some(eof("CALLF", 0, 2));
+	table[Opcode::RETF.as_usize()] = Some(eof("RETF", 0, 0));
+
+	table[Opcode::CREATE.as_usize()] = Some(external("CREATE", 3, 1, Fork::Frontier));
+	table[Opcode::CALL.as_usize()] = Some(external("CALL", 7, 1, Fork::Frontier));
+	table[Opcode::CALLCODE.as_usize()] = Some(external("CALLCODE", 7, 1, Fork::Frontier));
+	table[Opcode::RETURN.as_usize()] = Some(internal("RETURN", 2, 0, Fork::Frontier));
+	table[Opcode::DELEGATECALL.as_usize()] = Some(external("DELEGATECALL", 6, 1, Fork::Homestead));
+	table[Opcode::CREATE2.as_usize()] = Some(external("CREATE2", 4, 1, Fork::Constantinople));
+	table[Opcode::STATICCALL.as_usize()] = Some(external("STATICCALL", 6, 1, Fork::Byzantium));
+	table[Opcode::REVERT.as_usize()] = Some(internal("REVERT", 2, 0, Fork::Byzantium));
+	table[Opcode::INVALID.as_usize()] = Some(internal("INVALID", 0, 0, Fork::Frontier));
+	table[Opcode::SUICIDE.as_usize()] = Some(external("SUICIDE", 1, 0, Fork::Frontier));
+
+	table
+};
+
+#[cfg(test)]
+mod tests {
+	use alloc::vec;
+	use alloc::vec::Vec;
+	use crate::eval::{eval, Control};
+	use crate::{Fork, Machine, Opcode, H256};
+
+	#[test]
+	fn test_opcode_info() {
+		for byte in 0..=u8::MAX {
+			let opcode = Opcode(byte);
+			let mut machine = Machine::new(vec![byte; 33], Vec::new(), Vec::new(), 1024, 10000);
+			for _ in 0..17 {
+				machine.stack_mut().push(H256::default()).unwrap();
+			}
+
+			let control = eval(&mut machine, opcode, 0);
+			let info = opcode.info();
+			assert_eq!(matches!(control, Control::Trap(_)), info.is_none_or(|info| info.is_external), "{opcode}");
+			if let (Control::Continue(_), Some(info)) = (control, info) {
+				assert_eq!(machine.stack().len() + usize::from(info.inputs), 17 + usize::from(info.outputs), "{opcode}");
+			}
+		}
+
+		let push2 = Opcode::PUSH2.info().unwrap();
+		assert_eq!((push2.inputs, push2.outputs, push2.immediate_size), (0, 1, 2));
+		assert_eq!(Opcode::PUSH0.info().unwrap().fork, Fork::Shanghai);
+		assert!(Opcode::SLOAD.info().unwrap().is_external);
+		assert_eq!(Opcode(0x0c).info(), None);
+	}
+}
@@ -170,3 +170,45 @@ impl Stack {
 		Ok(())
 	}
 }
+
+/// Unchecked operations, for opcodes of a basic block known to fit the stack.
+#[allow(clippy::redundant_pub_crate)]
+impl Stack {
+	/// Pop a value from the stack without checking for underflow. Returns zero
+	/// if the stack is empty.
+	pub(crate) fn pop_unchecked(&mut self) -> H256 {
+		let mut value = H256::default();
+		self.pop_u256_unchecked().into_big_endian_fast(&mut value[..]);
+		value
+	}
+
+	/// Push a new value into the stack without checking the stack limit.
+	pub(crate) fn push_unchecked(&mut self, value: H256) {
+		self.data.push(U256::from_big_endian_fast(&value[..]));
+	}
+
+	/// Pop a value from the stack without checking for underflow. Returns zero
+	/// if the stack is empty.
+	pub(crate) fn pop_u256_unchecked(&mut self) -> U256 {
+		self.data.pop().unwrap_or_default()
+	}
+
+	/// Push a new value into the stack without checking the stack limit.
+	pub(crate) fn push_u256_unchecked(&mut self, value: U256) {
+		self.data.push(value);
+	}
+
+	/// Duplicate a value at given index without checking for underflow or the
+	/// stack limit. Panics if the index is too large.
+	pub(crate) fn dup_unchecked(&mut self, no_from_top: usize) {
+		let index = self.data.len() - no_from_top - 1;
+		self.data.push(self.data[index]);
+	}
+
+	/// Swap a value at given index with the top value without checking for
+	/// underflow. Panics if the index is too large.
+	pub(crate) fn swap_unchecked(&mut self, no_from_top: usize) {
+		let len = self.data.len();
+		self.data.swap(len - no_from_top - 1, len - 1);
+	}
+}
@@ -0,0 +1,327 @@
+use evm_core::{ExitError, U256, H256};
+use evm_runtime::Config;
+use crate::consts::{
+	G_CALLVALUE, G_COPY, G_CREATE, G_EXP, G_INITCODE_WORD, G_LOG, G_LOGDATA, G_LOGTOPIC,
+	G_NEWACCOUNT, G_SHA3, G_SHA3WORD, G_VERYLOW, R_SUICIDE,
+};
+
+/// Number of 32-byte words needed to hold `len` bytes.
+fn words(len: U256) -> U256 {
+	let rem = len % U256::from(32);
+	if rem == U256::zero() {
+		len / U256::from(32)
+	} else {
+		len / U256::from(32) + U256::one()
+	}
+}
+
+/// Refund of a `SUICIDE`, unless the account has already been removed or
+/// the refund has been removed (EIP-3529).
+pub const fn suicide_refund(already_removed: bool, config: &Config) -> i64 {
+	if already_removed || config.decrease_clears_refund {
+		0
+	} else {
+		R_SUICIDE
+	}
+}
+
+/// Refund of an `SSTORE`, following EIP-2200 if `sstore_gas_metering` is set.
+#[allow(clippy::collapsible_else_if, clippy::cast_possible_wrap)]
+pub fn sstore_refund(original: H256, current: H256, new: H256, config: &Config) -> i64 {
+	if config.sstore_gas_metering {
+		if current == new {
+			0
+		} else {
+			if original == current && new == H256::default() {
+				config.refund_sstore_clears
+			} else {
+				let mut refund = 0;
+
+				if original != H256::default() {
+					if current == H256::default() {
+						refund -= config.refund_sstore_clears;
+					} else if new == H256::default() {
+						refund += config.refund_sstore_clears;
+					}
+				}
+
+				if original == new {
+					if original == H256::default() {
+						refund += (config.gas_sstore_set - config.gas_sload) as i64;
+					} else {
+						refund += (config.gas_sstore_reset - config.gas_sload) as i64;
+					}
+				}
+
+				refund
+			}
+		}
+	} else {
+		if current != H256::default() && new == H256::default() {
+			config.refund_sstore_clears
+		} else {
+			0
+		}
+	}
+}
+
+/// Cost of accessing an account, which is higher for the first access in a
+/// transaction once EIP-2929 is enabled.
+pub const fn address_access_cost(target_is_cold: bool, regular_value: u64, config: &Config) -> u64 {
+	if config.increase_state_access_gas && target_is_cold {
+		config.gas_account_access_cold
+	} else {
+		regular_value
+	}
+}
+
+/// Cost of `SLOAD`, depending on whether the slot is cold (EIP-2929).
+pub const fn sload_cost(target_is_cold: bool, config: &Config) -> u64 {
+	if config.increase_state_access_gas && target_is_cold {
+		config.gas_sload_cold
+	} else {
+		config.gas_sload
+	}
+}
+
+/// Cost of initcode of `len` bytes for `CREATE` and `CREATE2`, rejecting
+/// initcode above `max_initcode_size` (EIP-3860).
+pub fn initcode_cost(len: U256, config: &Config) -> Result<u64, ExitError> {
+	match config.max_initcode_size {
+		Some(max_initcode_size) => {
+			if len > U256::from(max_initcode_size) {
+				return Err(ExitError::CreateContractLimit)
+			}
+
+			Ok(G_INITCODE_WORD * words(len).as_u64())
+		},
+		None => Ok(0),
+	}
+}
+
+/// Cost of `CREATE`, including the initcode cost.
+pub fn create_cost(len: U256, config: &Config) -> Result<u64, ExitError> {
+	Ok(G_CREATE + initcode_cost(len, config)?)
+}
+
+/// Cost of `CREATE2`, which has to hash the init code.
+pub fn create2_cost(len: U256, config: &Config) -> Result<u64, ExitError> {
+	let base = U256::from(G_CREATE + initcode_cost(len, config)?);
+	let sha_addup = U256::from(G_SHA3WORD).checked_mul(words(len))
+		.ok_or(ExitError::OutOfGas)?;
+	let gas = base.checked_add(sha_addup).ok_or(ExitError::OutOfGas)?;
+
+	if gas > U256::from(u64::MAX) {
+		return Err(ExitError::OutOfGas)
+	}
+
+	Ok(gas.as_u64())
+}
+
+/// Cost of `EXP`, charged per byte of the exponent.
+pub fn exp_cost(power: U256, config: &Config) -> Result<u64, ExitError> {
+	if power == U256::zero() {
+		Ok(G_EXP)
+	} else {
+		let gas = U256::from(G_EXP)
+			.checked_add(
+				U256::from(config.gas_expbyte)
+					.checked_mul(U256::from(power.bits().div_ceil(8)))
+					.ok_or(ExitError::OutOfGas)?
+			)
+			.ok_or(ExitError::OutOfGas)?;
+
+		if gas > U256::from(u64::MAX) {
+			return Err(ExitError::OutOfGas)
+		}
+
+		Ok(gas.as_u64())
+	}
+}
+
+/// Cost of a copy operation of `len` bytes, on top of `G_VERYLOW`.
+pub fn verylowcopy_cost(len: U256) -> Result<u64, ExitError> {
+	let gas = U256::from(G_VERYLOW).checked_add(
+		U256::from(G_COPY).checked_mul(words(len)).ok_or(ExitError::OutOfGas)?
+	).ok_or(ExitError::OutOfGas)?;
+
+	if gas > U256::from(u64::MAX) {
+		return Err(ExitError::OutOfGas)
+	}
+
+	Ok(gas.as_u64())
+}
+
+/// Cost of `EXTCODECOPY` of `len` bytes.
+pub fn extcodecopy_cost(len: U256, target_is_cold: bool, config: &Config) -> Result<u64, ExitError> {
+	let gas = U256::from(address_access_cost(target_is_cold, config.gas_ext_code, config)).checked_add(
+		U256::from(G_COPY).checked_mul(words(len)).ok_or(ExitError::OutOfGas)?
+	).ok_or(ExitError::OutOfGas)?;
+
+	if gas > U256::from(u64::MAX) {
+		return Err(ExitError::OutOfGas)
+	}
+
+	Ok(gas.as_u64())
+}
+
+/// Cost of `LOGn` with `len` bytes of data.
+pub fn log_cost(n: u8, len: U256) -> Result<u64, ExitError> {
+	let gas = U256::from(G_LOG)
+		.checked_add(U256::from(G_LOGDATA).checked_mul(len).ok_or(ExitError::OutOfGas)?)
+		.ok_or(ExitError::OutOfGas)?
+		.checked_add(U256::from(G_LOGTOPIC * u64::from(n)))
+		.ok_or(ExitError::OutOfGas)?;
+
+	if gas > U256::from(u64::MAX) {
+		return Err(ExitError::OutOfGas)
+	}
+
+	Ok(gas.as_u64())
+}
+
+/// Cost of `SHA3` over `len` bytes.
+pub fn sha3_cost(len: U256) -> Result<u64, ExitError> {
+	let gas = U256::from(G_SHA3).checked_add(
+		U256::from(G_SHA3WORD).checked_mul(words(len)).ok_or(ExitError::OutOfGas)?
+	).ok_or(ExitError::OutOfGas)?;
+
+	if gas > U256::from(u64::MAX) {
+		return Err(ExitError::OutOfGas)
+	}
+
+	Ok(gas.as_u64())
+}
+
+/// Cost of an `SSTORE`, following EIP-2200 if `sstore_gas_metering` is set,
+/// plus the cold slot surcharge of EIP-2929.
+pub fn sstore_cost(
+	original: H256,
+	current: H256,
+	new: H256,
+	gas: u64,
+	target_is_cold: bool,
+	config: &Config,
+) -> Result<u64, ExitError> {
+	if config.sstore_gas_metering {
+		if config.sstore_revert_under_stipend && gas <= config.call_stipend {
+			return Err(ExitError::OutOfGas)
+		}
+
+		let cold_surcharge = if config.increase_state_access_gas && target_is_cold {
+			config.gas_sload_cold
+		} else {
+			0
+		};
+
+		Ok(cold_surcharge + if new == current {
+			config.gas_sload
+		} else if original == current {
+			if original == H256::zero() {
+				config.gas_sstore_set
+			} else {
+				config.gas_sstore_reset
+			}
+		} else {
+			config.gas_sload
+		})
+	} else {
+		Ok(if current == H256::zero() && new != H256::zero() {
+			config.gas_sstore_set
+		} else {
+			config.gas_sstore_reset
+		})
+	}
+}
+
+/// Cost of a `SUICIDE`, including the new account charge for the beneficiary
+/// and the cold access charge of EIP-2929.
+pub fn suicide_cost(value: U256, target_is_cold: bool, target_exists: bool, config: &Config) -> u64 {
+	let eip161 = !config.empty_considered_exists;
+	let should_charge_topup = if eip161 {
+		value != U256::zero() && !target_exists
+	} else {
+		!target_exists
+	};
+
+	let suicide_gas_topup = if should_charge_topup {
+		config.gas_suicide_new_account
+	} else {
+		0
+	};
+
+	let cold_surcharge = if config.increase_state_access_gas && target_is_cold {
+		config.gas_account_access_cold
+	} else {
+		0
+	};
+
+	config.gas_suicide + suicide_gas_topup + cold_surcharge
+}
+
+/// Cost of the `CALL` family, without the gas forwarded to the callee.
+#[allow(clippy::fn_params_excessive_bools)]
+pub fn call_cost(
+	value: U256,
+	is_call_or_callcode: bool,
+	is_call_or_staticcall: bool,
+	new_account: bool,
+	target_is_cold: bool,
+	config: &Config,
+) -> u64 {
+	let transfers_value = value != U256::default();
+	address_access_cost(target_is_cold, config.gas_call, config) +
+		xfer_cost(is_call_or_callcode, transfers_value) +
+		new_cost(is_call_or_staticcall, new_account, transfers_value, config)
+}
+
+/// Cost of loading the code of the account a call target delegates to, if it
+/// is delegated (EIP-7702).
+pub const fn delegation_cost(delegate_is_cold: Option<bool>, config: &Config) -> u64 {
+	match delegate_is_cold {
+		Some(is_cold) => address_access_cost(is_cold, config.gas_sload, config),
+		None => 0,
+	}
+}
+
+/// Additional check of the requested call gas, for forks without the
+/// all-but-one-64th rule (EIP-150).
+pub fn call_extra_check(gas: U256, after_gas: u64, config: &Config) -> Result<(), ExitError> {
+	if config.err_on_call_with_more_gas && U256::from(after_gas) < gas {
+		Err(ExitError::OutOfGas)
+	} else {
+		Ok(())
+	}
+}
+
+const fn xfer_cost(is_call_or_callcode: bool, transfers_value: bool) -> u64 {
+	if is_call_or_callcode && transfers_value {
+		G_CALLVALUE
+	} else {
+		0
+	}
+}
+
+const fn new_cost(
+	is_call_or_staticcall: bool,
+	new_account: bool,
+	transfers_value: bool,
+	config: &Config,
+) -> u64 {
+	let eip161 = !config.empty_considered_exists;
+	if is_call_or_staticcall {
+		if eip161 {
+			if transfers_value && new_account {
+				G_NEWACCOUNT
+			} else {
+				0
+			}
+		} else if new_account {
+			G_NEWACCOUNT
+		} else {
+			0
+		}
+	} else {
+		0
+	}
+}
@@ -0,0 +1,1033 @@
+//! EVM gasometer.
+
+#![deny(warnings)]
+#![forbid(unsafe_code, unused_variables)]
+#![deny(clippy::all, clippy::pedantic, clippy::nursery)]
+#![allow(
+	clippy::module_name_repetitions,
+	clippy::missing_errors_doc,
+	clippy::missing_panics_doc
+)]
+#![cfg_attr(not(feature = "std"), no_std)]
+#![forbid(unused_imports)]
+
+extern crate alloc;
+
+mod consts;
+mod costs;
+
+use alloc::vec::Vec;
+use core::cmp::max;
+use evm_core::{Opcode, ExitError, Memory, Stack, H160, H256, U256};
+use evm_runtime::{Handler, Config};
+
+macro_rules! try_or_fail {
+	( $inner:expr, $e:expr ) => (
+		match $e {
+			Ok(value) => value,
+			Err(e) => {
+				$inner = Err(e);
+				return Err(e)
+			},
+		}
+	)
+}
+
+/// EVM gasometer.
+#[derive(Clone, Debug)]
+pub struct Gasometer<'config> {
+	gas_limit: u64,
+	config: &'config Config,
+	inner: Result<Inner<'config>, ExitError>,
+}
+
+impl<'config> Gasometer<'config> {
+	/// Create a new gasometer with given gas limit and config.
+	#[must_use]
+	pub const fn new(gas_limit: u64, config: &'config Config) -> Self {
+		Self {
+			gas_limit,
+			config,
+			inner: Ok(Inner {
+				memory_gas: 0,
+				used_gas: 0,
+				refunded_gas: 0,
+				config,
+			}),
+		}
+	}
+
+	/// Reference of the config.
+	#[must_use]
+	pub const fn config(&self) -> &'config Config {
+		self.config
+	}
+
+	/// Gas limit of the gasometer.
+	#[must_use]
+	pub const fn gas_limit(&self) -> u64 {
+		self.gas_limit
+	}
+
+	/// Remaining gas.
+	#[must_use]
+	pub const fn gas(&self) -> u64 {
+		match &self.inner {
+			Ok(inner) => self.gas_limit - inner.used_gas - inner.memory_gas,
+			Err(_) => 0,
+		}
+	}
+
+	/// Total used gas.
+	#[must_use]
+	pub const fn total_used_gas(&self) -> u64 {
+		match &self.inner {
+			Ok(inner) => inner.used_gas + inner.memory_gas,
+			Err(_) => self.gas_limit,
+		}
+	}
+
+	/// Refunded gas.
+	#[must_use]
+	pub const fn refunded_gas(&self) -> i64 {
+		match &self.inner {
+			Ok(inner) => inner.refunded_gas,
+			Err(_) => 0,
+		}
+	}
+
+	/// Explicitly fail the gasometer with out of gas. Return `OutOfGas` error.
+	pub const fn fail(&mut self) -> ExitError {
+		self.inner = Err(ExitError::OutOfGas);
+		ExitError::OutOfGas
+	}
+
+	/// Record an explicit cost.
+	pub fn record_cost(&mut self, cost: u64) -> Result<(), ExitError> {
+		let all_gas_cost = self.total_used_gas().checked_add(cost);
+		if let Some(all_gas_cost) = all_gas_cost {
+			if self.gas_limit < all_gas_cost {
+				self.inner = Err(ExitError::OutOfGas);
+				return Err(ExitError::OutOfGas)
+			}
+		} else {
+			self.inner = Err(ExitError::OutOfGas);
+			return Err(ExitError::OutOfGas)
+		}
+
+		let inner = self.inner_mut()?;
+		inner.used_gas += cost;
+		Ok(())
+	}
+
+	/// Record an explicit refund.
+	pub fn record_refund(&mut self, refund: i64) -> Result<(), ExitError> {
+		self.inner_mut()?.refunded_gas += refund;
+		Ok(())
+	}
+
+	/// Record `CREATE` code deposit.
+	pub fn record_deposit(&mut self, len: usize) -> Result<(), ExitError> {
+		let cost = (len as u64).checked_mul(consts::G_CODEDEPOSIT).ok_or(ExitError::OutOfGas)?;
+		self.record_cost(cost)
+	}
+
+	/// Record opcode gas cost.
+	pub fn record_dynamic_cost(
+		&mut self,
+		cost: GasCost,
+		memory: Option<MemoryCost>,
+	) -> Result<(), ExitError> {
+		let gas = self.gas();
+
+		let memory_gas = match memory {
+			Some(memory) => try_or_fail!(self.inner, self.inner_mut()?.memory_gas(memory)),
+			None => self.inner_mut()?.memory_gas,
+		};
+		let gas_cost = try_or_fail!(self.inner, self.inner_mut()?.gas_cost(cost, gas));
+		let gas_refund = self.inner_mut()?.gas_refund(cost);
+		let used_gas = self.inner_mut()?.used_gas;
+
+		let all_gas_cost = memory_gas.checked_add(used_gas.saturating_add(gas_cost));
+		if let Some(all_gas_cost) = all_gas_cost {
+			if self.gas_limit < all_gas_cost {
+				self.inner = Err(ExitError::OutOfGas);
+				return Err(ExitError::OutOfGas)
+			}
+		} else {
+			self.inner = Err(ExitError::OutOfGas);
+			return Err(ExitError::OutOfGas)
+		}
+
+		let after_gas = self.gas_limit - all_gas_cost.unwrap_or_default();
+		try_or_fail!(self.inner, self.inner_mut()?.extra_check(cost, after_gas));
+
+		let inner = self.inner_mut()?;
+		inner.used_gas += gas_cost;
+		inner.memory_gas = memory_gas;
+		inner.refunded_gas += gas_refund;
+
+		Ok(())
+	}
+
+	/// Record opcode stipend.
+	pub fn record_stipend(&mut self, stipend: u64) -> Result<(), ExitError> {
+		let inner = self.inner_mut()?;
+		inner.used_gas = inner.used_gas.saturating_sub(stipend);
+		Ok(())
+	}
+
+	/// Record transaction cost.
+	pub fn record_transaction(&mut self, cost: TransactionCost) -> Result<(), ExitError> {
+		let gas_cost = match cost {
+			TransactionCost::Call {
+				zero_data_len, non_zero_data_len,
+				access_list_address_len, access_list_storage_len,
+				authorization_list_len,
+			} => {
+				self.config.gas_transaction_call
+					.saturating_add(data_cost(zero_data_len, non_zero_data_len, self.config))
+					.saturating_add(access_list_cost(access_list_address_len, access_list_storage_len, self.config))
+					.saturating_add((authorization_list_len as u64).saturating_mul(self.config.gas_per_empty_account_cost))
+			},
+			TransactionCost::Create {
+				zero_data_len, non_zero_data_len,
+				access_list_address_len, access_list_storage_len,
+			} => {
+				let initcode_cost = try_or_fail!(self.inner, costs::initcode_cost(
+					U256::from(zero_data_len + non_zero_data_len),
+					self.config,
+				));
+
+				self.config.gas_transaction_create
+					.saturating_add(data_cost(zero_data_len, non_zero_data_len, self.config))
+					.saturating_add(access_list_cost(access_list_address_len, access_list_storage_len, self.config))
+					.saturating_add(initcode_cost)
+			},
+		};
+
+		self.record_cost(gas_cost)
+	}
+
+	fn inner_mut(&mut self) -> Result<&mut Inner<'config>, ExitError> {
+		self.inner.as_mut().map_err(|e| *e)
+	}
+}
+
+/// Calculate the call transaction cost.
+#[must_use]
+pub fn call_transaction_cost(data: &[u8], access_list: &[(H160, Vec<H256>)]) -> TransactionCost {
+	set_code_transaction_cost(data, access_list, 0)
+}
+
+/// Calculate the cost of a set code transaction (EIP-7702), a call
+/// transaction with `authorization_list_len` authorizations.
+#[must_use]
+#[allow(clippy::naive_bytecount)]
+pub fn set_code_transaction_cost(
+	data: &[u8],
+	access_list: &[(H160, Vec<H256>)],
+	authorization_list_len: usize,
+) -> TransactionCost {
+	let zero_data_len = data.iter().filter(|v| **v == 0).count();
+	let non_zero_data_len = data.len() - zero_data_len;
+	let (access_list_address_len, access_list_storage_len) = count_access_list(access_list);
+
+	TransactionCost::Call {
+		zero_data_len,
+		non_zero_data_len,
+		access_list_address_len,
+		access_list_storage_len,
+		authorization_list_len,
+	}
+}
+
+/// Calculate the create transaction cost.
+#[must_use]
+#[allow(clippy::naive_bytecount)]
+pub fn create_transaction_cost(data: &[u8], access_list: &[(H160, Vec<H256>)]) -> TransactionCost {
+	let zero_data_len = data.iter().filter(|v| **v == 0).count();
+	let non_zero_data_len = data.len() - zero_data_len;
+	let (access_list_address_len, access_list_storage_len) = count_access_list(access_list);
+
+	TransactionCost::Create {
+		zero_data_len,
+		non_zero_data_len,
+		access_list_address_len,
+		access_list_storage_len,
+	}
+}
+
+/// Minimum gas a transaction is charged for its data (EIP-7623), or zero if
+/// the floor is not enabled.
+#[must_use]
+pub const fn transaction_floor_cost(cost: TransactionCost, config: &Config) -> u64 {
+	if !config.has_floor_gas {
+		return 0
+	}
+
+	let (zero_data_len, non_zero_data_len) = match cost {
+		TransactionCost::Call { zero_data_len, non_zero_data_len, .. } |
+		TransactionCost::Create { zero_data_len, non_zero_data_len, .. } =>
+			(zero_data_len as u64, non_zero_data_len as u64),
+	};
+	let tokens = zero_data_len.saturating_add(non_zero_data_len.saturating_mul(4));
+
+	config.gas_transaction_call.saturating_add(tokens.saturating_mul(config.total_cost_floor_per_token))
+}
+
+fn count_access_list(access_list: &[(H160, Vec<H256>)]) -> (usize, usize) {
+	let access_list_address_len = access_list.len();
+	let access_list_storage_len = access_list.iter().map(|(_, keys)| keys.len()).sum();
+
+	(access_list_address_len, access_list_storage_len)
+}
+
+/// Cost of the transaction data, saturating on overflow.
+const fn data_cost(zero_data_len: usize, non_zero_data_len: usize, config: &Config) -> u64 {
+	(zero_data_len as u64).saturating_mul(config.gas_transaction_zero_data)
+		.saturating_add((non_zero_data_len as u64).saturating_mul(config.gas_transaction_non_zero_data))
+}
+
+/// Cost of the transaction access list (EIP-2930), saturating on overflow.
+const fn access_list_cost(address_len: usize, storage_len: usize, config: &Config) -> u64 {
+	(address_len as u64).saturating_mul(config.gas_access_list_address)
+		.saturating_add((storage_len as u64).saturating_mul(config.gas_access_list_storage_key))
+}
+
+/// Static gas cost of an opcode, or `None` if the cost depends on the
+/// operands or on the state.
+#[must_use]
+pub fn static_opcode_cost(opcode: Opcode) -> Option<u64> {
+	static TABLE: [Option<u64>; 256] = {
+		let mut table = [None; 256];
+
+		table[Opcode::STOP.as_usize()] = Some(consts::G_ZERO);
+		table[Opcode::CALLDATASIZE.as_usize()] = Some(consts::G_BASE);
+		table[Opcode::CODESIZE.as_usize()] = Some(consts::G_BASE);
+		table[Opcode::POP.as_usize()] = Some(consts::G_BASE);
+		table[Opcode::PC.as_usize()] = Some(consts::G_BASE);
+		table[Opcode::MSIZE.as_usize()] = Some(consts::G_BASE);
+
+		table[Opcode::ADDRESS.as_usize()] = Some(consts::G_BASE);
+		table[Opcode::ORIGIN.as_usize()] = Some(consts::G_BASE);
+		table[Opcode::CALLER.as_usize()] = Some(consts::G_BASE);
+		table[Opcode::CALLVALUE.as_usize()] = Some(consts::G_BASE);
+		table[Opcode::COINBASE.as_usize()] = Some(consts::G_BASE);
+		table[Opcode::TIMESTAMP.as_usize()] = Some(consts::G_BASE);
+		table[Opcode::NUMBER.as_usize()] = Some(consts::G_BASE);
+		table[Opcode::DIFFICULTY.as_usize()] = Some(consts::G_BASE);
+		table[Opcode::GASLIMIT.as_usize()] = Some(consts::G_BASE);
+		table[Opcode::GASPRICE.as_usize()] = Some(consts::G_BASE);
+		table[Opcode::GAS.as_usize()] = Some(consts::G_BASE);
+
+		table[Opcode::ADD.as_usize()] = Some(consts::G_VERYLOW);
+		table[Opcode::SUB.as_usize()] = Some(consts::G_VERYLOW);
+		table[Opcode::NOT.as_usize()] = Some(consts::G_VERYLOW);
+		table[Opcode::LT.as_usize()] = Some(consts::G_VERYLOW);
+		table[Opcode::GT.as_usize()] = Some(consts::G_VERYLOW);
+		table[Opcode::SLT.as_usize()] = Some(consts::G_VERYLOW);
+		table[Opcode::SGT.as_usize()] = Some(consts::G_VERYLOW);
+		table[Opcode::EQ.as_usize()] = Some(consts::G_VERYLOW);
+		table[Opcode::ISZERO.as_usize()] = Some(consts::G_VERYLOW);
+		table[Opcode::AND.as_usize()] = Some(consts::G_VERYLOW);
+		table[Opcode::OR.as_usize()] = Some(consts::G_VERYLOW);
+		table[Opcode::XOR.as_usize()] = Some(consts::G_VERYLOW);
+		table[Opcode::BYTE.as_usize()] = Some(consts::G_VERYLOW);
+		table[Opcode::CALLDATALOAD.as_usize()] = Some(consts::G_VERYLOW);
+
+		let mut i = Opcode::PUSH1.as_usize();
+		while i <= Opcode::SWAP16.as_usize() {
+			table[i] = Some(consts::G_VERYLOW);
+			i += 1;
+		}
+
+		table[Opcode::MUL.as_usize()] = Some(consts::G_LOW);
+		table[Opcode::DIV.as_usize()] = Some(consts::G_LOW);
+		table[Opcode::SDIV.as_usize()] = Some(consts::G_LOW);
+		table[Opcode::MOD.as_usize()] = Some(consts::G_LOW);
+		table[Opcode::SMOD.as_usize()] = Some(consts::G_LOW);
+		table[Opcode::SIGNEXTEND.as_usize()] = Some(consts::G_LOW);
+
+		table[Opcode::ADDMOD.as_usize()] = Some(consts::G_MID);
+		table[Opcode::MULMOD.as_usize()] = Some(consts::G_MID);
+		table[Opcode::JUMP.as_usize()] = Some(consts::G_MID);
+
+		table[Opcode::JUMPI.as_usize()] = Some(consts::G_HIGH);
+		table[Opcode::JUMPDEST.as_usize()] = Some(consts::G_JUMPDEST);
+
+		table[Opcode::RJUMP.as_usize()] = Some(consts::G_BASE);
+		table[Opcode::RJUMPI.as_usize()] = Some(consts::G_RJUMPI);
+		table[Opcode::RJUMPV.as_usize()] = Some(consts::G_RJUMPI);
+		table[Opcode::CALLF.as_usize()] = Some(consts::G_LOW);
+		table[Opcode::RETF.as_usize()] = Some(consts::G_VERYLOW);
+
+		table
+	};
+
+	TABLE[opcode.as_usize()]
+}
+
+/// Calculate the opcode cost, and the account or storage slot it accesses.
+///
+/// The handler is only queried for state-dependent costs (`SSTORE`,
+/// `SUICIDE`, account accesses and the `CALL` family), so it is fine to call
+/// this from `Handler::pre_validate` before recording the result into the
+/// gasometer owned by the same handler. With EIP-2929 enabled, the returned
+/// `StorageTarget` should be marked as warm afterwards.
+#[allow(clippy::too_many_lines, clippy::match_same_arms)]
+pub fn dynamic_opcode_cost<H: Handler>(
+	address: H160,
+	opcode: Opcode,
+	stack: &Stack,
+	is_static: bool,
+	config: &Config,
+	handler: &H,
+) -> Result<(GasCost, StorageTarget, Option<MemoryCost>), ExitError> {
+	let mut storage_target = StorageTarget::None;
+	let mut access_address = |target: H160| {
+		storage_target = StorageTarget::Address(target);
+		handler.is_cold(target, None)
+	};
+	let delegate_is_cold = |target: H160| {
+		handler.delegated_address(target).map(|delegate| handler.is_cold(delegate, None))
+	};
+
+	let gas_cost = match opcode {
+		Opcode::RETURN => GasCost::Zero,
+
+		Opcode::MLOAD | Opcode::MSTORE | Opcode::MSTORE8 => GasCost::VeryLow,
+
+		Opcode::REVERT if config.has_revert => GasCost::Zero,
+		Opcode::REVERT => GasCost::Invalid(opcode),
+
+		Opcode::CHAINID if config.has_chain_id => GasCost::Base,
+		Opcode::CHAINID => GasCost::Invalid(opcode),
+
+		Opcode::SHL | Opcode::SHR | Opcode::SAR if config.has_bitwise_shifting =>
+			GasCost::VeryLow,
+		Opcode::SHL | Opcode::SHR | Opcode::SAR => GasCost::Invalid(opcode),
+
+		Opcode::SELFBALANCE if config.has_self_balance => GasCost::Low,
+		Opcode::SELFBALANCE => GasCost::Invalid(opcode),
+
+		Opcode::BASEFEE if config.has_base_fee => GasCost::Base,
+		Opcode::BASEFEE => GasCost::Invalid(opcode),
+
+		Opcode::BLOBHASH if config.has_blob_opcodes => GasCost::VeryLow,
+		Opcode::BLOBBASEFEE if config.has_blob_opcodes => GasCost::Base,
+		Opcode::BLOBHASH | Opcode::BLOBBASEFEE => GasCost::Invalid(opcode),
+
+		Opcode::PUSH0 if config.has_push0 => GasCost::Base,
+		Opcode::PUSH0 => GasCost::Invalid(opcode),
+
+		Opcode::EXTCODESIZE => GasCost::ExtCodeSize {
+			target_is_cold: access_address(stack.peek(0)?.into()),
+		},
+		Opcode::BALANCE => GasCost::Balance {
+			target_is_cold: access_address(stack.peek(0)?.into()),
+		},
+		Opcode::BLOCKHASH => GasCost::BlockHash,
+		Opcode::EXTCODEHASH if config.has_ext_code_hash => GasCost::ExtCodeHash {
+			target_is_cold: access_address(stack.peek(0)?.into()),
+		},
+		Opcode::EXTCODEHASH => GasCost::Invalid(opcode),
+
+		Opcode::CALLCODE => {
+			let target = stack.peek(1)?.into();
+			GasCost::CallCode {
+				value: stack.peek(2)?,
+				gas: stack.peek(0)?,
+				target_is_cold: access_address(target),
+				target_exists: handler.exists(target),
+				delegate_is_cold: delegate_is_cold(target),
+			}
+		},
+		Opcode::STATICCALL => {
+			let target = stack.peek(1)?.into();
+			GasCost::StaticCall {
+				gas: stack.peek(0)?,
+				target_is_cold: access_address(target),
+				target_exists: handler.exists(target),
+				delegate_is_cold: delegate_is_cold(target),
+			}
+		},
+		Opcode::SHA3 => GasCost::Sha3 { len: stack.peek(1)? },
+		Opcode::EXTCODECOPY => GasCost::ExtCodeCopy {
+			target_is_cold: access_address(stack.peek(0)?.into()),
+			len: stack.peek(3)?,
+		},
+		Opcode::CALLDATACOPY | Opcode::CODECOPY => GasCost::VeryLowCopy { len: stack.peek(2)? },
+		Opcode::MCOPY if config.has_mcopy => GasCost::VeryLowCopy { len: stack.peek(2)? },
+		Opcode::MCOPY => GasCost::Invalid(opcode),
+		Opcode::EXP => GasCost::Exp { power: stack.peek(1)? },
+		Opcode::SLOAD => {
+			let index = stack.peek(0)?;
+			storage_target = StorageTarget::Slot(address, index);
+			GasCost::SLoad { target_is_cold: handler.is_cold(address, Some(index)) }
+		},
+		Opcode::TLOAD if config.has_transient_storage => GasCost::WarmAccess,
+		Opcode::TSTORE if config.has_transient_storage && !is_static => GasCost::WarmAccess,
+		Opcode::TLOAD | Opcode::TSTORE if !config.has_transient_storage => GasCost::Invalid(opcode),
+
+		Opcode::DELEGATECALL if config.has_delegate_call => {
+			let target = stack.peek(1)?.into();
+			GasCost::DelegateCall {
+				gas: stack.peek(0)?,
+				target_is_cold: access_address(target),
+				target_exists: handler.exists(target),
+				delegate_is_cold: delegate_is_cold(target),
+			}
+		},
+		Opcode::DELEGATECALL => GasCost::Invalid(opcode),
+
+		Opcode::RETURNDATASIZE if config.has_return_data => GasCost::Base,
+		Opcode::RETURNDATACOPY if config.has_return_data => GasCost::VeryLowCopy {
+			len: stack.peek(2)?,
+		},
+		Opcode::RETURNDATASIZE | Opcode::RETURNDATACOPY => GasCost::Invalid(opcode),
+
+		Opcode::SSTORE if !is_static => {
+			let index = stack.peek(0)?;
+			let value = stack.peek(1)?;
+			storage_target = StorageTarget::Slot(address, index);
+
+			GasCost::SStore {
+				original: H256::from(handler.original_storage(address, index)),
+				current: H256::from(handler.storage(address, index)),
+				new: H256::from(value),
+				target_is_cold: handler.is_cold(address, Some(index)),
+			}
+		},
+		Opcode::LOG0 if !is_static => GasCost::Log { n: 0, len: stack.peek(1)? },
+		Opcode::LOG1 if !is_static => GasCost::Log { n: 1, len: stack.peek(1)? },
+		Opcode::LOG2 if !is_static => GasCost::Log { n: 2, len: stack.peek(1)? },
+		Opcode::LOG3 if !is_static => GasCost::Log { n: 3, len: stack.peek(1)? },
+		Opcode::LOG4 if !is_static => GasCost::Log { n: 4, len: stack.peek(1)? },
+		Opcode::CREATE if !is_static => GasCost::Create { len: stack.peek(2)? },
+		Opcode::CREATE2 if !config.has_create2 => GasCost::Invalid(opcode),
+		Opcode::CREATE2 if !is_static => GasCost::Create2 {
+			len: stack.peek(2)?,
+		},
+		Opcode::SUICIDE if !is_static => {
+			let target = stack.peek(0)?.into();
+			GasCost::Suicide {
+				value: handler.balance(address),
+				target_is_cold: access_address(target),
+				target_exists: handler.exists(target),
+				already_removed: handler.deleted(address),
+			}
+		},
+		Opcode::CALL
+			if !is_static || stack.peek(2)? == U256::zero() =>
+		{
+			let target = stack.peek(1)?.into();
+			GasCost::Call {
+				value: stack.peek(2)?,
+				gas: stack.peek(0)?,
+				target_is_cold: access_address(target),
+				target_exists: handler.exists(target),
+				delegate_is_cold: delegate_is_cold(target),
+			}
+		},
+
+		Opcode::SSTORE | Opcode::TSTORE | Opcode::LOG0 | Opcode::LOG1 | Opcode::LOG2 |
+		Opcode::LOG3 | Opcode::LOG4 | Opcode::CREATE | Opcode::CREATE2 | Opcode::SUICIDE |
+		Opcode::CALL if is_static => return Err(ExitError::StaticModeViolation),
+
+		_ => GasCost::Invalid(opcode),
+	};
+
+	let memory_cost = match opcode {
+		Opcode::SHA3 | Opcode::RETURN | Opcode::REVERT |
+		Opcode::LOG0 | Opcode::LOG1 | Opcode::LOG2 |
+		Opcode::LOG3 | Opcode::LOG4 => Some(MemoryCost {
+			offset: stack.peek(0)?,
+			len: stack.peek(1)?,
+		}),
+
+		Opcode::CODECOPY | Opcode::CALLDATACOPY |
+		Opcode::RETURNDATACOPY => Some(MemoryCost {
+			offset: stack.peek(0)?,
+			len: stack.peek(2)?,
+		}),
+
+		Opcode::MCOPY => Some(MemoryCost {
+			offset: max(stack.peek(0)?, stack.peek(1)?),
+			len: stack.peek(2)?,
+		}),
+
+		Opcode::EXTCODECOPY => Some(MemoryCost {
+			offset: stack.peek(1)?,
+			len: stack.peek(3)?,
+		}),
+
+		Opcode::MLOAD | Opcode::MSTORE => Some(MemoryCost {
+			offset: stack.peek(0)?,
+			len: U256::from(32),
+		}),
+
+		Opcode::MSTORE8 => Some(MemoryCost {
+			offset: stack.peek(0)?,
+			len: U256::from(1),
+		}),
+
+		Opcode::CREATE | Opcode::CREATE2 => Some(MemoryCost {
+			offset: stack.peek(1)?,
+			len: stack.peek(2)?,
+		}),
+
+		Opcode::CALL | Opcode::CALLCODE => Some(MemoryCost {
+			offset: stack.peek(3)?,
+			len: stack.peek(4)?,
+		}.join(MemoryCost {
+			offset: stack.peek(5)?,
+			len: stack.peek(6)?,
+		})),
+
+		Opcode::DELEGATECALL |
+		Opcode::STATICCALL => Some(MemoryCost {
+			offset: stack.peek(2)?,
+			len: stack.peek(3)?,
+		}.join(MemoryCost {
+			offset: stack.peek(4)?,
+			len: stack.peek(5)?,
+		})),
+
+		_ => None,
+	};
+
+	Ok((gas_cost, storage_target, memory_cost))
+}
+
+/// Holds the gas consumption for a Gasometer instance.
+#[derive(Clone, Debug)]
+struct Inner<'config> {
+	memory_gas: u64,
+	used_gas: u64,
+	refunded_gas: i64,
+	config: &'config Config,
+}
+
+impl Inner<'_> {
+	fn memory_gas(&self, memory: MemoryCost) -> Result<u64, ExitError> {
+		let from = memory.offset;
+		let len = memory.len;
+
+		if len == U256::zero() {
+			return Ok(self.memory_gas)
+		}
+
+		let end = from.checked_add(len).ok_or(ExitError::OutOfGas)?;
+
+		if end > U256::from(usize::MAX) {
+			return Err(ExitError::OutOfGas)
+		}
+		let end = end.as_usize();
+
+		let rem = end % 32;
+		let new = if rem == 0 {
+			end / 32
+		} else {
+			end / 32 + 1
+		};
+
+		Ok(max(self.memory_gas, Memory::cost(new)?))
+	}
+
+	fn extra_check(&self, cost: GasCost, after_gas: u64) -> Result<(), ExitError> {
+		match cost {
+			GasCost::Call { gas, .. } |
+			GasCost::CallCode { gas, .. } |
+			GasCost::DelegateCall { gas, .. } |
+			GasCost::StaticCall { gas, .. } => costs::call_extra_check(gas, after_gas, self.config),
+			_ => Ok(()),
+		}
+	}
+
+	/// Returns the gas cost numerical value.
+	fn gas_cost(
+		&self,
+		cost: GasCost,
+		gas: u64,
+	) -> Result<u64, ExitError> {
+		Ok(match cost {
+			GasCost::Call { value, target_is_cold, target_exists, delegate_is_cold, .. } =>
+				costs::call_cost(value, true, true, !target_exists, target_is_cold, self.config) +
+					costs::delegation_cost(delegate_is_cold, self.config),
+			GasCost::CallCode { value, target_is_cold, target_exists, delegate_is_cold, .. } =>
+				costs::call_cost(value, true, false, !target_exists, target_is_cold, self.config) +
+					costs::delegation_cost(delegate_is_cold, self.config),
+			GasCost::DelegateCall { target_is_cold, target_exists, delegate_is_cold, .. } =>
+				costs::call_cost(U256::zero(), false, false, !target_exists, target_is_cold, self.config) +
+					costs::delegation_cost(delegate_is_cold, self.config),
+			GasCost::StaticCall { target_is_cold, target_exists, delegate_is_cold, .. } =>
+				costs::call_cost(U256::zero(), false, true, !target_exists, target_is_cold, self.config) +
+					costs::delegation_cost(delegate_is_cold, self.config),
+			GasCost::Suicide { value, target_is_cold, target_exists, .. } =>
+				costs::suicide_cost(value, target_is_cold, target_exists, self.config),
+			GasCost::SStore { original, current, new, target_is_cold } =>
+				costs::sstore_cost(original, current, new, gas, target_is_cold, self.config)?,
+
+			GasCost::Sha3 { len } => costs::sha3_cost(len)?,
+			GasCost::Log { n, len } => costs::log_cost(n, len)?,
+			GasCost::ExtCodeCopy { target_is_cold, len } =>
+				costs::extcodecopy_cost(len, target_is_cold, self.config)?,
+			GasCost::VeryLowCopy { len } => costs::verylowcopy_cost(len)?,
+			GasCost::Exp { power } => costs::exp_cost(power, self.config)?,
+			GasCost::Create { len } => costs::create_cost(len, self.config)?,
+			GasCost::Create2 { len } => costs::create2_cost(len, self.config)?,
+			GasCost::SLoad { target_is_cold } => costs::sload_cost(target_is_cold, self.config),
+
+			GasCost::Zero => consts::G_ZERO,
+			GasCost::Base => consts::G_BASE,
+			GasCost::VeryLow => consts::G_VERYLOW,
+			GasCost::Low => consts::G_LOW,
+			GasCost::WarmAccess => consts::G_WARM_ACCESS,
+			GasCost::Invalid(Opcode::INVALID) => return Err(ExitError::DesignatedInvalid),
+			GasCost::Invalid(opcode) => return Err(ExitError::InvalidCode(opcode)),
+
+			GasCost::ExtCodeSize { target_is_cold } =>
+				costs::address_access_cost(target_is_cold, self.config.gas_ext_code, self.config),
+			GasCost::Balance { target_is_cold } =>
+				costs::address_access_cost(target_is_cold, self.config.gas_balance, self.config),
+			GasCost::BlockHash => consts::G_BLOCKHASH,
+			GasCost::ExtCodeHash { target_is_cold } =>
+				costs::address_access_cost(target_is_cold, self.config.gas_ext_code_hash, self.config),
+		})
+	}
+
+	fn gas_refund(&self, cost: GasCost) -> i64 {
+		match cost {
+			GasCost::SStore { original, current, new, .. } =>
+				costs::sstore_refund(original, current, new, self.config),
+			GasCost::Suicide { already_removed, .. } =>
+				costs::suicide_refund(already_removed, self.config),
+			_ => 0,
+		}
+	}
+}
+
+/// Gas cost.
+#[derive(Debug, Clone, Copy)]
+pub enum GasCost {
+	/// Zero gas cost.
+	Zero,
+	/// Base gas cost.
+	Base,
+	/// Very low gas cost.
+	VeryLow,
+	/// Low gas cost.
+	Low,
+	/// Warm storage access gas cost (transient storage).
+	WarmAccess,
+	/// Fail the gasometer, as the opcode is invalid or not enabled by the
+	/// active fork.
+	Invalid(Opcode),
+
+	/// Gas cost for `EXTCODESIZE`.
+	ExtCodeSize {
+		/// Whether the target is accessed for the first time (EIP-2929).
+		target_is_cold: bool,
+	},
+	/// Gas cost for `BALANCE`.
+	Balance {
+		/// Whether the target is accessed for the first time (EIP-2929).
+		target_is_cold: bool,
+	},
+	/// Gas cost for `BLOCKHASH`.
+	BlockHash,
+	/// Gas cost for `EXTCODEHASH`.
+	ExtCodeHash {
+		/// Whether the target is accessed for the first time (EIP-2929).
+		target_is_cold: bool,
+	},
+
+	/// Gas cost for `CALL`.
+	Call {
+		/// Call value.
+		value: U256,
+		/// Call gas.
+		gas: U256,
+		/// Whether the target is accessed for the first time (EIP-2929).
+		target_is_cold: bool,
+		/// Whether the target exists.
+		target_exists: bool,
+		/// Whether the account the target delegates to is accessed for the
+		/// first time, if the target is delegated (EIP-7702).
+		delegate_is_cold: Option<bool>,
+	},
+	/// Gas cost for `CALLCODE`.
+	CallCode {
+		/// Call value.
+		value: U256,
+		/// Call gas.
+		gas: U256,
+		/// Whether the target is accessed for the first time (EIP-2929).
+		target_is_cold: bool,
+		/// Whether the target exists.
+		target_exists: bool,
+		/// Whether the account the target delegates to is accessed for the
+		/// first time, if the target is delegated (EIP-7702).
+		delegate_is_cold: Option<bool>,
+	},
+	/// Gas cost for `DELEGATECALL`.
+	DelegateCall {
+		/// Call gas.
+		gas: U256,
+		/// Whether the target is accessed for the first time (EIP-2929).
+		target_is_cold: bool,
+		/// Whether the target exists.
+		target_exists: bool,
+		/// Whether the account the target delegates to is accessed for the
+		/// first time, if the target is delegated (EIP-7702).
+		delegate_is_cold: Option<bool>,
+	},
+	/// Gas cost for `STATICCALL`.
+	StaticCall {
+		/// Call gas.
+		gas: U256,
+		/// Whether the target is accessed for the first time (EIP-2929).
+		target_is_cold: bool,
+		/// Whether the target exists.
+		target_exists: bool,
+		/// Whether the account the target delegates to is accessed for the
+		/// first time, if the target is delegated (EIP-7702).
+		delegate_is_cold: Option<bool>,
+	},
+	/// Gas cost for `SUICIDE`.
+	Suicide {
+		/// Value.
+		value: U256,
+		/// Whether the target is accessed for the first time (EIP-2929).
+		target_is_cold: bool,
+		/// Whether the target exists.
+		target_exists: bool,
+		/// Whether the target has already been removed.
+		already_removed: bool,
+	},
+	/// Gas cost for `SSTORE`.
+	SStore {
+		/// Original value.
+		original: H256,
+		/// Current value.
+		current: H256,
+		/// New value.
+		new: H256,
+		/// Whether the slot is accessed for the first time (EIP-2929).
+		target_is_cold: bool,
+	},
+	/// Gas cost for `SHA3`.
+	Sha3 {
+		/// Length of the data.
+		len: U256,
+	},
+	/// Gas cost for `LOG`.
+	Log {
+		/// Topic length.
+		n: u8,
+		/// Data length.
+		len: U256,
+	},
+	/// Gas cost for `EXTCODECOPY`.
+	ExtCodeCopy {
+		/// Whether the target is accessed for the first time (EIP-2929).
+		target_is_cold: bool,
+		/// Length.
+		len: U256,
+	},
+	/// Gas cost for some copy opcodes that is documented as `VERYLOW`.
+	VeryLowCopy {
+		/// Length.
+		len: U256,
+	},
+	/// Gas cost for `EXP`.
+	Exp {
+		/// Power of `EXP`.
+		power: U256,
+	},
+	/// Gas cost for `CREATE`.
+	Create {
+		/// Length of the init code.
+		len: U256,
+	},
+	/// Gas cost for `CREATE2`.
+	Create2 {
+		/// Length of the init code.
+		len: U256,
+	},
+	/// Gas cost for `SLOAD`.
+	SLoad {
+		/// Whether the slot is accessed for the first time (EIP-2929).
+		target_is_cold: bool,
+	},
+}
+
+/// Account or storage slot accessed by an opcode. Once the cost has been
+/// recorded, the handler should mark it as warm (EIP-2929).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum StorageTarget {
+	/// No account or slot is accessed.
+	None,
+	/// An account is accessed.
+	Address(H160),
+	/// A storage slot of an account is accessed.
+	Slot(H160, U256),
+}
+
+/// Memory cost.
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryCost {
+	/// Affected memory offset.
+	pub offset: U256,
+	/// Affected length.
+	pub len: U256,
+}
+
+/// Transaction cost.
+#[derive(Debug, Clone, Copy)]
+pub enum TransactionCost {
+	/// Call transaction cost.
+	Call {
+		/// Length of zeros in transaction data.
+		zero_data_len: usize,
+		/// Length of non-zeros in transaction data.
+		non_zero_data_len: usize,
+		/// Number of addresses in the access list.
+		access_list_address_len: usize,
+		/// Number of storage keys in the access list.
+		access_list_storage_len: usize,
+		/// Number of authorizations of a set code transaction (EIP-7702).
+		authorization_list_len: usize,
+	},
+	/// Create transaction cost.
+	Create {
+		/// Length of zeros in transaction data.
+		zero_data_len: usize,
+		/// Length of non-zeros in transaction data.
+		non_zero_data_len: usize,
+		/// Number of addresses in the access list.
+		access_list_address_len: usize,
+		/// Number of storage keys in the access list.
+		access_list_storage_len: usize,
+	},
+}
+
+impl MemoryCost {
+	/// Join two memory cost together.
+	#[must_use]
+	pub fn join(self, other: Self) -> Self {
+		if self.len == U256::zero() {
+			return other
+		}
+
+		if other.len == U256::zero() {
+			return self
+		}
+
+		let self_end = self.offset.saturating_add(self.len);
+		let other_end = other.offset.saturating_add(other.len);
+
+		if self_end >= other_end {
+			self
+		} else {
+			other
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use evm_core::U256;
+	use evm_runtime::Config;
+	use crate::{Gasometer, GasCost, MemoryCost, TransactionCost, static_opcode_cost, transaction_floor_cost};
+	use evm_core::{ExitError, Opcode};
+
+	#[test]
+	fn test_static_costs() {
+		assert_eq!(static_opcode_cost(Opcode::ADD), Some(3));
+		assert_eq!(static_opcode_cost(Opcode::MUL), Some(5));
+		assert_eq!(static_opcode_cost(Opcode::PUSH32), Some(3));
+		assert_eq!(static_opcode_cost(Opcode::SWAP16), Some(3));
+		assert_eq!(static_opcode_cost(Opcode::JUMPDEST), Some(1));
+		assert_eq!(static_opcode_cost(Opcode::SSTORE), None);
+	}
+
+	#[test]
+	fn test_memory_expansion() {
+		let config = Config::istanbul();
+		let mut gasometer = Gasometer::new(1_000, &config);
+
+		// MSTORE at 0 touches one word: 3 (verylow) + 3 (memory).
+		gasometer.record_dynamic_cost(GasCost::VeryLow, Some(MemoryCost {
+			offset: U256::zero(),
+			len: U256::from(32),
+		})).unwrap();
+		assert_eq!(gasometer.total_used_gas(), 6);
+
+		// Touching the same word again only pays the opcode.
+		gasometer.record_dynamic_cost(GasCost::VeryLow, Some(MemoryCost {
+			offset: U256::from(31),
+			len: U256::one(),
+		})).unwrap();
+		assert_eq!(gasometer.total_used_gas(), 9);
+	}
+
+	#[test]
+	fn test_fork_costs() {
+		let istanbul = Config::istanbul();
+		let berlin = Config::berlin();
+		let shanghai = Config::shanghai();
+
+		let mut gasometer = Gasometer::new(10_000, &istanbul);
+		gasometer.record_dynamic_cost(GasCost::SLoad { target_is_cold: true }, None).unwrap();
+		assert_eq!(gasometer.total_used_gas(), 800);
+		assert_eq!(
+			gasometer.record_dynamic_cost(GasCost::Invalid(Opcode::PUSH0), None),
+			Err(ExitError::InvalidCode(Opcode::PUSH0)),
+		);
+
+		let mut gasometer = Gasometer::new(10_000, &berlin);
+		gasometer.record_dynamic_cost(GasCost::SLoad { target_is_cold: true }, None).unwrap();
+		gasometer.record_dynamic_cost(GasCost::SLoad { target_is_cold: false }, None).unwrap();
+		assert_eq!(gasometer.total_used_gas(), 2200);
+
+		// 33 bytes of initcode are two words.
+		let mut gasometer = Gasometer::new(100_000, &shanghai);
+		gasometer.record_dynamic_cost(GasCost::Create { len: U256::from(33) }, None).unwrap();
+		assert_eq!(gasometer.total_used_gas(), 32_004);
+		assert_eq!(
+			gasometer.record_dynamic_cost(GasCost::Create { len: U256::from(0xc001) }, None),
+			Err(ExitError::CreateContractLimit),
+		);
+	}
+
+	#[test]
+	fn test_out_of_gas() {
+		let config = Config::istanbul();
+		let mut gasometer = Gasometer::new(10, &config);
+
+		assert_eq!(gasometer.record_cost(8), Ok(()));
+		assert_eq!(gasometer.gas(), 2);
+		assert_eq!(gasometer.record_cost(3), Err(ExitError::OutOfGas));
+		assert_eq!(gasometer.gas(), 0);
+		assert_eq!(gasometer.total_used_gas(), 10);
+		assert_eq!(gasometer.record_cost(0), Err(ExitError::OutOfGas));
+	}
+
+	#[test]
+	fn test_transaction_cost_overflow() {
+		let config = Config::istanbul();
+		let mut gasometer = Gasometer::new(1_000_000, &config);
+		let cost = TransactionCost::Call {
+			zero_data_len: 0,
+			non_zero_data_len: usize::MAX,
+			access_list_address_len: usize::MAX,
+			access_list_storage_len: 0,
+			authorization_list_len: 0,
+		};
+
+		assert_eq!(gasometer.record_transaction(cost), Err(ExitError::OutOfGas));
+		assert_eq!(transaction_floor_cost(cost, &Config::prague()), u64::MAX);
+	}
+}
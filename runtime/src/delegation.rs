@@ -0,0 +1,43 @@
+use alloc::vec::Vec;
+use crate::H160;
+
+/// Prefix of an EIP-7702 delegation designator, followed by the address the
+/// account delegates to.
+pub const DELEGATION_PREFIX: [u8; 3] = [0xef, 0x01, 0x00];
+/// Length of a delegation designator.
+pub const DELEGATION_DESIGNATOR_LEN: usize = 23;
+
+/// Delegation designator of an account delegating to `address` (EIP-7702).
+#[must_use]
+pub fn delegation_designator(address: H160) -> Vec<u8> {
+	let mut code = Vec::with_capacity(DELEGATION_DESIGNATOR_LEN);
+	code.extend_from_slice(&DELEGATION_PREFIX);
+	code.extend_from_slice(address.as_bytes());
+	code
+}
+
+/// Address `code` delegates to, if it is a delegation designator.
+#[must_use]
+pub fn delegated_address(code: &[u8]) -> Option<H160> {
+	if code.len() == DELEGATION_DESIGNATOR_LEN && code.starts_with(&DELEGATION_PREFIX) {
+		Some(H160::from_slice(&code[DELEGATION_PREFIX.len()..]))
+	} else {
+		None
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{delegated_address, delegation_designator};
+	use crate::H160;
+
+	#[test]
+	fn test_designator() {
+		let address = H160::repeat_byte(0x11);
+		let code = delegation_designator(address);
+		assert_eq!(code.len(), 23);
+		assert_eq!(delegated_address(&code), Some(address));
+		assert_eq!(delegated_address(&code[..22]), None);
+		assert_eq!(delegated_address(&[0xef, 0x01, 0x01]), None);
+	}
+}
@@ -3,7 +3,7 @@ mod macros;
 mod system;
 
 pub use system::{save_return_value, save_created_address};
-use crate::{Handler, Runtime, ExitReason, CallScheme, Opcode};
+use crate::{Handler, Inspector, Runtime, ExitReason, CallScheme, Opcode};
 
 /// continue the execution / spawn next frame / exit to previous frame
 pub enum Control<H: Handler> {
@@ -28,14 +28,22 @@ fn handle_other<H: Handler>(state: &mut Runtime, opcode: Opcode, handler: &mut H
 	}
 }
 
-/// process `external` opcodes
-pub fn eval<H: Handler>(state: &mut Runtime, opcode: Opcode, handler: &mut H) -> Control<H> {
+/// process `external` opcodes, reporting storage accesses and logs to the
+/// inspector of `handler`
+pub fn eval<H: Handler, I: Inspector>(
+	state: &mut Runtime,
+	opcode: Opcode,
+	handler: &mut H,
+	inspector: fn(&mut H) -> &mut I,
+) -> Control<H> {
 	match opcode {
 		Opcode::SHA3 => system::sha3(state, handler),
 		Opcode::ADDRESS => system::address(state),
 		Opcode::BALANCE => system::balance(state, handler),
 		Opcode::SELFBALANCE => system::selfbalance(state, handler),
 		Opcode::BASEFEE => system::basefee(state, handler),
+		Opcode::BLOBHASH => system::blobhash(state, handler),
+		Opcode::BLOBBASEFEE => system::blobbasefee(state, handler),
 		Opcode::ORIGIN => system::origin(state, handler),
 		Opcode::CALLER => system::caller(state),
 		Opcode::CALLVALUE => system::callvalue(state),
@@ -51,16 +59,16 @@ pub fn eval<H: Handler>(state: &mut Runtime, opcode: Opcode, handler: &mut H) ->
 		Opcode::NUMBER => system::number(state, handler),
 		Opcode::DIFFICULTY => system::difficulty(state, handler),
 		Opcode::GASLIMIT => system::gaslimit(state, handler),
-		Opcode::SLOAD => system::sload(state, handler),
-		Opcode::SSTORE => system::sstore(state, handler),
-		Opcode::TLOAD => system::tload(state, handler),
-		Opcode::TSTORE => system::tstore(state, handler),
+		Opcode::SLOAD => system::sload(state, handler, inspector),
+		Opcode::SSTORE => system::sstore(state, handler, inspector),
+		Opcode::TLOAD => system::tload(state, handler, inspector),
+		Opcode::TSTORE => system::tstore(state, handler, inspector),
 		Opcode::GAS => system::gas(state, handler),
-		Opcode::LOG0 => system::log(state, 0, handler),
-		Opcode::LOG1 => system::log(state, 1, handler),
-		Opcode::LOG2 => system::log(state, 2, handler),
-		Opcode::LOG3 => system::log(state, 3, handler),
-		Opcode::LOG4 => system::log(state, 4, handler),
+		Opcode::LOG0 => system::log(state, 0, handler, inspector),
+		Opcode::LOG1 => system::log(state, 1, handler, inspector),
+		Opcode::LOG2 => system::log(state, 2, handler, inspector),
+		Opcode::LOG3 => system::log(state, 3, handler, inspector),
+		Opcode::LOG4 => system::log(state, 4, handler, inspector),
 		Opcode::SUICIDE => system::suicide(state, handler),
 		Opcode::CREATE => system::create(state, false, handler),
 		Opcode::CREATE2 => system::create(state, true, handler),
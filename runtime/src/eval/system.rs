@@ -1,6 +1,6 @@
 use core::cmp::min;
 use alloc::vec::Vec;
-use crate::{Runtime, ExitError, Handler, Capture, Transfer, ExitReason, CreateScheme, CallScheme, Context, ExitSucceed, ExitFatal, H160, H256, U256};
+use crate::{Runtime, ExitError, Handler, Inspector, Capture, Transfer, ExitReason, CreateScheme, CallScheme, Context, ExitSucceed, ExitFatal, H160, H256, U256};
 use super::Control;
 
 /// Compute Keccak-256 hash
@@ -54,8 +54,29 @@ pub fn selfbalance<H: Handler>(runtime: &mut Runtime, handler: &H) -> Control<H>
 }
 
 /// Get the base fee
-pub fn basefee<H: Handler>(runtime: &mut Runtime, _handler: &H) -> Control<H> {
-	push_u256!(runtime, U256::zero());
+pub fn basefee<H: Handler>(runtime: &mut Runtime, handler: &H) -> Control<H> {
+	push_u256!(runtime, handler.block_base_fee_per_gas());
+
+	Control::Continue
+}
+
+/// Get the versioned hash of a blob of the transaction, or zero if the
+/// index is out of range
+pub fn blobhash<H: Handler>(runtime: &mut Runtime, handler: &H) -> Control<H> {
+	pop_u256!(runtime, index);
+	let hash = if index > U256::from(usize::MAX) {
+		None
+	} else {
+		handler.blob_versioned_hash(index.as_usize())
+	};
+	push!(runtime, hash.unwrap_or_default());
+
+	Control::Continue
+}
+
+/// Get the blob base fee of the current block
+pub fn blobbasefee<H: Handler>(runtime: &mut Runtime, handler: &H) -> Control<H> {
+	push_u256!(runtime, handler.block_blob_base_fee());
 
 	Control::Continue
 }
@@ -202,17 +223,19 @@ pub fn gaslimit<H: Handler>(runtime: &mut Runtime, handler: &H) -> Control<H> {
 }
 
 /// Load word from storage
-pub fn sload<H: Handler>(runtime: &mut Runtime, handler: &H) -> Control<H> {
+pub fn sload<H: Handler, I: Inspector>(runtime: &mut Runtime, handler: &mut H, inspector: fn(&mut H) -> &mut I) -> Control<H> {
 	pop_u256!(runtime, index);
 	let value = handler.storage(runtime.context.address, index);
+	inspector(handler).sload(runtime.context.address, index, value);
 	push_u256!(runtime, value);
 
 	Control::Continue
 }
 
 /// Save word to storage
-pub fn sstore<H: Handler>(runtime: &mut Runtime, handler: &mut H) -> Control<H> {
+pub fn sstore<H: Handler, I: Inspector>(runtime: &mut Runtime, handler: &mut H, inspector: fn(&mut H) -> &mut I) -> Control<H> {
 	pop_u256!(runtime, index, value);
+	inspector(handler).sstore(runtime.context.address, index, value);
 
 	match handler.set_storage(runtime.context.address, index, value) {
 		Ok(()) => Control::Continue,
@@ -221,17 +244,19 @@ pub fn sstore<H: Handler>(runtime: &mut Runtime, handler: &mut H) -> Control<H>
 }
 
 /// Load word from transient storage
-pub fn tload<H: Handler>(runtime: &mut Runtime, handler: &H) -> Control<H> {
+pub fn tload<H: Handler, I: Inspector>(runtime: &mut Runtime, handler: &mut H, inspector: fn(&mut H) -> &mut I) -> Control<H> {
 	pop_u256!(runtime, index);
 	let value = handler.transient_storage(runtime.context.address, index);
+	inspector(handler).tload(runtime.context.address, index, value);
 	push_u256!(runtime, value);
 
 	Control::Continue
 }
 
 /// Save word to transient storage
-pub fn tstore<H: Handler>(runtime: &mut Runtime, handler: &mut H) -> Control<H> {
+pub fn tstore<H: Handler, I: Inspector>(runtime: &mut Runtime, handler: &mut H, inspector: fn(&mut H) -> &mut I) -> Control<H> {
 	pop_u256!(runtime, index, value);
+	inspector(handler).tstore(runtime.context.address, index, value);
 
 	match handler.set_transient_storage(runtime.context.address, index, value) {
 		Ok(()) => Control::Continue,
@@ -249,7 +274,7 @@ pub fn gas<H: Handler>(runtime: &mut Runtime, handler: &H) -> Control<H> {
 
 
 /// Append log record
-pub fn log<H: Handler>(runtime: &mut Runtime, n: u8, handler: &mut H) -> Control<H> {
+pub fn log<H: Handler, I: Inspector>(runtime: &mut Runtime, n: u8, handler: &mut H, inspector: fn(&mut H) -> &mut I) -> Control<H> {
 	pop_u256!(runtime, offset, len);
 	let offset = as_usize_or_fail!(offset);
 	let len = as_usize_or_fail!(len);
@@ -269,6 +294,7 @@ pub fn log<H: Handler>(runtime: &mut Runtime, n: u8, handler: &mut H) -> Control
 		}
 	}
 
+	inspector(handler).log(runtime.context.address, &topics, &data);
 	match handler.log(runtime.context.address, topics, data) {
 		Ok(()) => Control::Continue,
 		Err(e) => Control::Exit(e.into()),
@@ -321,7 +347,11 @@ pub fn create<H: Handler>(
 		}
 	};
 
-	match handler.create(runtime.context.address, scheme, value, code, None) {
+	if runtime.depth >= runtime.config.call_stack_limit {
+		return save_created_address(runtime, ExitError::CallTooDeep.into(), None)
+	}
+
+	match handler.create(runtime.context.address, scheme, value, code, None, runtime.depth) {
 		Capture::Exit((reason, address, _return_data)) => {
 			save_created_address(runtime, reason, address)
 		},
@@ -373,21 +403,25 @@ pub fn call<'config, H: Handler>(
 		runtime.machine.memory().get(in_offset, in_len)
 	};
 
+	let is_static = runtime.context.is_static || scheme == CallScheme::StaticCall;
 	let context = match scheme {
 		CallScheme::Call | CallScheme::StaticCall => Context {
 			address: to.into(),
 			caller: runtime.context.address,
 			apparent_value: value,
+			is_static,
 		},
 		CallScheme::CallCode => Context {
 			address: runtime.context.address,
 			caller: runtime.context.address,
 			apparent_value: value,
+			is_static,
 		},
 		CallScheme::DelegateCall => Context {
 			address: runtime.context.address,
 			caller: runtime.context.caller,
 			apparent_value: runtime.context.apparent_value,
+			is_static,
 		},
 	};
 
@@ -407,7 +441,11 @@ pub fn call<'config, H: Handler>(
 		None
 	};
 
-	match handler.call(to.into(), transfer, input, gas, scheme == CallScheme::StaticCall, context) {
+	if runtime.depth >= runtime.config.call_stack_limit {
+		return save_return_value(runtime, ExitError::CallTooDeep.into(), Vec::new())
+	}
+
+	match handler.call(to.into(), scheme, transfer, input, gas, is_static, context, runtime.depth) {
 		Capture::Exit((reason, return_data)) => {
 			save_return_value(runtime, reason, return_data)
 		},
@@ -445,7 +483,12 @@ pub fn save_created_address<'config, H: Handler>(
 			push!(runtime, H256::default());
 			Control::Exit(e.into())
 		},
-		ExitReason::StepLimitReached => { unreachable!() }
+		// A create run out of steps never finished, so it cannot be resumed
+		// from.
+		ExitReason::StepLimitReached => {
+			push!(runtime, H256::default());
+			Control::Exit(ExitFatal::UnhandledInterrupt.into())
+		},
 	}
 
 }
@@ -507,7 +550,12 @@ pub fn save_return_value<'config, H: Handler>(
 
 					Control::Exit(e.into())
 				},
-				ExitReason::StepLimitReached => { unreachable!() }
+				// Neither can a call run out of steps.
+				ExitReason::StepLimitReached => {
+					push_u256!(runtime, U256::zero());
+
+					Control::Exit(ExitFatal::UnhandledInterrupt.into())
+				},
 			}
         }
 }
@@ -2,7 +2,7 @@ use alloc::vec::Vec;
 use crate::{Capture, Stack, ExitError, Opcode,
 			Machine, ExitReason,
 			H160, H256, U256};
-use evm_core::{Context, CreateScheme, ExitFatal, Transfer};
+use evm_core::{CallScheme, Context, CreateScheme, ExitFatal, Transfer};
 
 /// EVM context handler.
 pub trait Handler {
@@ -26,16 +26,53 @@ pub trait Handler {
 	fn code_size(&self, address: H160) -> U256;
 	/// Get code hash of address.
 	fn code_hash(&self, address: H160) -> H256;
-	/// Get code of address.
+	/// Get code of address. For a delegated account this is the delegation
+	/// designator itself, which is also what `EXTCODESIZE`, `EXTCODEHASH`
+	/// and `EXTCODECOPY` observe (EIP-7702).
 	fn code(&self, address: H160) -> Vec<u8>;
+	/// Get the address whose code runs when address is called, if its code
+	/// is a delegation designator and delegation is enabled (EIP-7702).
+	/// Defaults to `None`, as before Prague.
+	fn delegated_address(&self, _address: H160) -> Option<H160> {
+		None
+	}
 	/// Get valids of address.
 	fn valids(&self, address: H160) -> Vec<u8>;
 	/// Get storage value of address at index.
 	fn storage(&self, address: H160, index: U256) -> U256;
 	fn transient_storage(&self, address: H160, index: U256) -> U256;
+	/// Get original storage value of address at index, as it was at the
+	/// start of the transaction. Defaults to the current value, for handlers
+	/// not tracking it.
+	fn original_storage(&self, address: H160, index: U256) -> U256 {
+		self.storage(address, index)
+	}
+	/// Check whether an address exists. Defaults to whether the account is
+	/// not empty (EIP-161).
+	fn exists(&self, address: H160) -> bool {
+		self.nonce(address) != U256::zero() ||
+			self.balance(address) != U256::zero() ||
+			self.code_size(address) != U256::zero()
+	}
+	/// Check whether an address has already been deleted. Defaults to
+	/// `false`, for handlers not tracking deletions.
+	fn deleted(&self, _address: H160) -> bool {
+		false
+	}
+	/// Check whether an address, or a storage slot of it if `index` is given,
+	/// has not been accessed yet in the transaction (EIP-2929). Defaults to
+	/// `false`, for handlers not tracking accesses.
+	fn is_cold(&self, _address: H160, _index: Option<U256>) -> bool {
+		false
+	}
 
 	/// Get the gas left value.
 	fn gas_left(&self) -> U256;
+	/// Get the gas refunded so far in the transaction. Defaults to zero, for
+	/// handlers not metering gas.
+	fn refunded_gas(&self) -> i64 {
+		0
+	}
 	/// Get the gas price value.
 	fn gas_price(&self) -> U256;
 	/// Get execution origin.
@@ -52,6 +89,22 @@ pub trait Handler {
 	fn block_difficulty(&self) -> U256;
 	/// Get environmental gas limit.
 	fn block_gas_limit(&self) -> U256;
+	/// Get environmental block base fee per gas (EIP-1559). Defaults to
+	/// zero, as before London.
+	fn block_base_fee_per_gas(&self) -> U256 {
+		U256::zero()
+	}
+	/// Get environmental block blob base fee (EIP-7516). Defaults to zero,
+	/// as before Cancun.
+	fn block_blob_base_fee(&self) -> U256 {
+		U256::zero()
+	}
+	/// Get the versioned hash of the transaction's blob at `index`, if there
+	/// is one (EIP-4844). Defaults to `None`, as for a transaction without
+	/// blobs.
+	fn blob_versioned_hash(&self, _index: usize) -> Option<H256> {
+		None
+	}
 	/// Get environmental chain ID.
 	fn chain_id(&self) -> U256;
 	/// Set storage value of address at index.
@@ -60,9 +113,10 @@ pub trait Handler {
 	fn set_transient_storage(&mut self, address: H160, index: U256, value: U256) -> Result<(), ExitError>;
 	/// Create a log owned by address with given topics and data.
 	fn log(&mut self, address: H160, topcis: Vec<H256>, data: Vec<u8>) -> Result<(), ExitError>;
-	/// Mark an address to be deleted, with funds transferred to target.
+	/// Mark an address to be deleted, with funds transferred to target. With
+	/// EIP-6780 only accounts created in the same transaction are deleted.
 	fn mark_delete(&mut self, address: H160, target: H160) -> Result<(), ExitError>;
-	/// Invoke a create operation.
+	/// Invoke a create operation from a runtime at call depth `depth`.
 	fn create(
 		&mut self,
 		caller: H160,
@@ -70,6 +124,7 @@ pub trait Handler {
 		value: U256,
 		init_code: Vec<u8>,
 		target_gas: Option<u64>,
+		depth: usize,
 	) -> Capture<(ExitReason, Option<H160>, Vec<u8>), Self::CreateInterrupt>;
 	/// Feed in create feedback.
 	fn create_feedback(
@@ -78,15 +133,19 @@ pub trait Handler {
 	) -> Result<(), ExitError> {
 		Ok(())
 	}
-	/// Invoke a call operation.
+	/// Invoke a call operation of `scheme` from a runtime at call depth
+	/// `depth`.
+	#[allow(clippy::too_many_arguments)]
 	fn call(
 		&mut self,
 		code_address: H160,
+		scheme: CallScheme,
 		transfer: Option<Transfer>,
 		input: Vec<u8>,
 		target_gas: Option<u64>,
 		is_static: bool,
 		context: Context,
+		depth: usize,
 	) -> Capture<(ExitReason, Vec<u8>), Self::CallInterrupt>;
 	/// Feed in call feedback.
 	fn call_feedback(
@@ -0,0 +1,138 @@
+use evm_core::Memory;
+use crate::{CallScheme, Context, CreateScheme, ExitReason, Opcode, Stack, Transfer, H160, H256, U256};
+
+/// Machine state before a step.
+#[derive(Clone, Copy, Debug)]
+pub struct Step<'a> {
+	/// Context of the running frame.
+	pub context: &'a Context,
+	/// Call depth of the running frame.
+	pub depth: usize,
+	/// Opcode about to be executed.
+	pub opcode: Opcode,
+	/// Program counter of the opcode.
+	pub position: usize,
+	/// Gas left before the opcode is charged.
+	pub gas: u64,
+	/// Gas refunded so far in the transaction.
+	pub refund: i64,
+	/// Stack before the step.
+	pub stack: &'a Stack,
+	/// Memory before the step.
+	pub memory: &'a Memory,
+	/// Return data of the last call made by the frame.
+	pub return_data: &'a [u8],
+}
+
+/// Observer of the execution, for tracing.
+///
+/// All callbacks do nothing by default. The runtime only collects step
+/// information when `ENABLED` is set, so the `()` inspector costs nothing.
+pub trait Inspector {
+	/// Whether the inspector observes anything.
+	const ENABLED: bool = true;
+
+	/// Called before an opcode is executed.
+	fn step(&mut self, _step: &Step<'_>) {}
+	/// Called after an opcode is executed, including opcodes handled by the
+	/// `Handler`, with the exit reason if the step stopped the frame and the
+	/// gas left after it.
+	fn step_result(&mut self, _exit: Option<&ExitReason>, _gas: u64, _stack: &Stack, _memory: &Memory) {}
+
+	/// Called after `SLOAD` reads `value`.
+	fn sload(&mut self, _address: H160, _index: U256, _value: U256) {}
+	/// Called before `SSTORE` writes `value`.
+	fn sstore(&mut self, _address: H160, _index: U256, _value: U256) {}
+	/// Called after `TLOAD` reads `value`.
+	fn tload(&mut self, _address: H160, _index: U256, _value: U256) {}
+	/// Called before `TSTORE` writes `value`.
+	fn tstore(&mut self, _address: H160, _index: U256, _value: U256) {}
+	/// Called before a log is emitted.
+	fn log(&mut self, _address: H160, _topics: &[H256], _data: &[u8]) {}
+
+	/// Called when a call frame is entered with `gas`.
+	fn call(
+		&mut self,
+		_scheme: CallScheme,
+		_code_address: H160,
+		_transfer: Option<&Transfer>,
+		_input: &[u8],
+		_gas: u64,
+		_context: &Context,
+	) {}
+	/// Called when a create frame is entered with `gas`.
+	fn create(
+		&mut self,
+		_scheme: CreateScheme,
+		_address: H160,
+		_value: U256,
+		_init_code: &[u8],
+		_gas: u64,
+	) {}
+	/// Called when a call or create frame is exited, with the gas it leaves
+	/// to its parent. The return value of a succeeded create is the deployed
+	/// code.
+	fn exit(&mut self, _reason: &ExitReason, _return_value: &[u8], _gas_left: u64) {}
+	/// Called when an account self-destructs, sending `balance` to `target`.
+	fn suicide(&mut self, _address: H160, _target: H160, _balance: U256) {}
+}
+
+impl Inspector for () {
+	const ENABLED: bool = false;
+}
+
+impl<T: Inspector> Inspector for &mut T {
+	const ENABLED: bool = T::ENABLED;
+
+	fn step(&mut self, step: &Step<'_>) {
+		(**self).step(step);
+	}
+
+	fn step_result(&mut self, exit: Option<&ExitReason>, gas: u64, stack: &Stack, memory: &Memory) {
+		(**self).step_result(exit, gas, stack, memory);
+	}
+
+	fn sload(&mut self, address: H160, index: U256, value: U256) {
+		(**self).sload(address, index, value);
+	}
+
+	fn sstore(&mut self, address: H160, index: U256, value: U256) {
+		(**self).sstore(address, index, value);
+	}
+
+	fn tload(&mut self, address: H160, index: U256, value: U256) {
+		(**self).tload(address, index, value);
+	}
+
+	fn tstore(&mut self, address: H160, index: U256, value: U256) {
+		(**self).tstore(address, index, value);
+	}
+
+	fn log(&mut self, address: H160, topics: &[H256], data: &[u8]) {
+		(**self).log(address, topics, data);
+	}
+
+	fn call(
+		&mut self,
+		scheme: CallScheme,
+		code_address: H160,
+		transfer: Option<&Transfer>,
+		input: &[u8],
+		gas: u64,
+		context: &Context,
+	) {
+		(**self).call(scheme, code_address, transfer, input, gas, context);
+	}
+
+	fn create(&mut self, scheme: CreateScheme, address: H160, value: U256, init_code: &[u8], gas: u64) {
+		(**self).create(scheme, address, value, init_code, gas);
+	}
+
+	fn exit(&mut self, reason: &ExitReason, return_value: &[u8], gas_left: u64) {
+		(**self).exit(reason, return_value, gas_left);
+	}
+
+	fn suicide(&mut self, address: H160, target: H160, balance: U256) {
+		(**self).suicide(address, target, balance);
+	}
+}
@@ -1,45 +1,247 @@
-use crate::{Runtime, Handler, ExitFatal};
+use core::marker::PhantomData;
+use alloc::vec::Vec;
+use crate::{Runtime, Handler, ExitFatal, ExitReason, Control, H160, save_return_value, save_created_address};
 
 /// Interrupt resolution.
-pub enum Resolve<'a, H: Handler> {
+pub enum Resolve<'a, 'config, H: Handler> {
 	/// Create interrupt resolution.
-	Create(H::CreateInterrupt, ResolveCreate<'a>),
+	Create(H::CreateInterrupt, ResolveCreate<'a, 'config, H>),
 	/// Call interrupt resolution.
-	Call(H::CallInterrupt, ResolveCall<'a>),
+	Call(H::CallInterrupt, ResolveCall<'a, 'config, H>),
+}
+
+/// Exit a runtime whose resumed interrupt failed.
+fn exit(runtime: &mut Runtime, reason: ExitReason) {
+	runtime.machine.exit(reason);
+	runtime.status = Err(reason);
+}
+
+/// Mark a runtime whose interrupt was dropped without being resumed.
+fn unhandled(runtime: &mut Runtime) {
+	runtime.status = Err(ExitFatal::UnhandledInterrupt.into());
+	runtime.machine.exit(ExitFatal::UnhandledInterrupt.into());
 }
 
 /// Create interrupt resolution.
-pub struct ResolveCreate<'a> {
-	runtime: &'a mut Runtime,
+///
+/// Dropping it without calling `resume_create` exits the runtime with
+/// `UnhandledInterrupt`.
+pub struct ResolveCreate<'a, 'config, H: Handler> {
+	runtime: Option<&'a mut Runtime<'config>>,
+	_marker: PhantomData<H>,
 }
 
-impl<'a> ResolveCreate<'a> {
-	pub(crate) fn new(runtime: &'a mut Runtime) -> Self {
-		Self { runtime }
+impl<'a, 'config, H: Handler> ResolveCreate<'a, 'config, H> {
+	pub(crate) const fn new(runtime: &'a mut Runtime<'config>) -> Self {
+		Self { runtime: Some(runtime), _marker: PhantomData }
+	}
+
+	/// Resume the runtime with the result of the create: push the created
+	/// address, or zero if it failed. Run the runtime again to continue
+	/// execution.
+	pub fn resume_create(mut self, reason: ExitReason, address: Option<H160>) {
+		let runtime = self.runtime.take().expect("resolver is only consumed once");
+		if let Control::Exit(reason) = save_created_address::<H>(runtime, reason, address) {
+			exit(runtime, reason);
+		}
 	}
 }
 
-impl<'a> Drop for ResolveCreate<'a> {
+impl<H: Handler> Drop for ResolveCreate<'_, '_, H> {
 	fn drop(&mut self) {
-		self.runtime.status = Err(ExitFatal::UnhandledInterrupt.into());
-		self.runtime.machine.exit(ExitFatal::UnhandledInterrupt.into());
+		if let Some(runtime) = self.runtime.take() {
+			unhandled(runtime);
+		}
 	}
 }
 
 /// Call interrupt resolution.
-pub struct ResolveCall<'a> {
-	runtime: &'a mut Runtime,
+///
+/// Dropping it without calling `resume` exits the runtime with
+/// `UnhandledInterrupt`.
+pub struct ResolveCall<'a, 'config, H: Handler> {
+	runtime: Option<&'a mut Runtime<'config>>,
+	_marker: PhantomData<H>,
 }
 
-impl<'a> ResolveCall<'a> {
-	pub(crate) fn new(runtime: &'a mut Runtime) -> Self {
-		Self { runtime }
+impl<'a, 'config, H: Handler> ResolveCall<'a, 'config, H> {
+	pub(crate) const fn new(runtime: &'a mut Runtime<'config>) -> Self {
+		Self { runtime: Some(runtime), _marker: PhantomData }
+	}
+
+	/// Resume the runtime with the result of the call: copy the return data
+	/// to the output memory region and push the success flag. Run the
+	/// runtime again to continue execution.
+	pub fn resume(mut self, reason: ExitReason, return_data: Vec<u8>) {
+		let runtime = self.runtime.take().expect("resolver is only consumed once");
+		if let Control::Exit(reason) = save_return_value::<H>(runtime, reason, return_data) {
+			exit(runtime, reason);
+		}
 	}
 }
 
-impl<'a> Drop for ResolveCall<'a> {
+impl<H: Handler> Drop for ResolveCall<'_, '_, H> {
 	fn drop(&mut self) {
-		self.runtime.status = Err(ExitFatal::UnhandledInterrupt.into());
-		self.runtime.machine.exit(ExitFatal::UnhandledInterrupt.into());
+		if let Some(runtime) = self.runtime.take() {
+			unhandled(runtime);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::vec::Vec;
+	use crate::{
+		CallScheme, Capture, Config, Context, CreateScheme, ExitError, ExitFatal, ExitReason, ExitSucceed, Handler, Machine,
+		Opcode, Resolve, Runtime, Stack, Transfer, H160, H256, U256,
+	};
+
+	/// Handler trapping on every call and create.
+	struct TrapHandler;
+
+	impl Handler for TrapHandler {
+		type CreateInterrupt = ();
+		type CreateFeedback = ();
+		type CallInterrupt = ();
+		type CallFeedback = ();
+
+		fn keccak256_h256(&self, _data: &[u8]) -> H256 { H256::zero() }
+		fn nonce(&self, _address: H160) -> U256 { U256::zero() }
+		fn balance(&self, _address: H160) -> U256 { U256::zero() }
+		fn code_size(&self, _address: H160) -> U256 { U256::zero() }
+		fn code_hash(&self, _address: H160) -> H256 { H256::zero() }
+		fn code(&self, _address: H160) -> Vec<u8> { Vec::new() }
+		fn valids(&self, _address: H160) -> Vec<u8> { Vec::new() }
+		fn storage(&self, _address: H160, _index: U256) -> U256 { U256::zero() }
+		fn transient_storage(&self, _address: H160, _index: U256) -> U256 { U256::zero() }
+		fn gas_left(&self) -> U256 { U256::zero() }
+		fn gas_price(&self) -> U256 { U256::zero() }
+		fn origin(&self) -> H160 { H160::zero() }
+		fn block_hash(&self, _number: U256) -> H256 { H256::zero() }
+		fn block_number(&self) -> U256 { U256::zero() }
+		fn block_coinbase(&self) -> H160 { H160::zero() }
+		fn block_timestamp(&self) -> U256 { U256::zero() }
+		fn block_difficulty(&self) -> U256 { U256::zero() }
+		fn block_gas_limit(&self) -> U256 { U256::zero() }
+		fn chain_id(&self) -> U256 { U256::zero() }
+		fn set_storage(&mut self, _address: H160, _index: U256, _value: U256) -> Result<(), ExitError> { Ok(()) }
+		fn set_transient_storage(&mut self, _address: H160, _index: U256, _value: U256) -> Result<(), ExitError> {
+			Ok(())
+		}
+		fn log(&mut self, _address: H160, _topics: Vec<H256>, _data: Vec<u8>) -> Result<(), ExitError> { Ok(()) }
+		fn mark_delete(&mut self, _address: H160, _target: H160) -> Result<(), ExitError> { Ok(()) }
+
+		fn create(
+			&mut self,
+			_caller: H160,
+			_scheme: CreateScheme,
+			_value: U256,
+			_init_code: Vec<u8>,
+			_target_gas: Option<u64>,
+			_depth: usize,
+		) -> Capture<(ExitReason, Option<H160>, Vec<u8>), ()> {
+			Capture::Trap(())
+		}
+
+		fn call(
+			&mut self,
+			_code_address: H160,
+			_scheme: CallScheme,
+			_transfer: Option<Transfer>,
+			_input: Vec<u8>,
+			_target_gas: Option<u64>,
+			_is_static: bool,
+			_context: Context,
+			_depth: usize,
+		) -> Capture<(ExitReason, Vec<u8>), ()> {
+			Capture::Trap(())
+		}
+
+		fn pre_validate(&mut self, _context: &Context, _opcode: Opcode, _stack: &Stack) -> Result<(), ExitError> {
+			Ok(())
+		}
+
+		fn other(&mut self, _opcode: Opcode, _stack: &mut Machine) -> Result<(), ExitFatal> {
+			Err(ExitFatal::NotSupported)
+		}
+	}
+
+	const CONFIG: Config = Config::cancun();
+
+	fn new_runtime(code: &[u8]) -> Runtime<'static> {
+		let context = Context {
+			address: H160::repeat_byte(0xaa),
+			caller: H160::repeat_byte(0xca),
+			apparent_value: U256::zero(),
+			is_static: false,
+		};
+		Runtime::new(code.to_vec(), Vec::new(), Vec::new(), context, &CONFIG)
+	}
+
+	fn assert_exit(runtime: &mut Runtime, reason: ExitReason) {
+		match runtime.run(1000, &mut TrapHandler) {
+			(_, Capture::Exit(exit)) => assert_eq!(exit, reason),
+			_ => panic!("runtime did not exit"),
+		}
+	}
+
+	// CALL(0, 0xbb, 0, 0, 0, 0, 32); STOP
+	const CALL: &[u8] = &[
+		0x60, 0x20, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0xbb, 0x60, 0x00, 0xf1, 0x00,
+	];
+	// CREATE(0, 0, 0); STOP
+	const CREATE: &[u8] = &[0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0xf0, 0x00];
+
+	#[test]
+	fn test_resume() {
+		let mut runtime = new_runtime(CALL);
+		match runtime.run(1000, &mut TrapHandler) {
+			(_, Capture::Trap(Resolve::Call((), resolve))) =>
+				resolve.resume(ExitSucceed::Returned.into(), vec![0x2a; 32]),
+			_ => panic!("call did not trap"),
+		}
+		assert_eq!(runtime.machine().stack().peek(0), Ok(U256::one()));
+		assert_eq!(runtime.machine().memory().get(0, 32), vec![0x2a; 32]);
+		assert_exit(&mut runtime, ExitSucceed::Stopped.into());
+
+		let mut runtime = new_runtime(CREATE);
+		match runtime.run(1000, &mut TrapHandler) {
+			(_, Capture::Trap(Resolve::Create((), resolve))) =>
+				resolve.resume_create(ExitSucceed::Returned.into(), Some(H160::repeat_byte(0xcc))),
+			_ => panic!("create did not trap"),
+		}
+		assert_eq!(runtime.machine().stack().peek(0), Ok(U256::from_big_endian(H256::from(H160::repeat_byte(0xcc)).as_bytes())));
+		assert_exit(&mut runtime, ExitSucceed::Stopped.into());
+	}
+
+	#[test]
+	fn test_resume_step_limit() {
+		let mut runtime = new_runtime(CALL);
+		match runtime.run(1000, &mut TrapHandler) {
+			(_, Capture::Trap(Resolve::Call((), resolve))) =>
+				resolve.resume(ExitReason::StepLimitReached, Vec::new()),
+			_ => panic!("call did not trap"),
+		}
+		assert_exit(&mut runtime, ExitFatal::UnhandledInterrupt.into());
+
+		let mut runtime = new_runtime(CREATE);
+		match runtime.run(1000, &mut TrapHandler) {
+			(_, Capture::Trap(Resolve::Create((), resolve))) =>
+				resolve.resume_create(ExitReason::StepLimitReached, None),
+			_ => panic!("create did not trap"),
+		}
+		assert_exit(&mut runtime, ExitFatal::UnhandledInterrupt.into());
+	}
+
+	#[test]
+	fn test_drop_unresolved() {
+		for code in [CALL, CREATE] {
+			let mut runtime = new_runtime(code);
+			match runtime.run(1000, &mut TrapHandler) {
+				(_, Capture::Trap(resolve)) => drop(resolve),
+				_ => panic!("did not trap"),
+			}
+			assert_exit(&mut runtime, ExitFatal::UnhandledInterrupt.into());
+		}
 	}
 }
@@ -17,16 +17,24 @@ extern crate alloc;
 mod eval;
 mod interrupt;
 mod handler;
+mod delegation;
+mod inspector;
 
 pub use evm_core::{
 	Machine, Transfer, ExitReason, Context, Capture, Stack, ExitError, CreateScheme, CallScheme,
-	ExitSucceed, ExitFatal, H160, H256, U256, Opcode,
+	ExitSucceed, ExitFatal, H160, H256, U256, Opcode, EofContainer, EofError, EOF_MAGIC,
 };
 
 pub use crate::interrupt::{Resolve, ResolveCall, ResolveCreate};
 pub use crate::handler::Handler;
+pub use crate::inspector::{Inspector, Step};
+pub use crate::delegation::{
+	delegated_address, delegation_designator, DELEGATION_DESIGNATOR_LEN, DELEGATION_PREFIX,
+};
 pub use crate::eval::{save_return_value, save_created_address, Control};
 
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
 use alloc::vec::Vec;
 
 /// EVM runtime.
@@ -35,27 +43,70 @@ use alloc::vec::Vec;
 #[cfg_attr(feature = "with-codec", derive(codec::Encode, codec::Decode))]
 #[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(borsh::BorshSerialize, borsh::BorshDeserialize)]
-pub struct Runtime {
+pub struct Runtime<'config> {
 	machine: Machine,
 	status: Result<(), ExitReason>,
 	#[cfg_attr(feature = "with-serde", serde(with = "serde_bytes"))]
 	return_data_buffer: Vec<u8>,
 	context: Context,
+	config: Cow<'config, Config>,
+	/// Call depth, zero for the outermost frame of a transaction.
+	depth: usize,
 }
 
-impl Runtime {
-	/// Create a new runtime with given code and data.
+impl<'config> Runtime<'config> {
+	/// Create a new runtime with given code and data, running under `config`.
+	/// With `config.has_eof`, code starting with the EOF magic runs as an EOF
+	/// container, and an invalid container exits with `InvalidCode`.
 	pub fn new(
 		code: Vec<u8>,
 		valids: Vec<u8>,
 		data: Vec<u8>,
 		context: Context,
+		config: &'config Config,
 	) -> Self {
+		let machine = if config.has_eof && EofContainer::is_eof(&code) {
+			Machine::new_eof(code, data, config.stack_limit, config.memory_limit).unwrap_or_else(|_| {
+				let mut machine =
+					Machine::new(Vec::new(), Vec::new(), Vec::new(), config.stack_limit, config.memory_limit);
+				machine.exit(ExitError::InvalidCode(Opcode(EOF_MAGIC[0])).into());
+				machine
+			})
+		} else {
+			Machine::new(code, valids, data, config.stack_limit, config.memory_limit)
+		};
+
 		Self {
-			machine: Machine::new(code, valids, data, CONFIG.stack_limit, CONFIG.memory_limit),
+			machine,
 			status: Ok(()),
 			return_data_buffer: Vec::new(),
 			context,
+			config: Cow::Borrowed(config),
+			depth: 0,
+		}
+	}
+
+	/// Report the next step to the inspector of `handler`.
+	fn inspect_step<H: Handler, I: Inspector>(&self, handler: &mut H, inspector: fn(&mut H) -> &mut I) {
+		let position = match self.machine.position() {
+			Ok(position) => *position,
+			Err(_) => return,
+		};
+
+		if let Some((opcode, stack)) = self.machine.inspect() {
+			let gas = handler.gas_left().low_u64();
+			let refund = handler.refunded_gas();
+			inspector(handler).step(&Step {
+				context: &self.context,
+				depth: self.depth,
+				opcode,
+				position,
+				gas,
+				refund,
+				stack,
+				memory: self.machine.memory(),
+				return_data: &self.return_data_buffer,
+			});
 		}
 	}
 
@@ -74,12 +125,46 @@ impl Runtime {
 		&self.machine
 	}
 
+	/// Call depth of the runtime.
+	#[must_use]
+	pub const fn depth(&self) -> usize {
+		self.depth
+	}
+
+	/// Set the call depth of the runtime. A runtime created for a call or
+	/// create should run one level deeper than its caller; `CALL` and
+	/// `CREATE` fail with `CallTooDeep` once the depth reaches
+	/// `call_stack_limit`.
+	pub const fn set_depth(&mut self, depth: usize) {
+		self.depth = depth;
+	}
+
+	/// Get a reference to the runtime configuration.
+	#[must_use]
+	pub fn config(&self) -> &Config {
+		&self.config
+	}
+
 	/// Loop stepping the runtime until it stops.
+	///
+	/// Legacy code runs by basic blocks through `Machine::run`.
 	pub fn run<'a, H: Handler>(
 		&'a mut self,
 		max_steps: u64,
 		handler: &mut H,
-	) -> (u64, Capture<ExitReason, Resolve<'a, H>>) {
+	) -> (u64, Capture<ExitReason, Resolve<'a, 'config, H>>) {
+		self.run_with_inspector(max_steps, handler, |_| Box::leak(Box::new(())))
+	}
+
+	/// Loop stepping the runtime until it stops, like `run`, reporting the
+	/// execution to the inspector `inspector` gets from `handler`. An enabled
+	/// inspector observes every step.
+	pub fn run_with_inspector<'a, H: Handler, I: Inspector>(
+		&'a mut self,
+		max_steps: u64,
+		handler: &mut H,
+		inspector: fn(&mut H) -> &mut I,
+	) -> (u64, Capture<ExitReason, Resolve<'a, 'config, H>>) {
 		if let Err(e) = self.status {
 			return (0, Capture::Exit(e));
 		}
@@ -87,48 +172,96 @@ impl Runtime {
 		let mut steps = 0_u64;
 
 		while steps < max_steps {
-			let (steps_executed, capture) = {
+			if I::ENABLED {
+				self.inspect_step(handler, inspector);
+			}
+
+			let result = {
 				let context = &self.context;
-				let pre_validate = |opcode, stack: &Stack| { handler.pre_validate(context, opcode, stack) };
-				self.machine.run(max_steps - steps, pre_validate, &self.context)
+				let config: &Config = &self.config;
+				let validate = |opcode, stack: &Stack| pre_validate(config, context, handler, opcode, stack);
+
+				if I::ENABLED {
+					self.machine.step(validate).err()
+				} else {
+					let (ran, capture) = self.machine.run(max_steps - steps, validate, context);
+					steps += ran;
+					Some(capture)
+				}
 			};
-			steps += steps_executed;
 
-			match capture {
-				Capture::Exit(ExitReason::StepLimitReached) => {
-					return (steps, Capture::Exit(ExitReason::StepLimitReached));
-				},
-				Capture::Exit(reason) => {
-					self.status = Err(reason);
-					return (steps, Capture::Exit(reason));
-				},
-				Capture::Trap(opcode) => {
-					match eval::eval(self, opcode, handler) {
-						eval::Control::Continue => {},
-						eval::Control::CallInterrupt(interrupt) => {
-							let resolve = ResolveCall::new(self);
-							return (steps, Capture::Trap(Resolve::Call(interrupt, resolve)));
-						},
-						eval::Control::CreateInterrupt(interrupt) => {
-							let resolve = ResolveCreate::new(self);
-							return (steps, Capture::Trap(Resolve::Create(interrupt, resolve)));
-						},
-						eval::Control::Exit(exit) => {
-							self.machine.exit(exit);
-							self.status = Err(exit);
-							return (steps, Capture::Exit(exit));
-						},
-					}
+			let exit = match result {
+				None => None,
+				Some(Capture::Exit(ExitReason::StepLimitReached)) => break,
+				Some(Capture::Exit(reason)) => Some(reason),
+				Some(Capture::Trap(opcode)) => match eval::eval(self, opcode, handler, inspector) {
+					eval::Control::Continue => None,
+					eval::Control::CallInterrupt(interrupt) => {
+						let resolve = ResolveCall::new(self);
+						return (steps, Capture::Trap(Resolve::Call(interrupt, resolve)));
+					},
+					eval::Control::CreateInterrupt(interrupt) => {
+						let resolve = ResolveCreate::new(self);
+						return (steps, Capture::Trap(Resolve::Create(interrupt, resolve)));
+					},
+					eval::Control::Exit(exit) => {
+						self.machine.exit(exit);
+						Some(exit)
+					},
 				},
+			};
+
+			if I::ENABLED {
+				let gas = handler.gas_left().low_u64();
+				inspector(handler).step_result(exit.as_ref(), gas, self.machine.stack(), self.machine.memory());
 			}
+
+			if let Some(reason) = exit {
+				self.status = Err(reason);
+				return (steps, Capture::Exit(reason));
+			}
+			steps += 1;
 		}
 
 		(steps, Capture::Exit(ExitReason::StepLimitReached))
 	}
 }
 
+/// Checks before each opcode: it must be enabled by `config`, must not
+/// change state in static mode, and must pass `handler.pre_validate`, which
+/// charges its gas.
+fn pre_validate<H: Handler>(
+	config: &Config,
+	context: &Context,
+	handler: &mut H,
+	opcode: Opcode,
+	stack: &Stack,
+) -> Result<(), ExitError> {
+	if !config.has_opcode(opcode) {
+		return Err(ExitError::InvalidCode(opcode))
+	}
+	if context.is_static && changes_state(opcode, stack)? {
+		return Err(ExitError::StaticModeViolation)
+	}
+	handler.pre_validate(context, opcode, stack)
+}
+
+/// Whether an opcode changes state, and is therefore rejected in static
+/// mode. `CALL` only changes state if it transfers value.
+fn changes_state(opcode: Opcode, stack: &Stack) -> Result<bool, ExitError> {
+	Ok(match opcode {
+		Opcode::SSTORE | Opcode::TSTORE | Opcode::LOG0 | Opcode::LOG1 | Opcode::LOG2 |
+		Opcode::LOG3 | Opcode::LOG4 | Opcode::CREATE | Opcode::CREATE2 | Opcode::SUICIDE => true,
+		Opcode::CALL => stack.peek(2)? != U256::zero(),
+		_ => false,
+	})
+}
+
 /// Runtime configuration.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "with-codec", derive(codec::Encode, codec::Decode))]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(borsh::BorshSerialize, borsh::BorshDeserialize)]
 pub struct Config {
 	/// Gas paid for extcode.
 	pub gas_ext_code: u64,
@@ -140,10 +273,14 @@ pub struct Config {
 	pub gas_sstore_reset: u64,
 	/// Gas paid for sstore refund.
 	pub refund_sstore_clears: i64,
+	/// Maximum refund quotient; at most `used_gas / max_refund_quotient` is refunded.
+	pub max_refund_quotient: u64,
 	/// Gas paid for BALANCE opcode.
 	pub gas_balance: u64,
 	/// Gas paid for SLOAD opcode.
 	pub gas_sload: u64,
+	/// Gas paid for cold SLOAD opcode.
+	pub gas_sload_cold: u64,
 	/// Gas paid for SUICIDE opcode.
 	pub gas_suicide: u64,
 	/// Gas paid for SUICIDE opcode when it hits a new account.
@@ -160,10 +297,24 @@ pub struct Config {
 	pub gas_transaction_zero_data: u64,
 	/// Gas paid for non-zero data in a transaction.
 	pub gas_transaction_non_zero_data: u64,
+	/// Gas paid per address in transaction access list (see EIP-2930).
+	pub gas_access_list_address: u64,
+	/// Gas paid per storage key in transaction access list (see EIP-2930).
+	pub gas_access_list_storage_key: u64,
+	/// Gas paid for accessing cold account.
+	pub gas_account_access_cold: u64,
 	/// EIP-1283.
 	pub sstore_gas_metering: bool,
 	/// EIP-1706.
 	pub sstore_revert_under_stipend: bool,
+	/// EIP-2929.
+	pub increase_state_access_gas: bool,
+	/// EIP-3529.
+	pub decrease_clears_refund: bool,
+	/// EIP-3541.
+	pub disallow_executable_format: bool,
+	/// EIP-3651.
+	pub warm_coinbase_address: bool,
 	/// Whether to throw out of gas error when
 	/// CALL/CALLCODE/DELEGATECALL requires more than maximum amount
 	/// of gas.
@@ -182,6 +333,8 @@ pub struct Config {
 	pub call_stack_limit: usize,
 	/// Create contract limit.
 	pub create_contract_limit: Option<usize>,
+	/// Init code limit (EIP-3860).
+	pub max_initcode_size: Option<usize>,
 	/// Call stipend.
 	pub call_stipend: u64,
 	/// Has delegate call.
@@ -200,23 +353,165 @@ pub struct Config {
 	pub has_self_balance: bool,
 	/// Has ext code hash.
 	pub has_ext_code_hash: bool,
+	/// Has base fee (EIP-3198).
+	pub has_base_fee: bool,
+	/// Has PUSH0 (EIP-3855).
+	pub has_push0: bool,
+	/// Has transient storage, TLOAD and TSTORE (EIP-1153).
+	pub has_transient_storage: bool,
+	/// Has BLOBHASH and BLOBBASEFEE (EIP-4844, EIP-7516).
+	pub has_blob_opcodes: bool,
+	/// Has MCOPY (EIP-5656).
+	pub has_mcopy: bool,
+	/// `SUICIDE` only deletes an account created in the same transaction,
+	/// and otherwise only sends its balance (EIP-6780).
+	pub suicide_only_in_same_transaction: bool,
+	/// Has EOF containers: parsing, validation and the RJUMP, RJUMPI, RJUMPV,
+	/// CALLF and RETF opcodes (EIP-3540, EIP-3670, EIP-4200, EIP-4750,
+	/// EIP-5450).
+	pub has_eof: bool,
+	/// Has set code transactions with an authorization list (EIP-7702).
+	pub has_authorization_list: bool,
+	/// Gas paid per authorization in a set code transaction (EIP-7702).
+	pub gas_per_auth_base_cost: u64,
+	/// Gas paid per authorization of an empty account (EIP-7702).
+	pub gas_per_empty_account_cost: u64,
+	/// Has calldata floor cost (EIP-7623).
+	pub has_floor_gas: bool,
+	/// Floor gas paid per calldata token (EIP-7623).
+	pub total_cost_floor_per_token: u64,
 	/// Whether the gasometer is running in estimate mode.
 	pub estimate: bool,
 }
 
+/// Default configuration. `Runtime` does not read it; pass a `Config`
+/// to `Runtime::new` instead.
 pub const CONFIG: Config = Config::istanbul();
 
 impl Config {
+	/// Frontier hard fork configuration.
+	#[must_use]
+	pub const fn frontier() -> Self {
+		Self {
+			gas_ext_code: 20,
+			gas_ext_code_hash: 20,
+			gas_balance: 20,
+			gas_sload: 50,
+			gas_sload_cold: 0,
+			gas_sstore_set: 20000,
+			gas_sstore_reset: 5000,
+			refund_sstore_clears: 15000,
+			max_refund_quotient: 2,
+			gas_suicide: 0,
+			gas_suicide_new_account: 0,
+			gas_call: 40,
+			gas_expbyte: 10,
+			gas_transaction_create: 21000,
+			gas_transaction_call: 21000,
+			gas_transaction_zero_data: 4,
+			gas_transaction_non_zero_data: 68,
+			gas_access_list_address: 0,
+			gas_access_list_storage_key: 0,
+			gas_account_access_cold: 0,
+			sstore_gas_metering: false,
+			sstore_revert_under_stipend: false,
+			increase_state_access_gas: false,
+			decrease_clears_refund: false,
+			disallow_executable_format: false,
+			warm_coinbase_address: false,
+			err_on_call_with_more_gas: true,
+			empty_considered_exists: true,
+			create_increase_nonce: false,
+			call_l64_after_gas: false,
+			stack_limit: 1024,
+			memory_limit: usize::max_value(),
+			call_stack_limit: 1024,
+			create_contract_limit: None,
+			max_initcode_size: None,
+			call_stipend: 2300,
+			has_delegate_call: false,
+			has_create2: false,
+			has_revert: false,
+			has_return_data: false,
+			has_bitwise_shifting: false,
+			has_chain_id: false,
+			has_self_balance: false,
+			has_ext_code_hash: false,
+			has_base_fee: false,
+			has_push0: false,
+			has_transient_storage: false,
+			has_blob_opcodes: false,
+			has_mcopy: false,
+			suicide_only_in_same_transaction: false,
+			has_eof: false,
+			has_authorization_list: false,
+			gas_per_auth_base_cost: 0,
+			gas_per_empty_account_cost: 0,
+			has_floor_gas: false,
+			total_cost_floor_per_token: 0,
+			estimate: false,
+		}
+	}
+
+	/// Homestead hard fork configuration.
+	#[must_use]
+	pub const fn homestead() -> Self {
+		Self {
+			gas_transaction_create: 53000,
+			has_delegate_call: true,
+			..Self::frontier()
+		}
+	}
+
+	/// Byzantium hard fork configuration, including Tangerine Whistle and
+	/// Spurious Dragon gas repricing.
+	#[must_use]
+	pub const fn byzantium() -> Self {
+		Self {
+			gas_ext_code: 700,
+			gas_balance: 400,
+			gas_sload: 200,
+			gas_suicide: 5000,
+			gas_suicide_new_account: 25000,
+			gas_call: 700,
+			gas_expbyte: 50,
+			err_on_call_with_more_gas: false,
+			empty_considered_exists: false,
+			create_increase_nonce: true,
+			call_l64_after_gas: true,
+			create_contract_limit: Some(0x6000),
+			has_revert: true,
+			has_return_data: true,
+			..Self::homestead()
+		}
+	}
+
+	/// Constantinople hard fork configuration, as amended by Petersburg
+	/// (without EIP-1283).
+	#[must_use]
+	pub const fn constantinople() -> Self {
+		Self {
+			gas_ext_code_hash: 400,
+			has_create2: true,
+			has_bitwise_shifting: true,
+			has_ext_code_hash: true,
+			..Self::byzantium()
+		}
+	}
+
 	/// Istanbul hard fork configuration.
-	pub const fn istanbul() -> Config {
-		Config {
+	#[must_use]
+	pub const fn istanbul() -> Self {
+		Self {
 			gas_ext_code: 700,
 			gas_ext_code_hash: 700,
 			gas_balance: 700,
 			gas_sload: 800,
+			gas_sload_cold: 0,
 			gas_sstore_set: 20000,
 			gas_sstore_reset: 5000,
 			refund_sstore_clears: 15000,
+			max_refund_quotient: 2,
 			gas_suicide: 5000,
 			gas_suicide_new_account: 25000,
 			gas_call: 700,
@@ -225,8 +520,15 @@ impl Config {
 			gas_transaction_call: 21000,
 			gas_transaction_zero_data: 4,
 			gas_transaction_non_zero_data: 16,
+			gas_access_list_address: 0,
+			gas_access_list_storage_key: 0,
+			gas_account_access_cold: 0,
 			sstore_gas_metering: true,
 			sstore_revert_under_stipend: true,
+			increase_state_access_gas: false,
+			decrease_clears_refund: false,
+			disallow_executable_format: false,
+			warm_coinbase_address: false,
 			err_on_call_with_more_gas: false,
 			empty_considered_exists: false,
 			create_increase_nonce: true,
@@ -235,6 +537,7 @@ impl Config {
 			memory_limit: usize::max_value(),
 			call_stack_limit: 1024,
 			create_contract_limit: Some(0x6000),
+			max_initcode_size: None,
 			call_stipend: 2300,
 			has_delegate_call: true,
 			has_create2: true,
@@ -244,12 +547,163 @@ impl Config {
 			has_chain_id: true,
 			has_self_balance: true,
 			has_ext_code_hash: true,
+			has_base_fee: false,
+			has_push0: false,
+			has_transient_storage: false,
+			has_blob_opcodes: false,
+			has_mcopy: false,
+			suicide_only_in_same_transaction: false,
+			has_eof: false,
+			has_authorization_list: false,
+			gas_per_auth_base_cost: 0,
+			gas_per_empty_account_cost: 0,
+			has_floor_gas: false,
+			total_cost_floor_per_token: 0,
 			estimate: false,
 		}
 	}
 
+	/// Berlin hard fork configuration.
+	///
+	/// With EIP-2929 the account and storage access costs above are the warm
+	/// costs; cold accesses are charged `gas_account_access_cold` and
+	/// `gas_sload_cold` instead.
+	#[must_use]
+	pub const fn berlin() -> Self {
+		Self {
+			gas_ext_code: 100,
+			gas_ext_code_hash: 100,
+			gas_balance: 100,
+			gas_sload: 100,
+			gas_sload_cold: 2100,
+			gas_sstore_reset: 2900,
+			gas_call: 100,
+			gas_access_list_address: 2400,
+			gas_access_list_storage_key: 1900,
+			gas_account_access_cold: 2600,
+			increase_state_access_gas: true,
+			..Self::istanbul()
+		}
+	}
+
+	/// London hard fork configuration.
+	#[must_use]
+	pub const fn london() -> Self {
+		Self {
+			refund_sstore_clears: 4800,
+			max_refund_quotient: 5,
+			decrease_clears_refund: true,
+			disallow_executable_format: true,
+			has_base_fee: true,
+			..Self::berlin()
+		}
+	}
+
+	/// Shanghai hard fork configuration.
+	#[must_use]
+	pub const fn shanghai() -> Self {
+		Self {
+			warm_coinbase_address: true,
+			max_initcode_size: Some(0xc000),
+			has_push0: true,
+			..Self::london()
+		}
+	}
+
+	/// Cancun hard fork configuration.
+	#[must_use]
+	pub const fn cancun() -> Self {
+		Self {
+			has_transient_storage: true,
+			has_blob_opcodes: true,
+			has_mcopy: true,
+			suicide_only_in_same_transaction: true,
+			..Self::shanghai()
+		}
+	}
+
+	/// Prague hard fork configuration.
+	#[must_use]
+	pub const fn prague() -> Self {
+		Self {
+			has_authorization_list: true,
+			gas_per_auth_base_cost: 12500,
+			gas_per_empty_account_cost: 25000,
+			has_floor_gas: true,
+			total_cost_floor_per_token: 10,
+			..Self::cancun()
+		}
+	}
+
+	/// Whether `opcode` is enabled by this configuration. Opcodes introduced
+	/// by a later fork fail with `ExitError::InvalidCode`. `Runtime` checks
+	/// this before each opcode; the core eval table does not, so callers of
+	/// `Machine::run` or `Machine::step` check it in their `pre_validate`.
+	#[must_use]
+	pub const fn has_opcode(&self, opcode: Opcode) -> bool {
+		match opcode {
+			Opcode::DELEGATECALL => self.has_delegate_call,
+			Opcode::CREATE2 => self.has_create2,
+			Opcode::REVERT => self.has_revert,
+			Opcode::RETURNDATASIZE | Opcode::RETURNDATACOPY => self.has_return_data,
+			Opcode::SHL | Opcode::SHR | Opcode::SAR => self.has_bitwise_shifting,
+			Opcode::CHAINID => self.has_chain_id,
+			Opcode::SELFBALANCE => self.has_self_balance,
+			Opcode::EXTCODEHASH => self.has_ext_code_hash,
+			Opcode::BASEFEE => self.has_base_fee,
+			Opcode::PUSH0 => self.has_push0,
+			Opcode::TLOAD | Opcode::TSTORE => self.has_transient_storage,
+			Opcode::BLOBHASH | Opcode::BLOBBASEFEE => self.has_blob_opcodes,
+			Opcode::MCOPY => self.has_mcopy,
+			Opcode::RJUMP | Opcode::RJUMPI | Opcode::RJUMPV | Opcode::CALLF | Opcode::RETF =>
+				self.has_eof,
+			_ => true,
+		}
+	}
+
 	/// Reference to default configuration
 	pub fn default() -> &'static Config {
 		&CONFIG
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use crate::{Config, Opcode};
+
+	#[test]
+	fn test_config_presets() {
+		let frontier = Config::frontier();
+		assert!(!frontier.has_delegate_call && !frontier.has_revert);
+		assert_eq!(frontier.gas_transaction_create, 21000);
+		assert!(Config::homestead().has_delegate_call);
+		assert_eq!(Config::byzantium().create_contract_limit, Some(0x6000));
+		assert!(Config::constantinople().has_create2 && !Config::byzantium().has_create2);
+		assert!(!Config::istanbul().increase_state_access_gas);
+		assert_eq!(Config::berlin().gas_account_access_cold, 2600);
+		assert_eq!(Config::london().max_refund_quotient, 5);
+		assert_eq!(Config::shanghai().max_initcode_size, Some(0xc000));
+		assert!(Config::cancun().suicide_only_in_same_transaction && !Config::shanghai().has_transient_storage);
+
+		let prague = Config::prague();
+		assert!(prague.has_authorization_list && prague.has_floor_gas && prague.has_mcopy);
+		assert!(!prague.has_eof && !Config::cancun().has_floor_gas);
+		assert_eq!(Config::default().gas_sload, Config::istanbul().gas_sload);
+	}
+
+	#[test]
+	fn test_has_opcode() {
+		let frontier = Config::frontier();
+		assert!(frontier.has_opcode(Opcode::ADD));
+		assert!(!frontier.has_opcode(Opcode::DELEGATECALL));
+		assert!(!frontier.has_opcode(Opcode::REVERT));
+		assert!(!Config::byzantium().has_opcode(Opcode::SHL));
+		assert!(Config::constantinople().has_opcode(Opcode::EXTCODEHASH));
+		assert!(!Config::berlin().has_opcode(Opcode::BASEFEE));
+		assert!(!Config::london().has_opcode(Opcode::PUSH0));
+		assert!(Config::shanghai().has_opcode(Opcode::PUSH0));
+		assert!(!Config::shanghai().has_opcode(Opcode::TLOAD));
+		assert!(Config::cancun().has_opcode(Opcode::MCOPY));
+		assert!(!Config::prague().has_opcode(Opcode::RJUMP));
+	}
+}
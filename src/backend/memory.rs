@@ -0,0 +1,240 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use crate::{H160, H256, U256};
+use super::{Apply, ApplyBackend, Backend, Basic, Log};
+
+/// Block environment of a memory backend.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "with-codec", derive(codec::Encode, codec::Decode))]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemoryVicinity {
+	/// Gas price.
+	pub gas_price: U256,
+	/// Origin.
+	pub origin: H160,
+	/// Chain ID.
+	pub chain_id: U256,
+	/// Hashes of the previous blocks, the most recent one last.
+	pub block_hashes: Vec<H256>,
+	/// Block number.
+	pub block_number: U256,
+	/// Block coinbase.
+	pub block_coinbase: H160,
+	/// Block timestamp.
+	pub block_timestamp: U256,
+	/// Block difficulty.
+	pub block_difficulty: U256,
+	/// Block gas limit.
+	pub block_gas_limit: U256,
+	/// Block base fee per gas.
+	pub block_base_fee_per_gas: U256,
+	/// Block blob base fee.
+	pub block_blob_base_fee: U256,
+	/// Versioned hashes of the transaction's blobs.
+	pub blob_hashes: Vec<H256>,
+}
+
+/// Account of a memory backend.
+#[derive(Default, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "with-codec", derive(codec::Encode, codec::Decode))]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemoryAccount {
+	/// Account nonce.
+	pub nonce: U256,
+	/// Account balance.
+	pub balance: U256,
+	/// Full account storage.
+	pub storage: BTreeMap<U256, U256>,
+	/// Account code.
+	#[cfg_attr(feature = "with-serde", serde(with = "serde_bytes"))]
+	pub code: Vec<u8>,
+}
+
+/// In-memory world state.
+#[derive(Clone, Debug)]
+pub struct MemoryBackend<'vicinity> {
+	vicinity: &'vicinity MemoryVicinity,
+	state: BTreeMap<H160, MemoryAccount>,
+	logs: Vec<Log>,
+}
+
+impl<'vicinity> MemoryBackend<'vicinity> {
+	/// Create a new memory backend.
+	#[must_use]
+	pub const fn new(vicinity: &'vicinity MemoryVicinity, state: BTreeMap<H160, MemoryAccount>) -> Self {
+		Self {
+			vicinity,
+			state,
+			logs: Vec::new(),
+		}
+	}
+
+	/// Accounts of the backend.
+	#[must_use]
+	pub const fn state(&self) -> &BTreeMap<H160, MemoryAccount> {
+		&self.state
+	}
+
+	/// Mutable accounts of the backend.
+	pub const fn state_mut(&mut self) -> &mut BTreeMap<H160, MemoryAccount> {
+		&mut self.state
+	}
+
+	/// Logs applied to the backend.
+	#[must_use]
+	pub fn logs(&self) -> &[Log] {
+		&self.logs
+	}
+}
+
+impl Backend for MemoryBackend<'_> {
+	fn gas_price(&self) -> U256 { self.vicinity.gas_price }
+	fn origin(&self) -> H160 { self.vicinity.origin }
+
+	fn block_hash(&self, number: U256) -> H256 {
+		if number >= self.vicinity.block_number ||
+			self.vicinity.block_number - number - U256::one() >= U256::from(self.vicinity.block_hashes.len())
+		{
+			H256::default()
+		} else {
+			let index = (self.vicinity.block_number - number - U256::one()).as_usize();
+			self.vicinity.block_hashes[self.vicinity.block_hashes.len() - 1 - index]
+		}
+	}
+
+	fn block_number(&self) -> U256 { self.vicinity.block_number }
+	fn block_coinbase(&self) -> H160 { self.vicinity.block_coinbase }
+	fn block_timestamp(&self) -> U256 { self.vicinity.block_timestamp }
+	fn block_difficulty(&self) -> U256 { self.vicinity.block_difficulty }
+	fn block_gas_limit(&self) -> U256 { self.vicinity.block_gas_limit }
+	fn block_base_fee_per_gas(&self) -> U256 { self.vicinity.block_base_fee_per_gas }
+	fn block_blob_base_fee(&self) -> U256 { self.vicinity.block_blob_base_fee }
+	fn blob_versioned_hash(&self, index: usize) -> Option<H256> {
+		self.vicinity.blob_hashes.get(index).copied()
+	}
+	fn chain_id(&self) -> U256 { self.vicinity.chain_id }
+
+	fn exists(&self, address: H160) -> bool {
+		self.state.contains_key(&address)
+	}
+
+	fn basic(&self, address: H160) -> Basic {
+		self.state.get(&address).map_or_else(Basic::default, |account| Basic {
+			balance: account.balance,
+			nonce: account.nonce,
+		})
+	}
+
+	fn code(&self, address: H160) -> Vec<u8> {
+		self.state.get(&address).map_or_else(Vec::new, |account| account.code.clone())
+	}
+
+	fn storage(&self, address: H160, index: U256) -> U256 {
+		self.state.get(&address)
+			.and_then(|account| account.storage.get(&index).copied())
+			.unwrap_or_default()
+	}
+
+	fn original_storage(&self, address: H160, index: U256) -> Option<U256> {
+		Some(self.storage(address, index))
+	}
+}
+
+impl ApplyBackend for MemoryBackend<'_> {
+	fn apply<A, I, L>(&mut self, values: A, logs: L, delete_empty: bool)
+	where
+		A: IntoIterator<Item = Apply<I>>,
+		I: IntoIterator<Item = (U256, U256)>,
+		L: IntoIterator<Item = Log>,
+	{
+		for apply in values {
+			match apply {
+				Apply::Modify { address, basic, code, storage, reset_storage } => {
+					let is_empty = {
+						let account = self.state.entry(address).or_default();
+						account.balance = basic.balance;
+						account.nonce = basic.nonce;
+						if let Some(code) = code {
+							account.code = code;
+						}
+
+						if reset_storage {
+							account.storage = BTreeMap::new();
+						}
+
+						for (index, value) in storage {
+							if value == U256::zero() {
+								account.storage.remove(&index);
+							} else {
+								account.storage.insert(index, value);
+							}
+						}
+
+						account.balance == U256::zero() &&
+							account.nonce == U256::zero() &&
+							account.code.is_empty()
+					};
+
+					if is_empty && delete_empty {
+						self.state.remove(&address);
+					}
+				},
+				Apply::Delete { address } => {
+					self.state.remove(&address);
+				},
+			}
+		}
+
+		self.logs.extend(logs);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::collections::BTreeMap;
+	use crate::backend::{vicinity, Apply, ApplyBackend, Backend, Basic, MemoryAccount, MemoryBackend};
+	use crate::{H160, H256, U256};
+
+	#[test]
+	fn test_block_hash() {
+		let vicinity = vicinity();
+		let backend = MemoryBackend::new(&vicinity, BTreeMap::new());
+
+		assert_eq!(backend.block_hash(U256::from(9)), H256::repeat_byte(2));
+		assert_eq!(backend.block_hash(U256::from(8)), H256::repeat_byte(1));
+		assert_eq!(backend.block_hash(U256::from(7)), H256::default());
+		assert_eq!(backend.block_hash(U256::from(10)), H256::default());
+	}
+
+	#[test]
+	fn test_apply() {
+		let vicinity = vicinity();
+		let address = H160::repeat_byte(0xaa);
+		let mut state = BTreeMap::new();
+		state.insert(address, MemoryAccount {
+			balance: U256::from(5),
+			..MemoryAccount::default()
+		});
+		let mut backend = MemoryBackend::new(&vicinity, state);
+
+		backend.apply(vec![Apply::Modify {
+			address,
+			basic: Basic { balance: U256::from(7), nonce: U256::one() },
+			code: None,
+			storage: vec![(U256::one(), U256::from(2))],
+			reset_storage: false,
+		}], Vec::new(), true);
+		assert_eq!(backend.basic(address), Basic { balance: U256::from(7), nonce: U256::one() });
+		assert_eq!(backend.storage(address, U256::one()), U256::from(2));
+
+		// An account emptied by a change is removed with EIP-161.
+		backend.apply(vec![Apply::Modify {
+			address,
+			basic: Basic::default(),
+			code: None,
+			storage: Vec::new(),
+			reset_storage: true,
+		}], Vec::new(), true);
+		assert!(!backend.exists(address));
+	}
+}
@@ -0,0 +1,137 @@
+//! Read-only world state the executors run on.
+
+mod memory;
+
+pub use self::memory::{MemoryAccount, MemoryBackend, MemoryVicinity};
+
+use alloc::vec::Vec;
+use crate::{H160, H256, U256};
+
+/// Basic account information.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "with-codec", derive(codec::Encode, codec::Decode))]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Basic {
+	/// Account balance.
+	pub balance: U256,
+	/// Account nonce.
+	pub nonce: U256,
+}
+
+/// A log emitted during execution.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "with-codec", derive(codec::Encode, codec::Decode))]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Log {
+	/// Address that emitted the log.
+	pub address: H160,
+	/// Topics of the log.
+	pub topics: Vec<H256>,
+	/// Data of the log.
+	#[cfg_attr(feature = "with-serde", serde(with = "serde_bytes"))]
+	pub data: Vec<u8>,
+}
+
+/// Change of an account, to be applied to a backend after execution.
+#[derive(Clone, Debug)]
+pub enum Apply<I> {
+	/// Modify or create an account.
+	Modify {
+		/// Address of the account.
+		address: H160,
+		/// New basic information of the account.
+		basic: Basic,
+		/// New code of the account, if it changed.
+		code: Option<Vec<u8>>,
+		/// Storage slots to set.
+		storage: I,
+		/// Whether the storage should be wiped before setting the slots.
+		reset_storage: bool,
+	},
+	/// Delete an account.
+	Delete {
+		/// Address of the account.
+		address: H160,
+	},
+}
+
+/// Read-only world state: accounts and the block environment.
+pub trait Backend {
+	/// Gas price of the transaction.
+	fn gas_price(&self) -> U256;
+	/// Origin of the transaction.
+	fn origin(&self) -> H160;
+	/// Hash of the block at `number`.
+	fn block_hash(&self, number: U256) -> H256;
+	/// Current block number.
+	fn block_number(&self) -> U256;
+	/// Current block coinbase.
+	fn block_coinbase(&self) -> H160;
+	/// Current block timestamp.
+	fn block_timestamp(&self) -> U256;
+	/// Current block difficulty.
+	fn block_difficulty(&self) -> U256;
+	/// Current block gas limit.
+	fn block_gas_limit(&self) -> U256;
+	/// Current block base fee per gas (EIP-1559).
+	fn block_base_fee_per_gas(&self) -> U256;
+	/// Current block blob base fee (EIP-7516).
+	fn block_blob_base_fee(&self) -> U256;
+	/// Versioned hash of the transaction's blob at `index` (EIP-4844).
+	fn blob_versioned_hash(&self, index: usize) -> Option<H256>;
+	/// Chain ID.
+	fn chain_id(&self) -> U256;
+
+	/// Whether an account exists.
+	fn exists(&self, address: H160) -> bool;
+	/// Balance and nonce of an account.
+	fn basic(&self, address: H160) -> Basic;
+	/// Code of an account.
+	fn code(&self, address: H160) -> Vec<u8>;
+	/// Storage value of an account at `index`.
+	fn storage(&self, address: H160, index: U256) -> U256;
+	/// Storage value of an account at `index` as it was at the start of the
+	/// transaction, or `None` if the backend does not keep track of it.
+	fn original_storage(&self, address: H160, index: U256) -> Option<U256>;
+}
+
+/// Backend that can apply the changes of an execution.
+pub trait ApplyBackend {
+	/// Apply the given account changes and logs. If `delete_empty` is set,
+	/// accounts left empty are removed (EIP-161).
+	fn apply<A, I, L>(&mut self, values: A, logs: L, delete_empty: bool)
+	where
+		A: IntoIterator<Item = Apply<I>>,
+		I: IntoIterator<Item = (U256, U256)>,
+		L: IntoIterator<Item = Log>;
+}
+
+/// Block environment of the tests.
+#[cfg(test)]
+pub(crate) fn vicinity() -> MemoryVicinity {
+	MemoryVicinity {
+		gas_price: U256::zero(),
+		origin: H160::default(),
+		chain_id: U256::one(),
+		block_hashes: alloc::vec![H256::repeat_byte(1), H256::repeat_byte(2)],
+		block_number: U256::from(10),
+		block_coinbase: H160::default(),
+		block_timestamp: U256::zero(),
+		block_difficulty: U256::zero(),
+		block_gas_limit: U256::from(30_000_000),
+		block_base_fee_per_gas: U256::from(7),
+		block_blob_base_fee: U256::from(3),
+		blob_hashes: alloc::vec![H256::repeat_byte(0x01)],
+	}
+}
+
+/// Run `f` on a memory backend holding `accounts`, in the block environment
+/// of the tests.
+#[cfg(test)]
+pub(crate) fn with_memory_backend<R>(
+	accounts: alloc::collections::BTreeMap<H160, MemoryAccount>,
+	f: impl FnOnce(&MemoryBackend<'_>) -> R,
+) -> R {
+	let vicinity = vicinity();
+	f(&MemoryBackend::new(&vicinity, accounts))
+}
@@ -0,0 +1,5 @@
+//! Executors that drive the runtime over a world state.
+
+mod stack;
+
+pub use self::stack::{Authorization, MemoryStackState, StackExecutor, StackState};
@@ -0,0 +1,65 @@
+use sha3::{Digest, Keccak256};
+use crate::{H160, H256, U256};
+
+/// Magic byte prefixed to the RLP of an authorization before signing it.
+const AUTHORIZATION_MAGIC: u8 = 0x05;
+
+/// A signed authorization of a set code transaction (EIP-7702), delegating
+/// the code of its signer, the authority, to `address`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "with-codec", derive(codec::Encode, codec::Decode))]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Authorization {
+	/// Chain the authorization is valid on, or zero for any chain.
+	pub chain_id: U256,
+	/// Address to delegate to, or zero to clear the delegation.
+	pub address: H160,
+	/// Nonce of the authority.
+	pub nonce: U256,
+	/// Signature y parity.
+	pub y_parity: u8,
+	/// Signature r.
+	pub r: H256,
+	/// Signature s.
+	pub s: H256,
+}
+
+impl Authorization {
+	/// Hash signed by the authority, `keccak256(0x05 || rlp([chain_id,
+	/// address, nonce]))`.
+	#[must_use]
+	pub fn signing_hash(&self) -> H256 {
+		let mut stream = rlp::RlpStream::new_list(3);
+		stream.append(&self.chain_id);
+		stream.append(&self.address);
+		stream.append(&self.nonce);
+
+		let mut hasher = Keccak256::new();
+		hasher.input([AUTHORIZATION_MAGIC]);
+		hasher.input(stream.out());
+		H256::from_slice(hasher.result().as_slice())
+	}
+
+	/// Recover the authority from the signature. Signatures with a high `s`
+	/// are rejected (EIP-2).
+	#[must_use]
+	pub fn authority(&self) -> Option<H160> {
+		if self.y_parity > 1 {
+			return None
+		}
+
+		let mut signature = [0u8; 64];
+		signature[..32].copy_from_slice(&self.r[..]);
+		signature[32..].copy_from_slice(&self.s[..]);
+
+		let message = libsecp256k1::Message::parse_slice(&self.signing_hash()[..]).ok()?;
+		let signature = libsecp256k1::Signature::parse_standard(&signature).ok()?;
+		if signature.s.is_high() {
+			return None
+		}
+		let recovery_id = libsecp256k1::RecoveryId::parse(self.y_parity).ok()?;
+		let public = libsecp256k1::recover(&message, &signature, &recovery_id).ok()?;
+
+		Some(H160::from_slice(&Keccak256::digest(&public.serialize()[1..])[12..]))
+	}
+}
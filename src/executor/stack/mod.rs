@@ -0,0 +1,1524 @@
+mod authorization;
+mod state;
+
+pub use self::authorization::Authorization;
+pub use self::state::{MemoryStackState, StackState};
+
+use core::cmp::{max, min};
+use core::convert::{Infallible, TryFrom};
+use alloc::vec::Vec;
+use sha3::{Digest, Keccak256};
+use crate::gasometer::{self, Gasometer, StorageTarget};
+use crate::precompile::{PrecompileOutput, PrecompileSet};
+use crate::{
+	delegation_designator, CallScheme, Capture, Config, Context, CreateScheme, EofContainer, ExitError,
+	ExitFatal, ExitReason, ExitSucceed, Handler, Inspector, Machine, Opcode, Resolve, Runtime, Stack, Transfer,
+	Valids, H160, H256, U256,
+};
+
+/// How a call or create frame ended.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum StackExitKind {
+	Succeeded,
+	Reverted,
+	Failed,
+}
+
+/// Gas and static flag of a call or create frame.
+struct StackFrame<'config> {
+	gasometer: Gasometer<'config>,
+	is_static: bool,
+}
+
+/// Stack-based executor.
+///
+/// Calls and creates are executed recursively: each of them runs a child
+/// `Runtime` to completion inside `Handler::call` or `Handler::create`, with
+/// its own gasometer and a checkpoint of the state. Calls to an address of
+/// the `PrecompileSet` run the precompile instead of the account code. The
+/// `Inspector` observes the execution, and `()` disables it.
+pub struct StackExecutor<'config, S, P = (), I = ()> {
+	config: &'config Config,
+	state: S,
+	precompiles: P,
+	inspector: I,
+	/// The first frame holds the transaction gas; frame `n` runs at call
+	/// depth `n - 1`.
+	frames: Vec<StackFrame<'config>>,
+	/// Calldata floor cost of the last transaction (EIP-7623).
+	floor_gas: u64,
+}
+
+impl<'config, S: StackState> StackExecutor<'config, S> {
+	/// Create a new stack-based executor without precompiles.
+	pub const fn new(state: S, config: &'config Config) -> Self {
+		Self::new_with_precompiles(state, config, ())
+	}
+}
+
+impl<'config, S: StackState, P: PrecompileSet> StackExecutor<'config, S, P> {
+	/// Create a new stack-based executor with a set of precompiles.
+	pub const fn new_with_precompiles(state: S, config: &'config Config, precompiles: P) -> Self {
+		Self::new_with_inspector(state, config, precompiles, ())
+	}
+}
+
+impl<'config, S: StackState, P: PrecompileSet, I: Inspector> StackExecutor<'config, S, P, I> {
+	/// Create a new stack-based executor with a set of precompiles and an
+	/// inspector.
+	pub const fn new_with_inspector(state: S, config: &'config Config, precompiles: P, inspector: I) -> Self {
+		Self {
+			config,
+			state,
+			precompiles,
+			inspector,
+			frames: Vec::new(),
+			floor_gas: 0,
+		}
+	}
+
+	/// Inspector of the executor.
+	pub const fn inspector(&self) -> &I {
+		&self.inspector
+	}
+
+	/// Precompiles of the executor.
+	pub const fn precompiles(&self) -> &P {
+		&self.precompiles
+	}
+
+	/// Configuration of the executor.
+	pub const fn config(&self) -> &'config Config {
+		self.config
+	}
+
+	/// Reference of the state.
+	pub const fn state(&self) -> &S {
+		&self.state
+	}
+
+	/// Mutable reference of the state.
+	pub const fn state_mut(&mut self) -> &mut S {
+		&mut self.state
+	}
+
+	/// Consume the executor and return the state.
+	pub fn into_state(self) -> S {
+		self.state
+	}
+
+	/// Remaining gas of the last transaction.
+	pub fn gas(&self) -> u64 {
+		self.frames.first().map_or(0, |frame| frame.gasometer.gas())
+	}
+
+	/// Gas used by the last transaction, after refunds, and at least its
+	/// calldata floor cost (EIP-7623).
+	#[allow(clippy::cast_sign_loss)]
+	pub fn used_gas(&self) -> u64 {
+		self.frames.first().map_or(0, |frame| {
+			let used_gas = frame.gasometer.total_used_gas();
+			let refunded_gas = frame.gasometer.refunded_gas().max(0) as u64;
+
+			max(used_gas - min(used_gas / self.config.max_refund_quotient, refunded_gas), self.floor_gas)
+		})
+	}
+
+	/// Fee of the last transaction at the given gas price.
+	pub fn fee(&self, price: U256) -> U256 {
+		U256::from(self.used_gas()).saturating_mul(price)
+	}
+
+	/// Execute a runtime until it exits, charging gas to the current frame.
+	/// Only meaningful while a transaction is being executed.
+	#[allow(clippy::match_same_arms)]
+	pub fn execute(&mut self, runtime: &mut Runtime) -> ExitReason {
+		loop {
+			match runtime.run_with_inspector(u64::MAX, self, |executor| &mut executor.inspector) {
+				(_, Capture::Exit(ExitReason::StepLimitReached)) => (),
+				(_, Capture::Exit(reason)) => return reason,
+				(_, Capture::Trap(Resolve::Call(interrupt, _))) => match interrupt {},
+				(_, Capture::Trap(Resolve::Create(interrupt, _))) => match interrupt {},
+			}
+		}
+	}
+
+	/// Execute a `CREATE` transaction.
+	pub fn transact_create(
+		&mut self,
+		caller: H160,
+		value: U256,
+		init_code: Vec<u8>,
+		gas_limit: u64,
+		access_list: &[(H160, Vec<H256>)],
+	) -> (ExitReason, Vec<u8>) {
+		let cost = gasometer::create_transaction_cost(&init_code, access_list);
+		if let Err(e) = self.enter_transaction(caller, gas_limit, cost, access_list) {
+			return (e.into(), Vec::new())
+		}
+
+		match self.create_inner(
+			caller,
+			CreateScheme::Legacy { caller },
+			value,
+			init_code,
+			Some(gas_limit),
+			false,
+		) {
+			Capture::Exit((reason, _, return_data)) => (reason, return_data),
+			Capture::Trap(interrupt) => match interrupt {},
+		}
+	}
+
+	/// Execute a `CREATE2` transaction.
+	pub fn transact_create2(
+		&mut self,
+		caller: H160,
+		value: U256,
+		init_code: Vec<u8>,
+		salt: H256,
+		gas_limit: u64,
+		access_list: &[(H160, Vec<H256>)],
+	) -> (ExitReason, Vec<u8>) {
+		let cost = gasometer::create_transaction_cost(&init_code, access_list);
+		if let Err(e) = self.enter_transaction(caller, gas_limit, cost, access_list) {
+			return (e.into(), Vec::new())
+		}
+
+		let code_hash = H256::from_slice(Keccak256::digest(&init_code).as_slice());
+		match self.create_inner(
+			caller,
+			CreateScheme::Create2 { caller, code_hash, salt },
+			value,
+			init_code,
+			Some(gas_limit),
+			false,
+		) {
+			Capture::Exit((reason, _, return_data)) => (reason, return_data),
+			Capture::Trap(interrupt) => match interrupt {},
+		}
+	}
+
+	/// Execute a `CALL` transaction.
+	pub fn transact_call(
+		&mut self,
+		caller: H160,
+		address: H160,
+		value: U256,
+		data: Vec<u8>,
+		gas_limit: u64,
+		access_list: &[(H160, Vec<H256>)],
+	) -> (ExitReason, Vec<u8>) {
+		self.transact_set_code(caller, address, value, data, gas_limit, access_list, &[])
+	}
+
+	/// Execute a set code transaction (EIP-7702): apply the authorizations,
+	/// skipping invalid ones, then call `address`. An empty authorization
+	/// list makes it a plain `CALL` transaction.
+	#[allow(clippy::too_many_arguments)]
+	pub fn transact_set_code(
+		&mut self,
+		caller: H160,
+		address: H160,
+		value: U256,
+		data: Vec<u8>,
+		gas_limit: u64,
+		access_list: &[(H160, Vec<H256>)],
+		authorization_list: &[Authorization],
+	) -> (ExitReason, Vec<u8>) {
+		if !authorization_list.is_empty() && !self.config.has_authorization_list {
+			return (ExitFatal::NotSupported.into(), Vec::new())
+		}
+
+		let cost = gasometer::set_code_transaction_cost(&data, access_list, authorization_list.len());
+		if let Err(e) = self.enter_transaction(caller, gas_limit, cost, access_list) {
+			return (e.into(), Vec::new())
+		}
+
+		self.state.inc_nonce(caller);
+		for authorization in authorization_list {
+			if let Err(e) = self.apply_authorization(authorization) {
+				return (e.into(), Vec::new())
+			}
+		}
+		self.state.mark_warm(address, None);
+		if let Some(delegate) = self.delegated_address(address) {
+			self.state.mark_warm(delegate, None);
+		}
+
+		let context = Context {
+			caller,
+			address,
+			apparent_value: value,
+			is_static: false,
+		};
+
+		match self.call_inner(
+			address,
+			CallScheme::Call,
+			Some(Transfer { source: caller, target: address, value }),
+			data,
+			Some(gas_limit),
+			false,
+			false,
+			context,
+		) {
+			Capture::Exit((reason, return_data)) => (reason, return_data),
+			Capture::Trap(interrupt) => match interrupt {},
+		}
+	}
+
+	/// Get the address a create scheme would deploy to.
+	pub fn create_address(&self, scheme: CreateScheme) -> H160 {
+		match scheme {
+			CreateScheme::Create2 { caller, code_hash, salt } => {
+				let mut hasher = Keccak256::new();
+				hasher.input([0xff]);
+				hasher.input(&caller[..]);
+				hasher.input(&salt[..]);
+				hasher.input(&code_hash[..]);
+				H256::from_slice(hasher.result().as_slice()).into()
+			},
+			CreateScheme::Legacy { caller } => {
+				let nonce = self.state.basic(caller).nonce;
+				let mut stream = rlp::RlpStream::new_list(2);
+				stream.append(&caller);
+				stream.append(&nonce);
+				H256::from_slice(Keccak256::digest(&stream.out()).as_slice()).into()
+			},
+			CreateScheme::Fixed(address) => address,
+		}
+	}
+
+	fn enter_transaction(
+		&mut self,
+		caller: H160,
+		gas_limit: u64,
+		cost: gasometer::TransactionCost,
+		access_list: &[(H160, Vec<H256>)],
+	) -> Result<(), ExitError> {
+		self.frames.clear();
+		self.state.begin_transaction();
+		self.floor_gas = gasometer::transaction_floor_cost(cost, self.config);
+		if gas_limit < self.floor_gas {
+			return Err(ExitError::OutOfGas)
+		}
+		self.frames.push(StackFrame {
+			gasometer: Gasometer::new(gas_limit, self.config),
+			is_static: false,
+		});
+		self.gasometer_mut().record_transaction(cost)?;
+
+		self.state.mark_warm(caller, None);
+		if self.config.warm_coinbase_address {
+			let coinbase = self.state.block_coinbase();
+			self.state.mark_warm(coinbase, None);
+		}
+		for (address, keys) in access_list {
+			self.state.mark_warm(*address, None);
+			for key in keys {
+				self.state.mark_warm(*address, Some(U256::from_big_endian(&key[..])));
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Apply an authorization of a set code transaction, or skip it if it is
+	/// invalid for the current chain and state.
+	fn apply_authorization(&mut self, authorization: &Authorization) -> Result<(), ExitError> {
+		if authorization.chain_id != U256::zero() && authorization.chain_id != self.state.chain_id() {
+			return Ok(())
+		}
+		if authorization.nonce >= U256::from(u64::MAX) {
+			return Ok(())
+		}
+		let Some(authority) = authorization.authority() else {
+			return Ok(())
+		};
+
+		self.state.mark_warm(authority, None);
+		let code = self.state.code(authority);
+		if !code.is_empty() && crate::delegated_address(&code).is_none() {
+			return Ok(())
+		}
+		if self.state.basic(authority).nonce != authorization.nonce {
+			return Ok(())
+		}
+
+		if self.state.exists(authority) {
+			let refund = self.config.gas_per_empty_account_cost.saturating_sub(self.config.gas_per_auth_base_cost);
+			self.gasometer_mut().record_refund(i64::try_from(refund).unwrap_or(i64::MAX))?;
+		}
+
+		if authorization.address == H160::zero() {
+			self.state.set_code(authority, Vec::new());
+		} else {
+			self.state.set_code(authority, delegation_designator(authorization.address));
+		}
+		self.state.inc_nonce(authority);
+
+		Ok(())
+	}
+
+	fn frame(&self) -> &StackFrame<'config> {
+		self.frames.last().expect("executor has no frame")
+	}
+
+	fn gasometer_mut(&mut self) -> &mut Gasometer<'config> {
+		&mut self.frames.last_mut().expect("executor has no frame").gasometer
+	}
+
+	/// Call depth of the current call or create frame.
+	const fn depth(&self) -> usize {
+		self.frames.len() - 2
+	}
+
+	fn enter_frame(&mut self, gas_limit: u64, is_static: bool) {
+		self.frames.push(StackFrame {
+			gasometer: Gasometer::new(gas_limit, self.config),
+			is_static,
+		});
+		self.state.checkpoint();
+	}
+
+	fn exit_frame(&mut self, kind: StackExitKind) -> Result<(), ExitError> {
+		let frame = self.frames.pop().expect("exit without a matching enter");
+
+		match kind {
+			StackExitKind::Succeeded => {
+				self.state.commit();
+				self.gasometer_mut().record_stipend(frame.gasometer.gas())?;
+				self.gasometer_mut().record_refund(frame.gasometer.refunded_gas())
+			},
+			StackExitKind::Reverted => {
+				self.state.revert();
+				self.gasometer_mut().record_stipend(frame.gasometer.gas())
+			},
+			StackExitKind::Failed => {
+				self.state.revert();
+				Ok(())
+			},
+		}
+	}
+
+	/// Exit a call frame, reporting its result to the inspector. Failing to
+	/// commit a succeeded frame turns its result into the error.
+	fn exit_call(
+		&mut self,
+		kind: StackExitKind,
+		reason: ExitReason,
+		output: Vec<u8>,
+	) -> Capture<(ExitReason, Vec<u8>), Infallible> {
+		self.inspect_exit(kind, reason, &output);
+		match self.exit_frame(kind) {
+			Err(e) if kind == StackExitKind::Succeeded => Capture::Exit((e.into(), Vec::new())),
+			_ => Capture::Exit((reason, output)),
+		}
+	}
+
+	/// Exit a create frame, like `exit_call`. The inspector sees the
+	/// deployed code as the output of a succeeded create, which returns no
+	/// data to its caller.
+	fn exit_create(
+		&mut self,
+		kind: StackExitKind,
+		reason: ExitReason,
+		address: Option<H160>,
+		output: Vec<u8>,
+	) -> Capture<(ExitReason, Option<H160>, Vec<u8>), Infallible> {
+		self.inspect_exit(kind, reason, &output);
+		let output = if kind == StackExitKind::Succeeded { Vec::new() } else { output };
+		match self.exit_frame(kind) {
+			Err(e) if kind == StackExitKind::Succeeded => Capture::Exit((e.into(), None, Vec::new())),
+			_ => Capture::Exit((reason, address, output)),
+		}
+	}
+
+	fn inspect_exit(&mut self, kind: StackExitKind, reason: ExitReason, output: &[u8]) {
+		let gas_left = if kind == StackExitKind::Failed { 0 } else { self.frame().gasometer.gas() };
+		self.inspector.exit(&reason, output, gas_left);
+	}
+
+	/// Gas available to a child frame, keeping 1/64 back if `take_l64` is
+	/// set and EIP-150 is enabled.
+	fn child_gas(&mut self, take_l64: bool) -> Result<u64, ExitError> {
+		const fn l64(gas: u64) -> u64 {
+			gas - gas / 64
+		}
+
+		let gas = self.gasometer_mut().gas();
+		if take_l64 && self.config.call_l64_after_gas {
+			if self.config.estimate {
+				self.gasometer_mut().record_cost(gas - l64(gas))?;
+				Ok(self.gasometer_mut().gas())
+			} else {
+				Ok(l64(gas))
+			}
+		} else {
+			Ok(gas)
+		}
+	}
+
+	fn is_empty(&self, address: H160) -> bool {
+		let basic = self.state.basic(address);
+
+		basic.balance == U256::zero() &&
+			basic.nonce == U256::zero() &&
+			self.state.code(address).is_empty()
+	}
+
+	fn create_inner(
+		&mut self,
+		caller: H160,
+		scheme: CreateScheme,
+		value: U256,
+		init_code: Vec<u8>,
+		target_gas: Option<u64>,
+		take_l64: bool,
+	) -> Capture<(ExitReason, Option<H160>, Vec<u8>), Infallible> {
+		macro_rules! try_or_fail {
+			( $e:expr ) => {
+				match $e {
+					Ok(v) => v,
+					Err(e) => return Capture::Exit((e.into(), None, Vec::new())),
+				}
+			}
+		}
+
+		if self.state.basic(caller).balance < value {
+			return Capture::Exit((ExitError::OutOfFund.into(), None, Vec::new()))
+		}
+
+		let after_gas = try_or_fail!(self.child_gas(take_l64));
+		let gas_limit = min(target_gas.unwrap_or(after_gas), after_gas);
+		try_or_fail!(self.gasometer_mut().record_cost(gas_limit));
+
+		let address = self.create_address(scheme);
+		self.state.mark_warm(address, None);
+		self.state.inc_nonce(caller);
+
+		self.inspector.create(scheme, address, value, &init_code, gas_limit);
+		self.enter_frame(gas_limit, false);
+
+		if !self.state.code(address).is_empty() || self.state.basic(address).nonce > U256::zero() {
+			return self.exit_create(StackExitKind::Failed, ExitError::CreateCollision.into(), None, Vec::new())
+		}
+		self.state.reset_storage(address);
+		self.state.set_created(address);
+
+		if let Err(e) = self.state.transfer(Transfer { source: caller, target: address, value }) {
+			return self.exit_create(StackExitKind::Reverted, e.into(), None, Vec::new())
+		}
+
+		if self.config.create_increase_nonce {
+			self.state.inc_nonce(address);
+		}
+
+		let context = Context {
+			address,
+			caller,
+			apparent_value: value,
+			is_static: false,
+		};
+		// EOF initcode deploys an EOF container, and legacy initcode legacy code.
+		let is_eof = self.config.has_eof && EofContainer::is_eof(&init_code);
+		let valids = Valids::compute(&init_code);
+		let mut runtime = Runtime::new(init_code, valids, Vec::new(), context, self.config);
+		runtime.set_depth(self.depth());
+
+		match self.execute(&mut runtime) {
+			ExitReason::Succeed(s) => {
+				let out = runtime.machine().return_value();
+
+				if let Some(limit) = self.config.create_contract_limit {
+					if out.len() > limit {
+						self.gasometer_mut().fail();
+						return self.exit_create(StackExitKind::Failed, ExitError::CreateContractLimit.into(), None, Vec::new())
+					}
+				}
+
+				let is_valid = if is_eof {
+					EofContainer::parse(&out).and_then(|container| container.validate()).is_ok()
+				} else {
+					!(self.config.disallow_executable_format && out.first() == Some(&0xef))
+				};
+				if !is_valid {
+					let reason = ExitError::InvalidCode(Opcode(0xef)).into();
+					return self.exit_create(StackExitKind::Failed, reason, None, Vec::new())
+				}
+
+				if let Err(e) = self.gasometer_mut().record_deposit(out.len()) {
+					return self.exit_create(StackExitKind::Failed, e.into(), None, Vec::new())
+				}
+
+				self.state.set_code(address, out.clone());
+				self.exit_create(StackExitKind::Succeeded, s.into(), Some(address), out)
+			},
+			ExitReason::Error(e) => self.exit_create(StackExitKind::Failed, e.into(), None, Vec::new()),
+			ExitReason::Revert(e) =>
+				self.exit_create(StackExitKind::Reverted, e.into(), None, runtime.machine().return_value()),
+			ExitReason::Fatal(e) => {
+				self.gasometer_mut().fail();
+				self.exit_create(StackExitKind::Failed, e.into(), None, Vec::new())
+			},
+			ExitReason::StepLimitReached => unreachable!("runtime is executed until it exits"),
+		}
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	fn call_inner(
+		&mut self,
+		code_address: H160,
+		scheme: CallScheme,
+		transfer: Option<Transfer>,
+		input: Vec<u8>,
+		target_gas: Option<u64>,
+		take_l64: bool,
+		take_stipend: bool,
+		context: Context,
+	) -> Capture<(ExitReason, Vec<u8>), Infallible> {
+		macro_rules! try_or_fail {
+			( $e:expr ) => {
+				match $e {
+					Ok(v) => v,
+					Err(e) => return Capture::Exit((e.into(), Vec::new())),
+				}
+			}
+		}
+
+		let after_gas = try_or_fail!(self.child_gas(take_l64));
+		let mut gas_limit = min(target_gas.unwrap_or(after_gas), after_gas);
+		try_or_fail!(self.gasometer_mut().record_cost(gas_limit));
+
+		if let Some(transfer) = transfer.as_ref() {
+			if take_stipend && transfer.value != U256::zero() {
+				gas_limit = gas_limit.saturating_add(self.config.call_stipend);
+			}
+		}
+
+		let code = self.state.code(self.delegated_address(code_address).unwrap_or(code_address));
+
+		self.inspector.call(scheme, code_address, transfer.as_ref(), &input, gas_limit, &context);
+		self.enter_frame(gas_limit, context.is_static);
+		self.state.touch(context.address);
+
+		if let Some(transfer) = transfer {
+			if let Err(e) = self.state.transfer(transfer) {
+				return self.exit_call(StackExitKind::Reverted, e.into(), Vec::new())
+			}
+		}
+
+		if let Some(result) = self.precompiles.execute(code_address, &input, gas_limit, &context, context.is_static) {
+			let result = result.and_then(|PrecompileOutput { output, cost }| {
+				self.gasometer_mut().record_cost(cost)?;
+				Ok(output)
+			});
+
+			return match result {
+				Ok(output) => self.exit_call(StackExitKind::Succeeded, ExitSucceed::Returned.into(), output),
+				Err(e) => self.exit_call(StackExitKind::Failed, e.into(), Vec::new()),
+			}
+		}
+
+		let valids = Valids::compute(&code);
+		let mut runtime = Runtime::new(code, valids, input, context, self.config);
+		runtime.set_depth(self.depth());
+
+		match self.execute(&mut runtime) {
+			ExitReason::Succeed(s) =>
+				self.exit_call(StackExitKind::Succeeded, s.into(), runtime.machine().return_value()),
+			ExitReason::Error(e) => self.exit_call(StackExitKind::Failed, e.into(), Vec::new()),
+			ExitReason::Revert(e) =>
+				self.exit_call(StackExitKind::Reverted, e.into(), runtime.machine().return_value()),
+			ExitReason::Fatal(e) => {
+				self.gasometer_mut().fail();
+				self.exit_call(StackExitKind::Failed, e.into(), Vec::new())
+			},
+			ExitReason::StepLimitReached => unreachable!("runtime is executed until it exits"),
+		}
+	}
+}
+
+impl<S: StackState, P: PrecompileSet, I: Inspector> Handler for StackExecutor<'_, S, P, I> {
+	type CreateInterrupt = Infallible;
+	type CreateFeedback = Infallible;
+	type CallInterrupt = Infallible;
+	type CallFeedback = Infallible;
+
+	fn keccak256_h256(&self, data: &[u8]) -> H256 {
+		H256::from_slice(Keccak256::digest(data).as_slice())
+	}
+
+	fn nonce(&self, address: H160) -> U256 {
+		self.state.basic(address).nonce
+	}
+
+	fn balance(&self, address: H160) -> U256 {
+		self.state.basic(address).balance
+	}
+
+	fn code_size(&self, address: H160) -> U256 {
+		U256::from(self.state.code(address).len())
+	}
+
+	fn code_hash(&self, address: H160) -> H256 {
+		if !self.exists(address) {
+			return H256::default()
+		}
+
+		H256::from_slice(Keccak256::digest(&self.state.code(address)).as_slice())
+	}
+
+	fn code(&self, address: H160) -> Vec<u8> {
+		self.state.code(address)
+	}
+
+	fn delegated_address(&self, address: H160) -> Option<H160> {
+		if !self.config.has_authorization_list {
+			return None
+		}
+
+		crate::delegated_address(&self.state.code(address))
+	}
+
+	fn valids(&self, address: H160) -> Vec<u8> {
+		Valids::compute(&self.state.code(address))
+	}
+
+	fn storage(&self, address: H160, index: U256) -> U256 {
+		self.state.storage(address, index)
+	}
+
+	fn transient_storage(&self, address: H160, index: U256) -> U256 {
+		self.state.transient_storage(address, index)
+	}
+
+	fn original_storage(&self, address: H160, index: U256) -> U256 {
+		self.state.original_storage(address, index)
+			.unwrap_or_else(|| self.state.storage(address, index))
+	}
+
+	fn exists(&self, address: H160) -> bool {
+		if self.config.empty_considered_exists {
+			self.state.exists(address)
+		} else {
+			self.state.exists(address) && !self.is_empty(address)
+		}
+	}
+
+	fn deleted(&self, address: H160) -> bool {
+		self.state.deleted(address)
+	}
+
+	fn is_cold(&self, address: H160, index: Option<U256>) -> bool {
+		if index.is_none() && self.precompiles.is_precompile(address) {
+			return false
+		}
+
+		self.state.is_cold(address, index)
+	}
+
+	fn gas_left(&self) -> U256 {
+		U256::from(self.frame().gasometer.gas())
+	}
+
+	fn refunded_gas(&self) -> i64 {
+		self.frames.iter().map(|frame| frame.gasometer.refunded_gas()).sum()
+	}
+
+	fn gas_price(&self) -> U256 {
+		self.state.gas_price()
+	}
+
+	fn origin(&self) -> H160 {
+		self.state.origin()
+	}
+
+	fn block_hash(&self, number: U256) -> H256 {
+		self.state.block_hash(number)
+	}
+
+	fn block_number(&self) -> U256 {
+		self.state.block_number()
+	}
+
+	fn block_coinbase(&self) -> H160 {
+		self.state.block_coinbase()
+	}
+
+	fn block_timestamp(&self) -> U256 {
+		self.state.block_timestamp()
+	}
+
+	fn block_difficulty(&self) -> U256 {
+		self.state.block_difficulty()
+	}
+
+	fn block_gas_limit(&self) -> U256 {
+		self.state.block_gas_limit()
+	}
+
+	fn block_base_fee_per_gas(&self) -> U256 {
+		self.state.block_base_fee_per_gas()
+	}
+
+	fn block_blob_base_fee(&self) -> U256 {
+		self.state.block_blob_base_fee()
+	}
+
+	fn blob_versioned_hash(&self, index: usize) -> Option<H256> {
+		self.state.blob_versioned_hash(index)
+	}
+
+	fn chain_id(&self) -> U256 {
+		self.state.chain_id()
+	}
+
+	fn set_storage(&mut self, address: H160, index: U256, value: U256) -> Result<(), ExitError> {
+		self.state.set_storage(address, index, value);
+		Ok(())
+	}
+
+	fn set_transient_storage(&mut self, address: H160, index: U256, value: U256) -> Result<(), ExitError> {
+		self.state.set_transient_storage(address, index, value);
+		Ok(())
+	}
+
+	fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) -> Result<(), ExitError> {
+		self.state.log(address, topics, data);
+		Ok(())
+	}
+
+	fn mark_delete(&mut self, address: H160, target: H160) -> Result<(), ExitError> {
+		let balance = self.state.basic(address).balance;
+		self.inspector.suicide(address, target, balance);
+
+		self.state.transfer(Transfer {
+			source: address,
+			target,
+			value: balance,
+		})?;
+		if self.config.suicide_only_in_same_transaction && !self.state.created(address) {
+			return Ok(())
+		}
+
+		self.state.reset_balance(address);
+		self.state.set_deleted(address);
+
+		Ok(())
+	}
+
+	fn create(
+		&mut self,
+		caller: H160,
+		scheme: CreateScheme,
+		value: U256,
+		init_code: Vec<u8>,
+		target_gas: Option<u64>,
+		_depth: usize,
+	) -> Capture<(ExitReason, Option<H160>, Vec<u8>), Self::CreateInterrupt> {
+		self.create_inner(caller, scheme, value, init_code, target_gas, true)
+	}
+
+	fn call(
+		&mut self,
+		code_address: H160,
+		scheme: CallScheme,
+		transfer: Option<Transfer>,
+		input: Vec<u8>,
+		target_gas: Option<u64>,
+		is_static: bool,
+		context: Context,
+		_depth: usize,
+	) -> Capture<(ExitReason, Vec<u8>), Self::CallInterrupt> {
+		let mut context = context;
+		context.is_static |= is_static || self.frame().is_static;
+		self.call_inner(code_address, scheme, transfer, input, target_gas, true, true, context)
+	}
+
+	fn pre_validate(
+		&mut self,
+		context: &Context,
+		opcode: Opcode,
+		stack: &Stack,
+	) -> Result<(), ExitError> {
+		if let Some(cost) = gasometer::static_opcode_cost(opcode) {
+			return self.gasometer_mut().record_cost(cost)
+		}
+
+		let (gas_cost, target, memory_cost) = gasometer::dynamic_opcode_cost(
+			context.address,
+			opcode,
+			stack,
+			self.frame().is_static,
+			self.config,
+			self,
+		)?;
+		self.gasometer_mut().record_dynamic_cost(gas_cost, memory_cost)?;
+
+		match target {
+			StorageTarget::Address(address) => {
+				self.state.mark_warm(address, None);
+				// Calls are charged for the account their target delegates to.
+				if matches!(opcode, Opcode::CALL | Opcode::CALLCODE | Opcode::DELEGATECALL | Opcode::STATICCALL) {
+					if let Some(delegate) = self.delegated_address(address) {
+						self.state.mark_warm(delegate, None);
+					}
+				}
+			},
+			StorageTarget::Slot(address, index) => self.state.mark_warm(address, Some(index)),
+			StorageTarget::None => (),
+		}
+
+		Ok(())
+	}
+
+	fn other(
+		&mut self,
+		_opcode: Opcode,
+		_machine: &mut Machine,
+	) -> Result<(), ExitFatal> {
+		Err(ExitFatal::NotSupported)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::collections::BTreeMap;
+	use alloc::vec::Vec;
+	use crate::backend::{vicinity, ApplyBackend, Backend, MemoryAccount, MemoryBackend};
+	use sha3::{Digest, Keccak256};
+	use crate::executor::{Authorization, MemoryStackState, StackExecutor, StackState};
+	use crate::precompile::StandardPrecompileSet;
+	use crate::{
+		delegation_designator, CallScheme, Config, Context, CreateScheme, ExitError, ExitReason,
+		ExitRevert, ExitSucceed, Inspector, Opcode, Step, Transfer, H160, H256, U256,
+	};
+
+	fn account(code: Vec<u8>) -> MemoryAccount {
+		MemoryAccount {
+			code,
+			..MemoryAccount::default()
+		}
+	}
+
+	#[test]
+	fn test_checkpoint_revert() {
+		let vicinity = vicinity();
+		let backend = MemoryBackend::new(&vicinity, BTreeMap::new());
+		let mut state = MemoryStackState::new(&backend);
+		let address = H160::repeat_byte(1);
+
+		state.checkpoint();
+		state.set_storage(address, U256::zero(), U256::one());
+		state.checkpoint();
+		state.set_storage(address, U256::zero(), U256::from(2));
+		state.set_transient_storage(address, U256::zero(), U256::one());
+		state.log(address, Vec::new(), Vec::new());
+		state.revert();
+
+		assert_eq!(state.storage(address, U256::zero()), U256::one());
+		assert_eq!(state.transient_storage(address, U256::zero()), U256::zero());
+		assert!(state.logs().is_empty());
+
+		state.checkpoint();
+		state.set_storage(address, U256::zero(), U256::from(3));
+		state.commit();
+		assert_eq!(state.storage(address, U256::zero()), U256::from(3));
+
+		state.revert();
+		assert_eq!(state.storage(address, U256::zero()), U256::zero());
+	}
+
+	#[test]
+	fn test_transactions() {
+		let caller = H160::repeat_byte(0xca);
+		let target = H160::repeat_byte(0xaa);
+		let other = H160::repeat_byte(0xbb);
+
+		let vicinity = vicinity();
+		let mut accounts = BTreeMap::new();
+		accounts.insert(caller, MemoryAccount::default());
+		// TSTORE(0, TLOAD(0) + 1); SSTORE(0, TLOAD(0)); STOP
+		accounts.insert(target, account(hex::decode("60005c600101805f5d5f5500").unwrap()));
+		accounts.insert(other, account(hex::decode("00").unwrap()));
+		let backend = MemoryBackend::new(&vicinity, accounts);
+
+		let config = Config::cancun();
+		let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+
+		// Transient storage and original values do not carry over to the next
+		// transaction.
+		for _ in 0..2 {
+			let (reason, _) = executor.transact_call(caller, target, U256::zero(), Vec::new(), 100_000, &[]);
+			assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+			assert_eq!(executor.state().storage(target, U256::zero()), U256::one());
+		}
+		assert_eq!(executor.state().original_storage(target, U256::zero()), Some(U256::one()));
+
+		// Neither do accessed accounts.
+		let (reason, _) = executor.transact_call(caller, other, U256::zero(), Vec::new(), 100_000, &[]);
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+		assert!(executor.state().is_cold(target, None));
+		assert!(!executor.state().is_cold(other, None));
+
+		// Nor do accounts deleted before EIP-6780.
+		let mut accounts = BTreeMap::new();
+		accounts.insert(caller, MemoryAccount::default());
+		// SSTORE(0, 1); SELFDESTRUCT(CALLER)
+		accounts.insert(target, MemoryAccount {
+			balance: U256::from(100),
+			..account(hex::decode("600160005533ff").unwrap())
+		});
+		let mut backend = MemoryBackend::new(&vicinity, accounts);
+
+		let config = Config::istanbul();
+		let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+
+		let (reason, _) = executor.transact_call(caller, target, U256::zero(), Vec::new(), 100_000, &[]);
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Suicided));
+		assert!(executor.state().deleted(target));
+
+		// The account is gone in the next transaction, which runs no code.
+		let (reason, _) = executor.transact_call(caller, target, U256::zero(), Vec::new(), 100_000, &[]);
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+		let state = executor.state();
+		assert!(state.code(target).is_empty());
+		assert_eq!(state.storage(target, U256::zero()), U256::zero());
+		assert!(!state.exists(target));
+		assert_eq!(state.basic(caller).balance, U256::from(100));
+
+		let (applies, logs) = executor.into_state().deconstruct();
+		backend.apply(applies, logs, false);
+		assert!(!backend.exists(target));
+	}
+
+	#[test]
+	fn test_reverted_call() {
+		let caller = H160::repeat_byte(0xca);
+		let outer = H160::repeat_byte(0xaa);
+		let inner = H160::repeat_byte(0xbb);
+
+		// SSTORE(0, 42); REVERT(0, 0)
+		let inner_code = hex::decode("602a60005560006000fd").unwrap();
+		// SSTORE(0, 1); SSTORE(1, CALL(GAS, inner, 0, 0, 0, 0, 0)); STOP
+		let outer_code = hex::decode(
+			"60016000556000600060006000600073bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb5af160015500"
+		).unwrap();
+
+		let vicinity = vicinity();
+		let mut accounts = BTreeMap::new();
+		accounts.insert(caller, MemoryAccount::default());
+		accounts.insert(outer, account(outer_code));
+		accounts.insert(inner, account(inner_code));
+		let mut backend = MemoryBackend::new(&vicinity, accounts);
+
+		let config = Config::istanbul();
+		let state = MemoryStackState::new(&backend);
+		let mut executor = StackExecutor::new(state, &config);
+
+		let (reason, _) = executor.transact_call(caller, outer, U256::zero(), Vec::new(), 1_000_000, &[]);
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+		assert!(executor.used_gas() > 21_000);
+
+		let (applies, logs) = executor.into_state().deconstruct();
+		backend.apply(applies, logs, false);
+
+		assert_eq!(backend.storage(outer, U256::zero()), U256::one());
+		assert_eq!(backend.storage(outer, U256::one()), U256::zero());
+		assert_eq!(backend.storage(inner, U256::zero()), U256::zero());
+		assert_eq!(backend.basic(caller).nonce, U256::one());
+	}
+
+	#[test]
+	fn test_static_call() {
+		let caller = H160::repeat_byte(0xca);
+		let outer = H160::repeat_byte(0xaa);
+		let inner = H160::repeat_byte(0xbb);
+
+		// SSTORE(0, ISZERO(STATICCALL(GAS, inner, 0, 0, 0, 0))); STOP
+		let outer_code = hex::decode(
+			"600060006000600073bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb5afa1560005500"
+		).unwrap();
+
+		let vicinity = vicinity();
+		let mut accounts = BTreeMap::new();
+		accounts.insert(caller, MemoryAccount::default());
+		accounts.insert(outer, account(outer_code));
+		// SSTORE(0, 42); STOP
+		accounts.insert(inner, account(hex::decode("602a60005500").unwrap()));
+		let backend = MemoryBackend::new(&vicinity, accounts);
+
+		let config = Config::istanbul();
+		let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+
+		let (reason, _) = executor.transact_call(caller, outer, U256::zero(), Vec::new(), 3_000_000, &[]);
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+		assert_eq!(executor.state().storage(outer, U256::zero()), U256::one());
+		assert_eq!(executor.state().storage(inner, U256::zero()), U256::zero());
+	}
+
+	#[test]
+	fn test_call_depth() {
+		let caller = H160::repeat_byte(0xca);
+		let target = H160::repeat_byte(0xaa);
+
+		// SSTORE(0, SLOAD(0) + 1); CALL(GAS, ADDRESS, 0, 0, 0, 0, 0); STOP
+		let code = hex::decode("60005460010160005560006000600060006000305af15000").unwrap();
+
+		let vicinity = vicinity();
+		let mut accounts = BTreeMap::new();
+		accounts.insert(caller, MemoryAccount::default());
+		accounts.insert(target, account(code));
+		let backend = MemoryBackend::new(&vicinity, accounts);
+
+		let mut config = Config::istanbul();
+		config.call_stack_limit = 2;
+		let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+
+		let (reason, _) = executor.transact_call(caller, target, U256::zero(), Vec::new(), 1_000_000, &[]);
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+		// Frames at depth 0, 1 and 2 run; the call from depth 2 fails.
+		assert_eq!(executor.state().storage(target, U256::zero()), U256::from(3));
+	}
+
+	#[test]
+	fn test_base_fee() {
+		let caller = H160::repeat_byte(0xca);
+		let target = H160::repeat_byte(0xaa);
+
+		let vicinity = vicinity();
+		let mut accounts = BTreeMap::new();
+		accounts.insert(caller, MemoryAccount::default());
+		// SSTORE(0, BASEFEE); STOP
+		accounts.insert(target, account(hex::decode("4860005500").unwrap()));
+		let backend = MemoryBackend::new(&vicinity, accounts);
+
+		let config = Config::london();
+		let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+
+		let (reason, _) = executor.transact_call(caller, target, U256::zero(), Vec::new(), 100_000, &[]);
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+		assert_eq!(executor.state().storage(target, U256::zero()), U256::from(7));
+	}
+
+	#[test]
+	fn test_blob_opcodes() {
+		let caller = H160::repeat_byte(0xca);
+		let target = H160::repeat_byte(0xaa);
+
+		let vicinity = vicinity();
+		let mut accounts = BTreeMap::new();
+		accounts.insert(caller, MemoryAccount::default());
+		// SSTORE(0, BLOBHASH(0)); SSTORE(1, BLOBHASH(1)); SSTORE(2, BLOBBASEFEE); STOP
+		accounts.insert(target, account(hex::decode("6000496000556001496001554a60025500").unwrap()));
+		let backend = MemoryBackend::new(&vicinity, accounts);
+
+		let config = Config::cancun();
+		let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+
+		let (reason, _) = executor.transact_call(caller, target, U256::zero(), Vec::new(), 100_000, &[]);
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+		let state = executor.state();
+		assert_eq!(state.storage(target, U256::zero()), U256::from_big_endian(&[0x01; 32]));
+		assert_eq!(state.storage(target, U256::one()), U256::zero());
+		assert_eq!(state.storage(target, U256::from(2)), U256::from(3));
+
+		let config = Config::shanghai();
+		let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+		let (reason, _) = executor.transact_call(caller, target, U256::zero(), Vec::new(), 100_000, &[]);
+		assert_eq!(reason, ExitReason::Error(ExitError::InvalidCode(Opcode::BLOBHASH)));
+	}
+
+	#[test]
+	fn test_fork_opcodes() {
+		let caller = H160::repeat_byte(0xca);
+		let target = H160::repeat_byte(0xaa);
+
+		let vicinity = vicinity();
+		let mut accounts = BTreeMap::new();
+		accounts.insert(caller, MemoryAccount::default());
+		// SSTORE(0, PUSH0); STOP
+		accounts.insert(target, account(hex::decode("5f60005500").unwrap()));
+		let backend = MemoryBackend::new(&vicinity, accounts);
+
+		let config = Config::london();
+		let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+		let (reason, _) = executor.transact_call(caller, target, U256::zero(), Vec::new(), 100_000, &[]);
+		assert_eq!(reason, ExitReason::Error(ExitError::InvalidCode(Opcode::PUSH0)));
+
+		let config = Config::shanghai();
+		let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+		let (reason, _) = executor.transact_call(caller, target, U256::zero(), Vec::new(), 100_000, &[]);
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+
+		// REVERT(0, 0)
+		let mut accounts = BTreeMap::new();
+		accounts.insert(caller, MemoryAccount::default());
+		accounts.insert(target, account(hex::decode("60006000fd").unwrap()));
+		let backend = MemoryBackend::new(&vicinity, accounts);
+
+		let config = Config::frontier();
+		let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+		let (reason, _) = executor.transact_call(caller, target, U256::zero(), Vec::new(), 100_000, &[]);
+		assert_eq!(reason, ExitReason::Error(ExitError::InvalidCode(Opcode::REVERT)));
+
+		let config = Config::byzantium();
+		let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+		let (reason, _) = executor.transact_call(caller, target, U256::zero(), Vec::new(), 100_000, &[]);
+		assert_eq!(reason, ExitReason::Revert(ExitRevert::Reverted));
+	}
+
+	#[test]
+	fn test_reverted_transaction() {
+		let caller = H160::repeat_byte(0xca);
+		let target = H160::repeat_byte(0xaa);
+
+		let vicinity = vicinity();
+		let mut accounts = BTreeMap::new();
+		accounts.insert(caller, MemoryAccount {
+			balance: U256::from(100),
+			..MemoryAccount::default()
+		});
+		accounts.insert(target, account(hex::decode("602a60005560006000fd").unwrap()));
+		let backend = MemoryBackend::new(&vicinity, accounts);
+
+		let config = Config::istanbul();
+		let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+
+		let (reason, _) = executor.transact_call(caller, target, U256::from(10), Vec::new(), 100_000, &[]);
+		assert_eq!(reason, ExitReason::Revert(ExitRevert::Reverted));
+
+		let state = executor.state();
+		assert_eq!(state.storage(target, U256::zero()), U256::zero());
+		assert_eq!(state.basic(caller).balance, U256::from(100));
+		assert_eq!(state.basic(caller).nonce, U256::one());
+		assert_eq!(state.code(target).len(), 10);
+	}
+
+	#[test]
+	fn test_precompile_call() {
+		let caller = H160::repeat_byte(0xca);
+		let target = H160::repeat_byte(0xaa);
+
+		// CALL(GAS, 0x04, 0, 0, 32, 0, 32) on memory holding 42; RETURN(0, 32)
+		let code = hex::decode("602a6000526020600060206000600060045af15060206000f3").unwrap();
+
+		let vicinity = vicinity();
+		let mut accounts = BTreeMap::new();
+		accounts.insert(caller, MemoryAccount::default());
+		accounts.insert(target, account(code));
+		let backend = MemoryBackend::new(&vicinity, accounts);
+
+		let config = Config::berlin();
+		let state = MemoryStackState::new(&backend);
+		let mut executor = StackExecutor::new_with_precompiles(state, &config, StandardPrecompileSet::berlin());
+
+		let (reason, output) = executor.transact_call(caller, target, U256::zero(), Vec::new(), 100_000, &[]);
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Returned));
+		assert_eq!(U256::from_big_endian(&output), U256::from(42));
+	}
+
+	#[test]
+	fn test_floor_gas() {
+		let caller = H160::repeat_byte(0xca);
+		let target = H160::repeat_byte(0xaa);
+
+		let vicinity = vicinity();
+		let mut accounts = BTreeMap::new();
+		accounts.insert(caller, MemoryAccount::default());
+		accounts.insert(target, account(hex::decode("00").unwrap()));
+		let backend = MemoryBackend::new(&vicinity, accounts);
+		// 100 non-zero bytes: 22600 intrinsic gas, 25000 floor.
+		let data = vec![0x01; 100];
+
+		let config = Config::prague();
+		let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+		let (reason, _) = executor.transact_call(caller, target, U256::zero(), data.clone(), 24_000, &[]);
+		assert_eq!(reason, ExitReason::Error(ExitError::OutOfGas));
+
+		let (reason, _) = executor.transact_call(caller, target, U256::zero(), data.clone(), 100_000, &[]);
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+		assert_eq!(executor.used_gas(), 25_000);
+
+		let config = Config::cancun();
+		let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+		let (reason, _) = executor.transact_call(caller, target, U256::zero(), data, 100_000, &[]);
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+		assert_eq!(executor.used_gas(), 22_600);
+	}
+
+	#[test]
+	fn test_set_code() {
+		let caller = H160::repeat_byte(0xca);
+		let delegate = H160::repeat_byte(0xde);
+		let probe = H160::repeat_byte(0xaa);
+
+		let secret = libsecp256k1::SecretKey::parse(&[0x42; 32]).unwrap();
+		let public = libsecp256k1::PublicKey::from_secret_key(&secret);
+		let authority = H160::from_slice(&Keccak256::digest(&public.serialize()[1..])[12..]);
+
+		let mut authorization = Authorization {
+			chain_id: U256::one(),
+			address: delegate,
+			nonce: U256::zero(),
+			y_parity: 0,
+			r: H256::zero(),
+			s: H256::zero(),
+		};
+		let scalar = |bytes: &[u8; 32]| {
+			let mut scalar = libsecp256k1::curve::Scalar::default();
+			let _ = scalar.set_b32(bytes);
+			scalar
+		};
+		let (r, s, y_parity) = libsecp256k1::ECMULT_GEN_CONTEXT.sign_raw(
+			&scalar(&[0x42; 32]),
+			&scalar(&authorization.signing_hash().0),
+			&scalar(&[0x07; 32]),
+		).unwrap();
+		authorization.y_parity = y_parity;
+		authorization.r = H256(r.b32());
+		authorization.s = H256(s.b32());
+		assert_eq!(authorization.authority(), Some(authority));
+
+		let vicinity = vicinity();
+		let mut accounts = BTreeMap::new();
+		accounts.insert(caller, MemoryAccount::default());
+		// SSTORE(0, 42); STOP
+		accounts.insert(delegate, account(hex::decode("602a60005500").unwrap()));
+		// SSTORE(0, EXTCODESIZE(authority)); STOP
+		let probe_code = [&[0x73][..], authority.as_bytes(), &hex::decode("3b60005500").unwrap()].concat();
+		accounts.insert(probe, account(probe_code));
+		let mut backend = MemoryBackend::new(&vicinity, accounts);
+
+		let config = Config::prague();
+		let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+		let (reason, _) = executor.transact_set_code(
+			caller, authority, U256::zero(), Vec::new(), 100_000, &[], &[authorization.clone()],
+		);
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+
+		// Replaying the authorization fails the nonce check and is skipped.
+		let (reason, _) = executor.transact_set_code(
+			caller, probe, U256::zero(), Vec::new(), 100_000, &[], &[authorization],
+		);
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+
+		let (applies, logs) = executor.into_state().deconstruct();
+		backend.apply(applies, logs, false);
+
+		assert_eq!(backend.code(authority), delegation_designator(delegate));
+		assert_eq!(backend.basic(authority).nonce, U256::one());
+		assert_eq!(backend.storage(authority, U256::zero()), U256::from(42));
+		assert_eq!(backend.storage(delegate, U256::zero()), U256::zero());
+		assert_eq!(backend.storage(probe, U256::zero()), U256::from(23));
+	}
+
+	#[test]
+	fn test_delegate_warm() {
+		let caller = H160::repeat_byte(0xca);
+		let authority = H160::repeat_byte(0x11);
+		let delegate = H160::repeat_byte(0xde);
+		let probe = H160::repeat_byte(0xaa);
+
+		let vicinity = vicinity();
+		let mut accounts = BTreeMap::new();
+		accounts.insert(caller, MemoryAccount::default());
+		accounts.insert(authority, account(delegation_designator(delegate)));
+		accounts.insert(delegate, account(hex::decode("00").unwrap()));
+		// CALL(GAS, authority, 0, 0, 0, 0, 0); STOP
+		let probe_code = [
+			&hex::decode("60006000600060006000").unwrap()[..], &[0x73], authority.as_bytes(), &[0x5a, 0xf1, 0x00],
+		].concat();
+		accounts.insert(probe, account(probe_code));
+		let backend = MemoryBackend::new(&vicinity, accounts);
+
+		// The call fails as too deep after being charged for the cold delegate,
+		// which is warm from then on.
+		let mut config = Config::prague();
+		config.call_stack_limit = 0;
+		let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+		let (reason, _) = executor.transact_call(caller, probe, U256::zero(), Vec::new(), 100_000, &[]);
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+		assert!(!executor.state().is_cold(authority, None));
+		assert!(!executor.state().is_cold(delegate, None));
+	}
+
+	#[test]
+	fn test_suicide() {
+		let caller = H160::repeat_byte(0xca);
+		let target = H160::repeat_byte(0xaa);
+		let beneficiary = H160::repeat_byte(0xbe);
+		// SUICIDE(beneficiary)
+		let code = hex::decode("73bebebebebebebebebebebebebebebebebebebebeff").unwrap();
+
+		for (config, deleted) in [(Config::istanbul(), true), (Config::cancun(), false)] {
+			let vicinity = vicinity();
+			let mut accounts = BTreeMap::new();
+			accounts.insert(caller, MemoryAccount {
+				balance: U256::from(10),
+				..MemoryAccount::default()
+			});
+			accounts.insert(target, MemoryAccount {
+				balance: U256::from(100),
+				..account(code.clone())
+			});
+			let mut backend = MemoryBackend::new(&vicinity, accounts);
+			let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+
+			let (reason, _) = executor.transact_call(caller, target, U256::zero(), Vec::new(), 100_000, &[]);
+			assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Suicided));
+			assert_eq!(executor.state().deleted(target), deleted);
+
+			// An account created in the same transaction is always deleted.
+			let created = executor.create_address(CreateScheme::Legacy { caller });
+			let (reason, _) = executor.transact_create(caller, U256::from(10), code.clone(), 100_000, &[]);
+			assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Suicided));
+			assert!(executor.state().deleted(created));
+
+			let (applies, logs) = executor.into_state().deconstruct();
+			backend.apply(applies, logs, false);
+
+			assert_eq!(backend.basic(beneficiary).balance, U256::from(110));
+			assert_eq!(backend.exists(target), !deleted);
+			assert_eq!(backend.code(target).is_empty(), deleted);
+		}
+	}
+
+	#[test]
+	fn test_eof_create() {
+		let caller = H160::repeat_byte(0xca);
+
+		let vicinity = vicinity();
+		let mut accounts = BTreeMap::new();
+		accounts.insert(caller, MemoryAccount::default());
+		let backend = MemoryBackend::new(&vicinity, accounts);
+
+		let mut config = Config::prague();
+		config.has_eof = true;
+		let mut executor = StackExecutor::new(MemoryStackState::new(&backend), &config);
+
+		// Legacy initcode returning a valid EOF container.
+		let init_code = hex::decode("73ef000101000402000100010300000000000000006000526014600cf3").unwrap();
+		let (reason, _) = executor.transact_create(caller, U256::zero(), init_code, 100_000, &[]);
+		assert_eq!(reason, ExitReason::Error(ExitError::InvalidCode(Opcode(0xef))));
+
+		// EOF initcode returning legacy code: RETURN(0, 1).
+		let init_code = hex::decode("ef00010100040200010004030000000000000260015ff3").unwrap();
+		let (reason, _) = executor.transact_create(caller, U256::zero(), init_code, 100_000, &[]);
+		assert_eq!(reason, ExitReason::Error(ExitError::InvalidCode(Opcode(0xef))));
+	}
+
+	#[derive(Default)]
+	struct Recorder {
+		opcodes: Vec<(usize, Opcode)>,
+		sstores: Vec<(H160, U256, U256)>,
+		frames: Vec<(CallScheme, H160, u64)>,
+		exits: Vec<(ExitReason, u64)>,
+	}
+
+	impl Inspector for Recorder {
+		fn step(&mut self, step: &Step<'_>) {
+			self.opcodes.push((step.depth, step.opcode));
+		}
+
+		fn sstore(&mut self, address: H160, index: U256, value: U256) {
+			self.sstores.push((address, index, value));
+		}
+
+		fn call(
+			&mut self,
+			scheme: CallScheme,
+			code_address: H160,
+			_transfer: Option<&Transfer>,
+			_input: &[u8],
+			gas: u64,
+			_context: &Context,
+		) {
+			self.frames.push((scheme, code_address, gas));
+		}
+
+		fn exit(&mut self, reason: &ExitReason, _return_value: &[u8], gas_left: u64) {
+			self.exits.push((*reason, gas_left));
+		}
+	}
+
+	#[test]
+	fn test_inspector() {
+		let caller = H160::repeat_byte(0xca);
+		let outer = H160::repeat_byte(0xaa);
+		let inner = H160::repeat_byte(0xbb);
+
+		// SSTORE(0, 1); SSTORE(1, CALL(GAS, inner, 0, 0, 0, 0, 0)); STOP
+		let outer_code = hex::decode(
+			"60016000556000600060006000600073bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb5af160015500"
+		).unwrap();
+
+		let vicinity = vicinity();
+		let mut accounts = BTreeMap::new();
+		accounts.insert(caller, MemoryAccount::default());
+		accounts.insert(outer, account(outer_code));
+		// SSTORE(0, 42); REVERT(0, 0)
+		accounts.insert(inner, account(hex::decode("602a60005560006000fd").unwrap()));
+		let backend = MemoryBackend::new(&vicinity, accounts);
+
+		let config = Config::istanbul();
+		let mut recorder = Recorder::default();
+		let mut executor = StackExecutor::new_with_inspector(
+			MemoryStackState::new(&backend), &config, (), &mut recorder,
+		);
+		let (reason, _) = executor.transact_call(caller, outer, U256::zero(), Vec::new(), 100_000, &[]);
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+		let gas = executor.gas();
+		drop(executor);
+
+		assert_eq!(recorder.opcodes.len(), 20);
+		assert_eq!(recorder.opcodes[0], (0, Opcode::PUSH1));
+		assert_eq!(recorder.opcodes[11], (1, Opcode::PUSH1));
+		assert_eq!(recorder.opcodes[19], (0, Opcode::STOP));
+		assert_eq!(recorder.sstores, vec![
+			(outer, U256::zero(), U256::one()),
+			(inner, U256::zero(), U256::from(42)),
+			(outer, U256::one(), U256::zero()),
+		]);
+		assert_eq!(recorder.frames.len(), 2);
+		assert_eq!(recorder.frames[0], (CallScheme::Call, outer, 100_000 - 21_000));
+		assert_eq!(recorder.frames[1].0, CallScheme::Call);
+		assert_eq!(recorder.exits[0].0, ExitReason::Revert(ExitRevert::Reverted));
+		assert_eq!(recorder.exits[1], (ExitReason::Succeed(ExitSucceed::Stopped), gas));
+	}
+
+	#[test]
+	fn test_call_schemes() {
+		let caller = H160::repeat_byte(0xca);
+		let target = H160::repeat_byte(0xaa);
+
+		// Without call data, call the same account with one byte of call data.
+		for (code, scheme) in [
+			// DELEGATECALL(GAS, ADDRESS, 0, 1, 0, 0)
+			("366010576000600060016000305af4505b00", CallScheme::DelegateCall),
+			// CALLCODE(GAS, ADDRESS, 0, 0, 1, 0, 0)
+			("3660125760006000600160006000305af2505b00", CallScheme::CallCode),
+		] {
+			let vicinity = vicinity();
+			let mut accounts = BTreeMap::new();
+			accounts.insert(caller, MemoryAccount::default());
+			accounts.insert(target, account(hex::decode(code).unwrap()));
+			let backend = MemoryBackend::new(&vicinity, accounts);
+
+			let config = Config::istanbul();
+			let mut recorder = Recorder::default();
+			let mut executor = StackExecutor::new_with_inspector(
+				MemoryStackState::new(&backend), &config, (), &mut recorder,
+			);
+			let (reason, _) = executor.transact_call(caller, target, U256::zero(), Vec::new(), 100_000, &[]);
+			assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+			drop(executor);
+
+			assert_eq!(recorder.frames.len(), 2);
+			assert_eq!(recorder.frames[1].0, scheme);
+			assert_eq!(recorder.frames[1].1, target);
+		}
+	}
+}
@@ -0,0 +1,418 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use crate::backend::{Apply, Backend, Basic, Log};
+use crate::{ExitError, Transfer, H160, H256, U256};
+
+/// World state the stack executor runs on: the current view of a
+/// `Backend` together with the changes of the running transaction.
+///
+/// Every call or create frame opens a checkpoint with `checkpoint`, and
+/// closes it with either `commit`, which merges its changes into the parent
+/// frame, or `revert`, which drops them.
+pub trait StackState: Backend {
+	/// Transient storage value of an account at `index`.
+	fn transient_storage(&self, address: H160, index: U256) -> U256;
+	/// Whether an account has been marked as deleted.
+	fn deleted(&self, address: H160) -> bool;
+	/// Whether an account has been created in the running transaction.
+	fn created(&self, address: H160) -> bool;
+	/// Whether an account, or one of its storage slots, has not been
+	/// accessed yet in the transaction.
+	fn is_cold(&self, address: H160, index: Option<U256>) -> bool;
+
+	/// Start a new transaction: remove the accounts deleted by the previous
+	/// one, and drop what only lives for the duration of a transaction:
+	/// transient storage, accessed and created accounts, and original
+	/// storage values.
+	fn begin_transaction(&mut self);
+	/// Open a checkpoint for a new call frame.
+	fn checkpoint(&mut self);
+	/// Merge the changes since the last checkpoint into the parent frame.
+	fn commit(&mut self);
+	/// Drop the changes since the last checkpoint.
+	fn revert(&mut self);
+
+	/// Mark an account, or one of its storage slots, as accessed.
+	fn mark_warm(&mut self, address: H160, index: Option<U256>);
+	/// Increase the nonce of an account.
+	fn inc_nonce(&mut self, address: H160);
+	/// Set storage value of an account at `index`.
+	fn set_storage(&mut self, address: H160, index: U256, value: U256);
+	/// Clear the whole storage of an account.
+	fn reset_storage(&mut self, address: H160);
+	/// Set transient storage value of an account at `index`.
+	fn set_transient_storage(&mut self, address: H160, index: U256, value: U256);
+	/// Append a log.
+	fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>);
+	/// Mark an account as deleted.
+	fn set_deleted(&mut self, address: H160);
+	/// Mark an account as created in the running transaction.
+	fn set_created(&mut self, address: H160);
+	/// Set the code of an account.
+	fn set_code(&mut self, address: H160, code: Vec<u8>);
+	/// Transfer value between two accounts.
+	fn transfer(&mut self, transfer: Transfer) -> Result<(), ExitError>;
+	/// Set the balance of an account to zero.
+	fn reset_balance(&mut self, address: H160);
+	/// Mark an account as touched (EIP-161).
+	fn touch(&mut self, address: H160);
+}
+
+/// Account changed by the running transaction.
+#[derive(Clone, Debug)]
+struct MemoryStackAccount {
+	basic: Basic,
+	code: Option<Vec<u8>>,
+	reset: bool,
+}
+
+impl MemoryStackAccount {
+	/// Whether the account has no balance, nonce or code (EIP-161).
+	fn is_empty(&self) -> bool {
+		self.basic.balance.is_zero() && self.basic.nonce.is_zero() && self.code.as_ref().is_none_or(Vec::is_empty)
+	}
+}
+
+/// Undo information of a single change.
+#[derive(Clone, Debug)]
+enum JournalEntry {
+	Account(H160, Option<MemoryStackAccount>),
+	Storage(H160, U256, Option<U256>),
+	TransientStorage(H160, U256, Option<U256>),
+	Log,
+	Deleted(H160),
+	Created(H160),
+	Accessed(H160, Option<U256>),
+}
+
+/// Journaled in-memory state on top of a `Backend`.
+///
+/// Every change is recorded in a journal, so that `revert` can undo all
+/// changes made since the matching `checkpoint`, including storage,
+/// transient storage, balances, logs and deletions.
+#[derive(Clone, Debug)]
+pub struct MemoryStackState<'backend, B> {
+	backend: &'backend B,
+	accounts: BTreeMap<H160, MemoryStackAccount>,
+	storages: BTreeMap<(H160, U256), U256>,
+	/// Storage values at the start of the running transaction, for the
+	/// slots it changed.
+	original_storages: BTreeMap<(H160, U256), U256>,
+	transient_storages: BTreeMap<(H160, U256), U256>,
+	logs: Vec<Log>,
+	deletes: BTreeSet<H160>,
+	/// Accounts deleted by an earlier transaction, and wiped since.
+	removed: BTreeSet<H160>,
+	creates: BTreeSet<H160>,
+	accessed: BTreeSet<(H160, Option<U256>)>,
+	journal: Vec<JournalEntry>,
+	checkpoints: Vec<usize>,
+}
+
+impl<'backend, B: Backend> MemoryStackState<'backend, B> {
+	/// Create a new state with no changes on top of `backend`.
+	#[must_use]
+	pub const fn new(backend: &'backend B) -> Self {
+		Self {
+			backend,
+			accounts: BTreeMap::new(),
+			storages: BTreeMap::new(),
+			original_storages: BTreeMap::new(),
+			transient_storages: BTreeMap::new(),
+			logs: Vec::new(),
+			deletes: BTreeSet::new(),
+			removed: BTreeSet::new(),
+			creates: BTreeSet::new(),
+			accessed: BTreeSet::new(),
+			journal: Vec::new(),
+			checkpoints: Vec::new(),
+		}
+	}
+
+	/// Logs emitted so far.
+	#[must_use]
+	pub fn logs(&self) -> &[Log] {
+		&self.logs
+	}
+
+	/// Consume the state and return the changes to apply to the backend.
+	#[must_use]
+	#[allow(clippy::type_complexity)]
+	pub fn deconstruct(self) -> (Vec<Apply<Vec<(U256, U256)>>>, Vec<Log>) {
+		let mut applies = Vec::new();
+
+		for (address, account) in self.accounts {
+			if self.deletes.contains(&address) {
+				continue
+			}
+			if self.removed.contains(&address) && account.is_empty() {
+				applies.push(Apply::Delete { address });
+				continue
+			}
+
+			let storage = self.storages.range((address, U256::zero())..=(address, U256::MAX))
+				.map(|(&(_, index), &value)| (index, value))
+				.collect();
+
+			applies.push(Apply::Modify {
+				address,
+				basic: account.basic,
+				code: account.code,
+				storage,
+				reset_storage: account.reset,
+			});
+		}
+
+		for address in self.deletes {
+			applies.push(Apply::Delete { address });
+		}
+
+		(applies, self.logs)
+	}
+
+	fn account_mut(&mut self, address: H160) -> &mut MemoryStackAccount {
+		let previous = self.accounts.get(&address).cloned();
+		if previous.is_none() {
+			let basic = self.backend.basic(address);
+			self.accounts.insert(address, MemoryStackAccount {
+				basic,
+				code: None,
+				reset: false,
+			});
+		}
+		self.journal.push(JournalEntry::Account(address, previous));
+
+		self.accounts.get_mut(&address).expect("account was just inserted")
+	}
+
+	/// Remember the value of a storage slot before the running transaction
+	/// first changes it.
+	fn record_original_storage(&mut self, address: H160, index: U256) {
+		if !self.original_storages.contains_key(&(address, index)) {
+			let value = self.storage(address, index);
+			self.original_storages.insert((address, index), value);
+		}
+	}
+
+	fn undo(&mut self, entry: JournalEntry) {
+		match entry {
+			JournalEntry::Account(address, Some(account)) => {
+				self.accounts.insert(address, account);
+			},
+			JournalEntry::Account(address, None) => {
+				self.accounts.remove(&address);
+			},
+			JournalEntry::Storage(address, index, Some(value)) => {
+				self.storages.insert((address, index), value);
+			},
+			JournalEntry::Storage(address, index, None) => {
+				self.storages.remove(&(address, index));
+			},
+			JournalEntry::TransientStorage(address, index, Some(value)) => {
+				self.transient_storages.insert((address, index), value);
+			},
+			JournalEntry::TransientStorage(address, index, None) => {
+				self.transient_storages.remove(&(address, index));
+			},
+			JournalEntry::Log => {
+				self.logs.pop();
+			},
+			JournalEntry::Deleted(address) => {
+				self.deletes.remove(&address);
+			},
+			JournalEntry::Created(address) => {
+				self.creates.remove(&address);
+			},
+			JournalEntry::Accessed(address, index) => {
+				self.accessed.remove(&(address, index));
+			},
+		}
+	}
+}
+
+impl<B: Backend> Backend for MemoryStackState<'_, B> {
+	fn gas_price(&self) -> U256 { self.backend.gas_price() }
+	fn origin(&self) -> H160 { self.backend.origin() }
+	fn block_hash(&self, number: U256) -> H256 { self.backend.block_hash(number) }
+	fn block_number(&self) -> U256 { self.backend.block_number() }
+	fn block_coinbase(&self) -> H160 { self.backend.block_coinbase() }
+	fn block_timestamp(&self) -> U256 { self.backend.block_timestamp() }
+	fn block_difficulty(&self) -> U256 { self.backend.block_difficulty() }
+	fn block_gas_limit(&self) -> U256 { self.backend.block_gas_limit() }
+	fn block_base_fee_per_gas(&self) -> U256 { self.backend.block_base_fee_per_gas() }
+	fn block_blob_base_fee(&self) -> U256 { self.backend.block_blob_base_fee() }
+	fn blob_versioned_hash(&self, index: usize) -> Option<H256> { self.backend.blob_versioned_hash(index) }
+	fn chain_id(&self) -> U256 { self.backend.chain_id() }
+
+	fn exists(&self, address: H160) -> bool {
+		match self.accounts.get(&address) {
+			Some(account) if self.removed.contains(&address) => !account.is_empty(),
+			Some(_) => true,
+			None => self.backend.exists(address),
+		}
+	}
+
+	fn basic(&self, address: H160) -> Basic {
+		self.accounts.get(&address)
+			.map_or_else(|| self.backend.basic(address), |account| account.basic.clone())
+	}
+
+	fn code(&self, address: H160) -> Vec<u8> {
+		self.accounts.get(&address)
+			.and_then(|account| account.code.clone())
+			.unwrap_or_else(|| self.backend.code(address))
+	}
+
+	fn storage(&self, address: H160, index: U256) -> U256 {
+		if let Some(value) = self.storages.get(&(address, index)) {
+			return *value
+		}
+
+		if self.accounts.get(&address).is_some_and(|account| account.reset) {
+			return U256::zero()
+		}
+
+		self.backend.storage(address, index)
+	}
+
+	fn original_storage(&self, address: H160, index: U256) -> Option<U256> {
+		Some(self.original_storages.get(&(address, index)).copied()
+			.unwrap_or_else(|| self.storage(address, index)))
+	}
+}
+
+impl<B: Backend> StackState for MemoryStackState<'_, B> {
+	fn transient_storage(&self, address: H160, index: U256) -> U256 {
+		self.transient_storages.get(&(address, index)).copied().unwrap_or_default()
+	}
+
+	fn deleted(&self, address: H160) -> bool {
+		self.deletes.contains(&address)
+	}
+
+	fn created(&self, address: H160) -> bool {
+		self.creates.contains(&address)
+	}
+
+	fn is_cold(&self, address: H160, index: Option<U256>) -> bool {
+		!self.accessed.contains(&(address, index))
+	}
+
+	fn begin_transaction(&mut self) {
+		for address in core::mem::take(&mut self.deletes) {
+			self.storages.retain(|&(storage_address, _), _| storage_address != address);
+			self.accounts.insert(address, MemoryStackAccount {
+				basic: Basic::default(),
+				code: Some(Vec::new()),
+				reset: true,
+			});
+			self.removed.insert(address);
+		}
+
+		self.original_storages.clear();
+		self.transient_storages.clear();
+		self.creates.clear();
+		self.accessed.clear();
+		self.journal.clear();
+		self.checkpoints.clear();
+	}
+
+	fn checkpoint(&mut self) {
+		self.checkpoints.push(self.journal.len());
+	}
+
+	fn commit(&mut self) {
+		self.checkpoints.pop().expect("commit without a matching checkpoint");
+		if self.checkpoints.is_empty() {
+			self.journal.clear();
+		}
+	}
+
+	fn revert(&mut self) {
+		let checkpoint = self.checkpoints.pop().expect("revert without a matching checkpoint");
+
+		while self.journal.len() > checkpoint {
+			let entry = self.journal.pop().expect("journal is longer than the checkpoint");
+			self.undo(entry);
+		}
+	}
+
+	fn mark_warm(&mut self, address: H160, index: Option<U256>) {
+		if self.accessed.insert((address, index)) {
+			self.journal.push(JournalEntry::Accessed(address, index));
+		}
+	}
+
+	fn inc_nonce(&mut self, address: H160) {
+		let account = self.account_mut(address);
+		account.basic.nonce = account.basic.nonce.saturating_add(U256::one());
+	}
+
+	fn set_storage(&mut self, address: H160, index: U256, value: U256) {
+		self.record_original_storage(address, index);
+		let previous = self.storages.insert((address, index), value);
+		self.journal.push(JournalEntry::Storage(address, index, previous));
+	}
+
+	fn reset_storage(&mut self, address: H160) {
+		let indexes = self.storages.range((address, U256::zero())..=(address, U256::MAX))
+			.map(|(&(_, index), _)| index)
+			.collect::<Vec<_>>();
+		for index in indexes {
+			self.record_original_storage(address, index);
+			let previous = self.storages.remove(&(address, index));
+			self.journal.push(JournalEntry::Storage(address, index, previous));
+		}
+
+		self.account_mut(address).reset = true;
+	}
+
+	fn set_transient_storage(&mut self, address: H160, index: U256, value: U256) {
+		let previous = self.transient_storages.insert((address, index), value);
+		self.journal.push(JournalEntry::TransientStorage(address, index, previous));
+	}
+
+	fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) {
+		self.logs.push(Log { address, topics, data });
+		self.journal.push(JournalEntry::Log);
+	}
+
+	fn set_deleted(&mut self, address: H160) {
+		if self.deletes.insert(address) {
+			self.journal.push(JournalEntry::Deleted(address));
+		}
+	}
+
+	fn set_created(&mut self, address: H160) {
+		if self.creates.insert(address) {
+			self.journal.push(JournalEntry::Created(address));
+		}
+	}
+
+	fn set_code(&mut self, address: H160, code: Vec<u8>) {
+		self.account_mut(address).code = Some(code);
+	}
+
+	fn transfer(&mut self, transfer: Transfer) -> Result<(), ExitError> {
+		let source = self.basic(transfer.source).balance;
+		if source < transfer.value {
+			return Err(ExitError::OutOfFund)
+		}
+
+		self.account_mut(transfer.source).basic.balance = source - transfer.value;
+		let target = self.account_mut(transfer.target);
+		target.basic.balance = target.basic.balance.saturating_add(transfer.value);
+
+		Ok(())
+	}
+
+	fn reset_balance(&mut self, address: H160) {
+		self.account_mut(address).basic.balance = U256::zero();
+	}
+
+	fn touch(&mut self, address: H160) {
+		if !self.accounts.contains_key(&address) {
+			self.account_mut(address);
+		}
+	}
+}
@@ -13,6 +13,12 @@
 
 extern crate alloc;
 
+pub mod backend;
+pub mod executor;
+pub mod precompile;
+pub mod tracing;
+
 pub use evm_core::*;
 pub use evm_runtime::*;
+pub use evm_gasometer as gasometer;
 
@@ -0,0 +1,111 @@
+use super::{check_gas, PrecompileOutput, PrecompileResult};
+use crate::ExitError;
+
+/// Byte length of the input: rounds, state, message, offset counters and
+/// final block flag.
+const INPUT_LEN: usize = 4 + 64 + 128 + 16 + 1;
+
+const IV: [u64; 8] = [
+	0x6a09_e667_f3bc_c908, 0xbb67_ae85_84ca_a73b, 0x3c6e_f372_fe94_f82b, 0xa54f_f53a_5f1d_36f1,
+	0x510e_527f_ade6_82d1, 0x9b05_688c_2b3e_6c1f, 0x1f83_d9ab_fb41_bd6b, 0x5be0_cd19_137e_2179,
+];
+
+const SIGMA: [[usize; 16]; 10] = [
+	[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+	[14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+	[11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+	[7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+	[9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+	[2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+	[12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+	[13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+	[6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+	[10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// `BLAKE2b` compression function `F`, at address `0x09` (EIP-152). Costs
+/// one gas per round.
+pub fn blake2f(input: &[u8], gas_limit: u64) -> PrecompileResult {
+	if input.len() != INPUT_LEN {
+		return Err(ExitError::InvalidPrecompileInput)
+	}
+
+	let mut rounds = [0u8; 4];
+	rounds.copy_from_slice(&input[0..4]);
+	let rounds = u32::from_be_bytes(rounds);
+	let cost = u64::from(rounds);
+	check_gas(cost, gas_limit)?;
+
+	let word = |offset: usize| {
+		let mut bytes = [0u8; 8];
+		bytes.copy_from_slice(&input[offset..offset + 8]);
+		u64::from_le_bytes(bytes)
+	};
+
+	let mut h = [0u64; 8];
+	for (i, value) in h.iter_mut().enumerate() {
+		*value = word(4 + i * 8);
+	}
+	let mut m = [0u64; 16];
+	for (i, value) in m.iter_mut().enumerate() {
+		*value = word(68 + i * 8);
+	}
+	let t = [word(196), word(204)];
+	let f = match input[212] {
+		0 => false,
+		1 => true,
+		_ => return Err(ExitError::InvalidPrecompileInput),
+	};
+
+	compress(&mut h, &m, t, f, rounds);
+
+	let mut output = [0u8; 64];
+	for (i, value) in h.iter().enumerate() {
+		output[i * 8..(i + 1) * 8].copy_from_slice(&value.to_le_bytes());
+	}
+
+	Ok(PrecompileOutput {
+		output: output.to_vec(),
+		cost,
+	})
+}
+
+#[allow(clippy::many_single_char_names)]
+const fn g(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+	v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+	v[d] = (v[d] ^ v[a]).rotate_right(32);
+	v[c] = v[c].wrapping_add(v[d]);
+	v[b] = (v[b] ^ v[c]).rotate_right(24);
+	v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+	v[d] = (v[d] ^ v[a]).rotate_right(16);
+	v[c] = v[c].wrapping_add(v[d]);
+	v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+#[allow(clippy::many_single_char_names)]
+fn compress(h: &mut [u64; 8], m: &[u64; 16], t: [u64; 2], f: bool, rounds: u32) {
+	let mut v = [0u64; 16];
+	v[..8].copy_from_slice(h);
+	v[8..].copy_from_slice(&IV);
+	v[12] ^= t[0];
+	v[13] ^= t[1];
+	if f {
+		v[14] = !v[14];
+	}
+
+	for round in 0..rounds as usize {
+		let s = &SIGMA[round % 10];
+		g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+		g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+		g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+		g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+		g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+		g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+		g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+		g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+	}
+
+	for i in 0..8 {
+		h[i] ^= v[i] ^ v[i + 8];
+	}
+}
@@ -0,0 +1,101 @@
+use alloc::vec::Vec;
+use bn::{AffineG1, AffineG2, Fq, Fq2, Fr, Group, Gt, G1, G2};
+use super::{check_gas, padded, PrecompileOutput, PrecompileResult};
+use crate::ExitError;
+
+/// Byte length of a pairing input element: a G1 and a G2 point.
+const PAIR_LEN: usize = 192;
+
+/// Addition on the `alt_bn128` curve, at address `0x06` (EIP-196).
+pub fn add(input: &[u8], gas_limit: u64, istanbul: bool) -> PrecompileResult {
+	let cost = if istanbul { 150 } else { 500 };
+	check_gas(cost, gas_limit)?;
+
+	let input = padded::<128>(input, 0);
+	let p1 = read_g1(&input[0..64])?;
+	let p2 = read_g1(&input[64..128])?;
+
+	Ok(PrecompileOutput {
+		output: encode_g1(p1 + p2),
+		cost,
+	})
+}
+
+/// Scalar multiplication on the `alt_bn128` curve, at address `0x07`
+/// (EIP-196).
+pub fn mul(input: &[u8], gas_limit: u64, istanbul: bool) -> PrecompileResult {
+	let cost = if istanbul { 6000 } else { 40000 };
+	check_gas(cost, gas_limit)?;
+
+	let input = padded::<96>(input, 0);
+	let p = read_g1(&input[0..64])?;
+	let scalar = Fr::from_slice(&input[64..96]).map_err(|_| ExitError::InvalidPrecompileInput)?;
+
+	Ok(PrecompileOutput {
+		output: encode_g1(p * scalar),
+		cost,
+	})
+}
+
+/// Pairing check on the `alt_bn128` curve, at address `0x08` (EIP-197).
+pub fn pairing(input: &[u8], gas_limit: u64, istanbul: bool) -> PrecompileResult {
+	let (base, pair): (u64, u64) = if istanbul { (45000, 34000) } else { (100_000, 80000) };
+	let cost = pair.saturating_mul((input.len() / PAIR_LEN) as u64).saturating_add(base);
+	check_gas(cost, gas_limit)?;
+
+	if !input.len().is_multiple_of(PAIR_LEN) {
+		return Err(ExitError::InvalidPrecompileInput)
+	}
+
+	let pairs = input.chunks(PAIR_LEN)
+		.map(|chunk| Ok((read_g1(&chunk[0..64])?, read_g2(&chunk[64..192])?)))
+		.collect::<Result<Vec<_>, ExitError>>()?;
+
+	let mut output = [0u8; 32];
+	if bn::pairing_batch(&pairs) == Gt::one() {
+		output[31] = 1;
+	}
+
+	Ok(PrecompileOutput {
+		output: output.to_vec(),
+		cost,
+	})
+}
+
+fn read_fq(input: &[u8]) -> Result<Fq, ExitError> {
+	Fq::from_slice(input).map_err(|_| ExitError::InvalidPrecompileInput)
+}
+
+/// Point `(x, y)`, where `(0, 0)` is the point at infinity.
+fn read_g1(input: &[u8]) -> Result<G1, ExitError> {
+	let x = read_fq(&input[0..32])?;
+	let y = read_fq(&input[32..64])?;
+
+	if x.is_zero() && y.is_zero() {
+		Ok(G1::zero())
+	} else {
+		AffineG1::new(x, y).map(Into::into).map_err(|_| ExitError::InvalidPrecompileInput)
+	}
+}
+
+/// Point `(x, y)` with coordinates encoded imaginary part first, where all
+/// zeros is the point at infinity.
+fn read_g2(input: &[u8]) -> Result<G2, ExitError> {
+	let x = Fq2::new(read_fq(&input[32..64])?, read_fq(&input[0..32])?);
+	let y = Fq2::new(read_fq(&input[96..128])?, read_fq(&input[64..96])?);
+
+	if x.is_zero() && y.is_zero() {
+		Ok(G2::zero())
+	} else {
+		AffineG2::new(x, y).map(Into::into).map_err(|_| ExitError::InvalidPrecompileInput)
+	}
+}
+
+fn encode_g1(point: G1) -> Vec<u8> {
+	let mut output = [0u8; 64];
+	if let Some(point) = AffineG1::from_jacobian(point) {
+		point.x().to_big_endian(&mut output[0..32]).expect("slice is 32 bytes");
+		point.y().to_big_endian(&mut output[32..64]).expect("slice is 32 bytes");
+	}
+	output.to_vec()
+}
@@ -0,0 +1,35 @@
+use alloc::vec::Vec;
+use sha3::{Digest, Keccak256};
+use super::{check_gas, padded, PrecompileOutput, PrecompileResult};
+
+/// Cost of `ecrecover`.
+const ECRECOVER_COST: u64 = 3000;
+
+/// Address of the signer of a message hash, at address `0x01`.
+///
+/// The input is the message hash, `v`, `r` and `s`, each padded to 32
+/// bytes. Invalid signatures return no data instead of failing.
+pub fn ecrecover(input: &[u8], gas_limit: u64) -> PrecompileResult {
+	check_gas(ECRECOVER_COST, gas_limit)?;
+
+	let input = padded::<128>(input, 0);
+	Ok(PrecompileOutput {
+		output: recover(&input).unwrap_or_default(),
+		cost: ECRECOVER_COST,
+	})
+}
+
+fn recover(input: &[u8; 128]) -> Option<Vec<u8>> {
+	if input[32..63].iter().any(|byte| *byte != 0) || !(27..=28).contains(&input[63]) {
+		return None
+	}
+
+	let message = libsecp256k1::Message::parse_slice(&input[0..32]).ok()?;
+	let signature = libsecp256k1::Signature::parse_standard_slice(&input[64..128]).ok()?;
+	let recovery_id = libsecp256k1::RecoveryId::parse(input[63] - 27).ok()?;
+	let public = libsecp256k1::recover(&message, &signature, &recovery_id).ok()?;
+
+	let mut output = Keccak256::digest(&public.serialize()[1..]).to_vec();
+	output[..12].copy_from_slice(&[0u8; 12]);
+	Some(output)
+}
@@ -0,0 +1,37 @@
+use alloc::vec::Vec;
+use sha2::Digest;
+use super::{check_gas, linear_cost, PrecompileOutput, PrecompileResult};
+
+/// SHA2-256 of the input, at address `0x02`.
+pub fn sha256(input: &[u8], gas_limit: u64) -> PrecompileResult {
+	let cost = linear_cost(input.len(), 60, 12);
+	check_gas(cost, gas_limit)?;
+
+	Ok(PrecompileOutput {
+		output: sha2::Sha256::digest(input).to_vec(),
+		cost,
+	})
+}
+
+/// RIPEMD-160 of the input, left-padded to 32 bytes, at address `0x03`.
+pub fn ripemd160(input: &[u8], gas_limit: u64) -> PrecompileResult {
+	let cost = linear_cost(input.len(), 600, 120);
+	check_gas(cost, gas_limit)?;
+
+	let mut output = Vec::with_capacity(32);
+	output.extend_from_slice(&[0u8; 12]);
+	output.extend_from_slice(&ripemd160::Ripemd160::digest(input));
+
+	Ok(PrecompileOutput { output, cost })
+}
+
+/// The input itself, at address `0x04`.
+pub fn identity(input: &[u8], gas_limit: u64) -> PrecompileResult {
+	let cost = linear_cost(input.len(), 15, 3);
+	check_gas(cost, gas_limit)?;
+
+	Ok(PrecompileOutput {
+		output: input.to_vec(),
+		cost,
+	})
+}
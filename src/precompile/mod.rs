@@ -0,0 +1,305 @@
+//! Precompiled contracts.
+
+mod blake2f;
+mod bn128;
+mod ecrecover;
+mod hash;
+mod modexp;
+
+use core::cmp::min;
+use alloc::vec::Vec;
+use crate::{Context, ExitError, H160};
+
+/// Output of a successful precompile execution.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PrecompileOutput {
+	/// Return data.
+	pub output: Vec<u8>,
+	/// Gas used.
+	pub cost: u64,
+}
+
+/// Result of a precompile execution. A failure consumes all gas given to
+/// the call.
+pub type PrecompileResult = Result<PrecompileOutput, ExitError>;
+
+/// Set of precompiled contracts, consulted by the executor before running
+/// the code of a call target.
+pub trait PrecompileSet {
+	/// Execute the precompile at `address`, or return `None` if there is no
+	/// precompile at that address.
+	fn execute(
+		&self,
+		address: H160,
+		input: &[u8],
+		gas_limit: u64,
+		context: &Context,
+		is_static: bool,
+	) -> Option<PrecompileResult>;
+
+	/// Whether there is a precompile at `address`. Precompile addresses are
+	/// always warm (EIP-2929).
+	fn is_precompile(&self, address: H160) -> bool;
+}
+
+impl PrecompileSet for () {
+	fn execute(
+		&self,
+		_address: H160,
+		_input: &[u8],
+		_gas_limit: u64,
+		_context: &Context,
+		_is_static: bool,
+	) -> Option<PrecompileResult> {
+		None
+	}
+
+	fn is_precompile(&self, _address: H160) -> bool {
+		false
+	}
+}
+
+impl<P: PrecompileSet> PrecompileSet for &P {
+	fn execute(
+		&self,
+		address: H160,
+		input: &[u8],
+		gas_limit: u64,
+		context: &Context,
+		is_static: bool,
+	) -> Option<PrecompileResult> {
+		(*self).execute(address, input, gas_limit, context, is_static)
+	}
+
+	fn is_precompile(&self, address: H160) -> bool {
+		(*self).is_precompile(address)
+	}
+}
+
+/// Hard fork the standard precompiles are priced for.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+enum PrecompileFork {
+	Frontier,
+	Byzantium,
+	Istanbul,
+	Berlin,
+}
+
+/// Precompiled contracts of the Ethereum mainnet, at addresses `0x01` to
+/// `0x09`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StandardPrecompileSet {
+	fork: PrecompileFork,
+}
+
+impl StandardPrecompileSet {
+	/// Frontier precompiles: ecrecover, sha256, ripemd160 and identity.
+	#[must_use]
+	pub const fn frontier() -> Self {
+		Self { fork: PrecompileFork::Frontier }
+	}
+
+	/// Byzantium precompiles, adding modexp (EIP-198) and the `alt_bn128`
+	/// contracts (EIP-196, EIP-197).
+	#[must_use]
+	pub const fn byzantium() -> Self {
+		Self { fork: PrecompileFork::Byzantium }
+	}
+
+	/// Istanbul precompiles, with repriced `alt_bn128` contracts (EIP-1108)
+	/// and blake2f (EIP-152).
+	#[must_use]
+	pub const fn istanbul() -> Self {
+		Self { fork: PrecompileFork::Istanbul }
+	}
+
+	/// Berlin precompiles, with repriced modexp (EIP-2565). Also used by
+	/// all later forks.
+	#[must_use]
+	pub const fn berlin() -> Self {
+		Self { fork: PrecompileFork::Berlin }
+	}
+
+	/// Index of the precompile at `address`, if any.
+	fn index(self, address: H160) -> Option<u64> {
+		if address[..19].iter().any(|byte| *byte != 0) {
+			return None
+		}
+
+		let index = u64::from(address[19]);
+		let count = match self.fork {
+			PrecompileFork::Frontier => 4,
+			PrecompileFork::Byzantium => 8,
+			PrecompileFork::Istanbul | PrecompileFork::Berlin => 9,
+		};
+
+		if (1..=count).contains(&index) {
+			Some(index)
+		} else {
+			None
+		}
+	}
+}
+
+impl PrecompileSet for StandardPrecompileSet {
+	fn execute(
+		&self,
+		address: H160,
+		input: &[u8],
+		gas_limit: u64,
+		_context: &Context,
+		_is_static: bool,
+	) -> Option<PrecompileResult> {
+		let istanbul = self.fork >= PrecompileFork::Istanbul;
+
+		Some(match self.index(address)? {
+			1 => ecrecover::ecrecover(input, gas_limit),
+			2 => hash::sha256(input, gas_limit),
+			3 => hash::ripemd160(input, gas_limit),
+			4 => hash::identity(input, gas_limit),
+			5 => modexp::modexp(input, gas_limit, self.fork >= PrecompileFork::Berlin),
+			6 => bn128::add(input, gas_limit, istanbul),
+			7 => bn128::mul(input, gas_limit, istanbul),
+			8 => bn128::pairing(input, gas_limit, istanbul),
+			9 => blake2f::blake2f(input, gas_limit),
+			_ => return None,
+		})
+	}
+
+	fn is_precompile(&self, address: H160) -> bool {
+		self.index(address).is_some()
+	}
+}
+
+/// Fail with `OutOfGas` if `cost` exceeds `gas_limit`.
+const fn check_gas(cost: u64, gas_limit: u64) -> Result<(), ExitError> {
+	if cost > gas_limit {
+		Err(ExitError::OutOfGas)
+	} else {
+		Ok(())
+	}
+}
+
+/// Cost of a precompile charging `base` plus `word` per 32 bytes of input.
+const fn linear_cost(len: usize, base: u64, word: u64) -> u64 {
+	let words = (len as u64).saturating_add(31) / 32;
+	base.saturating_add(words.saturating_mul(word))
+}
+
+/// Copy `input[offset..offset + N]` into an array, padding with zeros past
+/// the end of the input.
+fn padded<const N: usize>(input: &[u8], offset: usize) -> [u8; N] {
+	let mut out = [0u8; N];
+	if offset < input.len() {
+		let len = min(N, input.len() - offset);
+		out[..len].copy_from_slice(&input[offset..offset + len]);
+	}
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::precompile::{PrecompileSet, StandardPrecompileSet};
+	use crate::{Context, ExitError, H160, U256};
+
+	fn address(index: u8) -> H160 {
+		let mut bytes = [0u8; 20];
+		bytes[19] = index;
+		H160::from(bytes)
+	}
+
+	fn call(index: u8, input: &str, gas_limit: u64) -> Result<(String, u64), ExitError> {
+		let context = Context {
+			address: address(index),
+			caller: H160::default(),
+			apparent_value: U256::zero(),
+			is_static: false,
+		};
+		StandardPrecompileSet::berlin()
+			.execute(context.address, &hex::decode(input).unwrap(), gas_limit, &context, false)
+			.expect("precompile exists")
+			.map(|output| (hex::encode(output.output), output.cost))
+	}
+
+	#[test]
+	fn test_addresses() {
+		assert!(StandardPrecompileSet::frontier().is_precompile(address(4)));
+		assert!(!StandardPrecompileSet::frontier().is_precompile(address(5)));
+		assert!(StandardPrecompileSet::berlin().is_precompile(address(9)));
+		assert!(!StandardPrecompileSet::berlin().is_precompile(address(10)));
+		assert!(!StandardPrecompileSet::berlin().is_precompile(H160::repeat_byte(1)));
+	}
+
+	#[test]
+	fn test_hashes() {
+		assert_eq!(call(2, "", 100), Ok((
+			"e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855".into(),
+			60,
+		)));
+		assert_eq!(call(3, "", 1000), Ok((
+			"0000000000000000000000009c1185a5c5e9fc54612808977ee8f548b2258d31".into(),
+			600,
+		)));
+		assert_eq!(call(4, "0102", 100), Ok(("0102".into(), 18)));
+		assert_eq!(call(4, "0102", 17), Err(ExitError::OutOfGas));
+	}
+
+	#[test]
+	fn test_ecrecover() {
+		let input = "38d18acb67d25c8bb9942764b62f18e17054f66a817bd4295423adf9ed98873e\
+			000000000000000000000000000000000000000000000000000000000000001b\
+			38d18acb67d25c8bb9942764b62f18e17054f66a817bd4295423adf9ed98873e\
+			789d1dd423d25f0772d2748d60f7e4b81bb14d086eba8e8e8efb6dcff8a4ae02";
+		assert_eq!(call(1, input, 3000), Ok((
+			"000000000000000000000000ceaccac640adf55b2028469bd36ba501f28b699d".into(),
+			3000,
+		)));
+		// An invalid recovery id returns no data.
+		assert_eq!(call(1, &input.replace("001b", "001d"), 3000), Ok((String::new(), 3000)));
+	}
+
+	#[test]
+	fn test_modexp() {
+		// 3 ** 5 % 7
+		let input = "0000000000000000000000000000000000000000000000000000000000000001\
+			0000000000000000000000000000000000000000000000000000000000000001\
+			0000000000000000000000000000000000000000000000000000000000000001\
+			030507";
+		assert_eq!(call(5, input, 200), Ok(("05".into(), 200)));
+		assert_eq!(call(5, input, 199), Err(ExitError::OutOfGas));
+	}
+
+	#[test]
+	fn test_bn128() {
+		let generator = "0000000000000000000000000000000000000000000000000000000000000001\
+			0000000000000000000000000000000000000000000000000000000000000002";
+		let double = "030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3\
+			15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4";
+
+		assert_eq!(call(6, &[generator, generator].concat(), 150), Ok((double.into(), 150)));
+		assert_eq!(call(7, &[generator, &format!("{:064x}", 2)].concat(), 6000), Ok((double.into(), 6000)));
+		assert_eq!(call(8, "", 45000), Ok((format!("{:064x}", 1), 45000)));
+		// (1, 3) is not on the curve.
+		assert_eq!(call(6, &generator.replace("0002", "0003"), 150), Err(ExitError::InvalidPrecompileInput));
+	}
+
+	#[test]
+	fn test_blake2f() {
+		let input = "0000000c\
+			48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+			d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b\
+			6162630000000000000000000000000000000000000000000000000000000000\
+			0000000000000000000000000000000000000000000000000000000000000000\
+			0000000000000000000000000000000000000000000000000000000000000000\
+			0000000000000000000000000000000000000000000000000000000000000000\
+			0300000000000000\
+			0000000000000000\
+			01";
+		assert_eq!(call(9, input, 12), Ok((
+			"ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+				7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923".into(),
+			12,
+		)));
+		assert_eq!(call(9, input, 11), Err(ExitError::OutOfGas));
+	}
+}
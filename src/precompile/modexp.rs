@@ -0,0 +1,99 @@
+use core::cmp::{max, min};
+use alloc::vec;
+use alloc::vec::Vec;
+use num_bigint::BigUint;
+use super::{check_gas, padded, PrecompileOutput, PrecompileResult};
+use crate::{ExitError, U256};
+
+/// Arbitrary-precision modular exponentiation, at address `0x05`.
+///
+/// The input is the lengths of the base, exponent and modulus, each padded
+/// to 32 bytes, followed by the three numbers. Priced by EIP-2565 if
+/// `berlin` is set, and by EIP-198 otherwise.
+pub fn modexp(input: &[u8], gas_limit: u64, berlin: bool) -> PrecompileResult {
+	let base_len = U256::from_big_endian(&padded::<32>(input, 0));
+	let exp_len = U256::from_big_endian(&padded::<32>(input, 32));
+	let mod_len = U256::from_big_endian(&padded::<32>(input, 64));
+
+	let cost = cost(input, base_len, exp_len, mod_len, berlin).ok_or(ExitError::OutOfGas)?;
+	check_gas(cost, gas_limit)?;
+
+	// The lengths are bounded by the gas limit once the cost is paid.
+	let base_len = base_len.as_usize();
+	let mod_len = mod_len.as_usize();
+	if mod_len == 0 {
+		return Ok(PrecompileOutput { output: vec![], cost })
+	}
+	let exp_len = exp_len.as_usize();
+
+	let data = input.get(96..).unwrap_or_default();
+	let base = BigUint::from_bytes_be(&read(data, 0, base_len));
+	let exponent = BigUint::from_bytes_be(&read(data, base_len, exp_len));
+	let modulus = BigUint::from_bytes_be(&read(data, base_len + exp_len, mod_len));
+
+	let mut output = vec![0u8; mod_len];
+	if modulus != BigUint::from(0u8) {
+		let result = base.modpow(&exponent, &modulus).to_bytes_be();
+		output[mod_len - result.len()..].copy_from_slice(&result);
+	}
+
+	Ok(PrecompileOutput { output, cost })
+}
+
+/// `len` bytes of `data` from `offset`, padded with zeros.
+fn read(data: &[u8], offset: usize, len: usize) -> Vec<u8> {
+	let mut out = vec![0u8; len];
+	if offset < data.len() {
+		let available = min(len, data.len() - offset);
+		out[..available].copy_from_slice(&data[offset..offset + available]);
+	}
+	out
+}
+
+/// Gas cost of a call, or `None` if it does not fit in `u64`.
+fn cost(input: &[u8], base_len: U256, exp_len: U256, mod_len: U256, berlin: bool) -> Option<u64> {
+	let max_len = max(base_len, mod_len);
+	let iterations = max(iteration_count(input, base_len, exp_len)?, U256::one());
+
+	let cost = if berlin {
+		let words = max_len.checked_add(U256::from(7))? / 8;
+		let cost = words.checked_mul(words)?.checked_mul(iterations)? / 3;
+		max(cost, U256::from(200))
+	} else {
+		let complexity = if max_len <= U256::from(64) {
+			max_len * max_len
+		} else if max_len <= U256::from(1024) {
+			max_len * max_len / 4 + max_len * 96 - 3072
+		} else {
+			max_len.checked_mul(max_len)? / 16 + max_len.checked_mul(U256::from(480))? - 199_680
+		};
+		complexity.checked_mul(iterations)? / 20
+	};
+
+	if cost > U256::from(u64::MAX) {
+		None
+	} else {
+		Some(cost.as_u64())
+	}
+}
+
+/// Adjusted exponent length: the index of the highest set bit of the first
+/// 32 bytes of the exponent, plus 8 for each byte after them.
+fn iteration_count(input: &[u8], base_len: U256, exp_len: U256) -> Option<U256> {
+	let head_len = min(exp_len, U256::from(32)).as_usize();
+	let head = if base_len < U256::from(input.len()) {
+		let mut head = [0u8; 32];
+		let bytes = padded::<32>(input, 96 + base_len.as_usize());
+		head[32 - head_len..].copy_from_slice(&bytes[..head_len]);
+		U256::from_big_endian(&head)
+	} else {
+		U256::zero()
+	};
+
+	let head_bits = if head.is_zero() { 0 } else { head.bits() - 1 };
+	if exp_len <= U256::from(32) {
+		Some(U256::from(head_bits))
+	} else {
+		(exp_len - 32).checked_mul(U256::from(8))?.checked_add(U256::from(head_bits))
+	}
+}
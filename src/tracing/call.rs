@@ -0,0 +1,423 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::{
+	CallScheme, Context, CreateScheme, ExitReason, Inspector, Transfer, H160, H256, U256,
+};
+use super::exit_error;
+#[cfg(feature = "with-serde")]
+use super::geth;
+
+/// Selector of the `Error(string)` revert reason.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Options of the call tracer, named after the ones of geth.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "with-serde", serde(rename_all = "camelCase", default))]
+pub struct CallTracerConfig {
+	/// Only record the outermost frame.
+	pub only_top_call: bool,
+	/// Record the logs emitted by each frame.
+	pub with_log: bool,
+}
+
+/// Kind of a call frame.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "with-serde", serde(rename_all = "UPPERCASE"))]
+pub enum CallKind {
+	/// `CALL`
+	Call,
+	/// `CALLCODE`
+	CallCode,
+	/// `DELEGATECALL`
+	DelegateCall,
+	/// `STATICCALL`
+	StaticCall,
+	/// `CREATE`
+	Create,
+	/// `CREATE2`
+	Create2,
+	/// `SELFDESTRUCT`, recorded as a frame sending the balance away.
+	SelfDestruct,
+}
+
+impl From<CallScheme> for CallKind {
+	fn from(scheme: CallScheme) -> Self {
+		match scheme {
+			CallScheme::Call => Self::Call,
+			CallScheme::CallCode => Self::CallCode,
+			CallScheme::DelegateCall => Self::DelegateCall,
+			CallScheme::StaticCall => Self::StaticCall,
+		}
+	}
+}
+
+/// Log emitted by a call frame.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize))]
+pub struct CallLog {
+	/// Address of the emitting account.
+	pub address: H160,
+	/// Topics of the log.
+	pub topics: Vec<H256>,
+	/// Data of the log.
+	#[cfg_attr(feature = "with-serde", serde(serialize_with = "geth::data"))]
+	pub data: Vec<u8>,
+	/// Number of calls the frame made before emitting the log.
+	#[cfg_attr(feature = "with-serde", serde(serialize_with = "geth::quantity"))]
+	pub position: u64,
+}
+
+/// A call or create frame, as in the output of geth's `callTracer`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "with-serde", serde(rename_all = "camelCase"))]
+pub struct CallFrame {
+	/// Kind of the frame.
+	#[cfg_attr(feature = "with-serde", serde(rename = "type"))]
+	pub kind: CallKind,
+	/// Account making the call.
+	pub from: H160,
+	/// Gas given to the frame.
+	#[cfg_attr(feature = "with-serde", serde(serialize_with = "geth::quantity"))]
+	pub gas: u64,
+	/// Gas used by the frame.
+	#[cfg_attr(feature = "with-serde", serde(serialize_with = "geth::quantity"))]
+	pub gas_used: u64,
+	/// Called account, or created account if the create succeeded.
+	#[cfg_attr(feature = "with-serde", serde(skip_serializing_if = "Option::is_none"))]
+	pub to: Option<H160>,
+	/// Input data, or init code of a create.
+	#[cfg_attr(feature = "with-serde", serde(serialize_with = "geth::data"))]
+	pub input: Vec<u8>,
+	/// Return value, or deployed code of a create.
+	#[cfg_attr(feature = "with-serde", serde(
+		skip_serializing_if = "Option::is_none",
+		serialize_with = "geth::optional_data",
+	))]
+	pub output: Option<Vec<u8>>,
+	/// Error the frame failed with.
+	#[cfg_attr(feature = "with-serde", serde(skip_serializing_if = "Option::is_none"))]
+	pub error: Option<String>,
+	/// Decoded `Error(string)` reason of a revert.
+	#[cfg_attr(feature = "with-serde", serde(skip_serializing_if = "Option::is_none"))]
+	pub revert_reason: Option<String>,
+	/// Frames entered by this one.
+	#[cfg_attr(feature = "with-serde", serde(skip_serializing_if = "Vec::is_empty"))]
+	pub calls: Vec<Self>,
+	/// Logs emitted by this frame, if it succeeded.
+	#[cfg_attr(feature = "with-serde", serde(skip_serializing_if = "Vec::is_empty"))]
+	pub logs: Vec<CallLog>,
+	/// Value sent with the call. Static calls have none.
+	#[cfg_attr(feature = "with-serde", serde(skip_serializing_if = "Option::is_none"))]
+	pub value: Option<U256>,
+}
+
+impl CallFrame {
+	const fn new(kind: CallKind, from: H160, to: H160, input: Vec<u8>, gas: u64, value: Option<U256>) -> Self {
+		Self {
+			kind,
+			from,
+			gas,
+			gas_used: 0,
+			to: Some(to),
+			input,
+			output: None,
+			error: None,
+			revert_reason: None,
+			calls: Vec::new(),
+			logs: Vec::new(),
+			value,
+		}
+	}
+
+	/// Drop the logs of this frame and of all frames it entered.
+	fn clear_logs(&mut self) {
+		self.logs.clear();
+		for call in &mut self.calls {
+			call.clear_logs();
+		}
+	}
+}
+
+/// Inspector building the tree of call and create frames, like geth's
+/// `callTracer`.
+#[derive(Clone, Debug, Default)]
+pub struct CallTracer {
+	config: CallTracerConfig,
+	/// Frames not exited yet, outermost first, with the account each of them
+	/// runs as.
+	frames: Vec<(H160, CallFrame)>,
+	/// Frames entered below the outermost one with `only_top_call`.
+	skipped: usize,
+	result: Option<CallFrame>,
+}
+
+impl CallTracer {
+	/// Create a new tracer with the given options.
+	#[must_use]
+	pub fn new(config: CallTracerConfig) -> Self {
+		Self {
+			config,
+			..Self::default()
+		}
+	}
+
+	/// Consume the tracer into the outermost frame of the transaction, given
+	/// its gas limit and the gas it used. Returns `None` if no frame was
+	/// entered.
+	#[must_use]
+	pub fn into_frame(self, gas_limit: u64, gas_used: u64) -> Option<CallFrame> {
+		self.result.map(|frame| CallFrame {
+			gas: gas_limit,
+			gas_used,
+			..frame
+		})
+	}
+
+	/// Whether a frame entered now is left out of the tree.
+	const fn skip(&mut self) -> bool {
+		let skip = self.config.only_top_call && !self.frames.is_empty();
+		if skip {
+			self.skipped += 1;
+		}
+		skip
+	}
+}
+
+/// Decode the `Error(string)` reason of a revert.
+fn revert_reason(output: &[u8]) -> Option<String> {
+	if output.len() < 68 || output[..4] != ERROR_SELECTOR {
+		return None
+	}
+
+	let len = U256::from_big_endian(&output[36..68]);
+	if len > U256::from(output.len() - 68) {
+		return None
+	}
+
+	core::str::from_utf8(&output[68..68 + len.as_usize()]).ok().map(ToString::to_string)
+}
+
+impl Inspector for CallTracer {
+	fn log(&mut self, address: H160, topics: &[H256], data: &[u8]) {
+		if !self.config.with_log || self.skipped > 0 {
+			return
+		}
+
+		if let Some((_, frame)) = self.frames.last_mut() {
+			frame.logs.push(CallLog {
+				address,
+				topics: topics.to_vec(),
+				data: data.to_vec(),
+				position: frame.calls.len() as u64,
+			});
+		}
+	}
+
+	fn call(
+		&mut self,
+		scheme: CallScheme,
+		code_address: H160,
+		transfer: Option<&Transfer>,
+		input: &[u8],
+		gas: u64,
+		context: &Context,
+	) {
+		if self.skip() {
+			return
+		}
+
+		let value = match scheme {
+			CallScheme::Call | CallScheme::CallCode => Some(transfer.map_or_else(U256::zero, |transfer| transfer.value)),
+			CallScheme::DelegateCall => Some(context.apparent_value),
+			CallScheme::StaticCall => None,
+		};
+		// Delegate calls run in the context of the calling account.
+		let from = match scheme {
+			CallScheme::DelegateCall | CallScheme::CallCode => context.address,
+			CallScheme::Call | CallScheme::StaticCall => context.caller,
+		};
+		let frame = CallFrame::new(scheme.into(), from, code_address, input.to_vec(), gas, value);
+		self.frames.push((context.address, frame));
+	}
+
+	fn create(
+		&mut self,
+		scheme: CreateScheme,
+		address: H160,
+		value: U256,
+		init_code: &[u8],
+		gas: u64,
+	) {
+		if self.skip() {
+			return
+		}
+
+		let (kind, from) = match scheme {
+			CreateScheme::Legacy { caller } => (CallKind::Create, caller),
+			CreateScheme::Create2 { caller, .. } => (CallKind::Create2, caller),
+			CreateScheme::Fixed(_) => (CallKind::Create, self.frames.last().map_or_else(H160::zero, |(address, _)| *address)),
+		};
+		let frame = CallFrame::new(kind, from, address, init_code.to_vec(), gas, Some(value));
+		self.frames.push((address, frame));
+	}
+
+	fn exit(&mut self, reason: &ExitReason, return_value: &[u8], gas_left: u64) {
+		if self.skipped > 0 {
+			self.skipped -= 1;
+			return
+		}
+
+		let Some((_, mut frame)) = self.frames.pop() else { return };
+		frame.gas_used = frame.gas.saturating_sub(gas_left);
+
+		match reason {
+			ExitReason::Succeed(_) => {
+				frame.output = (!return_value.is_empty()).then(|| return_value.to_vec());
+			},
+			ExitReason::Revert(_) => {
+				frame.error = Some("execution reverted".to_string());
+				frame.revert_reason = revert_reason(return_value);
+				frame.output = (!return_value.is_empty()).then(|| return_value.to_vec());
+			},
+			reason => {
+				frame.error = exit_error(*reason);
+			},
+		}
+
+		if !matches!(reason, ExitReason::Succeed(_)) {
+			frame.clear_logs();
+			if matches!(frame.kind, CallKind::Create | CallKind::Create2) {
+				frame.to = None;
+			}
+		}
+
+		match self.frames.last_mut() {
+			Some((_, parent)) => parent.calls.push(frame),
+			None => self.result = Some(frame),
+		}
+	}
+
+	fn suicide(&mut self, address: H160, target: H160, balance: U256) {
+		if self.config.only_top_call || self.skipped > 0 {
+			return
+		}
+
+		if let Some((_, frame)) = self.frames.last_mut() {
+			frame.calls.push(CallFrame::new(CallKind::SelfDestruct, address, target, Vec::new(), 0, Some(balance)));
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::collections::BTreeMap;
+	use crate::backend::{with_memory_backend, MemoryAccount};
+	use crate::executor::{MemoryStackState, StackExecutor};
+	use crate::{Config, H160, U256};
+	use super::{CallFrame, CallKind, CallTracer, CallTracerConfig};
+
+	const CALLER: H160 = H160([0xca; 20]);
+	const OUTER: H160 = H160([0xaa; 20]);
+	const INNER: H160 = H160([0xbb; 20]);
+
+	fn run(config: CallTracerConfig) -> CallFrame {
+		// CALL(GAS, INNER, 0, 0, 0, 0, 0); LOG0(0, 0); STOP
+		trace("6000600060006000600073bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb5af15060006000a000", config)
+	}
+
+	fn trace(outer_code: &str, config: CallTracerConfig) -> CallFrame {
+		let mut accounts = BTreeMap::new();
+		accounts.insert(OUTER, MemoryAccount {
+			code: hex::decode(outer_code).unwrap(),
+			..MemoryAccount::default()
+		});
+		// LOG0(0, 0); REVERT with Error("no")
+		accounts.insert(INNER, MemoryAccount {
+			code: hex::decode(concat!(
+				"6308c379a060e01b600052", "6020600452", "6002602452", "616e6f60f01b604452",
+				"60006000a0", "60646000fd",
+			)).unwrap(),
+			..MemoryAccount::default()
+		});
+
+		let evm_config = Config::istanbul();
+		let mut tracer = CallTracer::new(config);
+		let used_gas = with_memory_backend(accounts, |backend| {
+			let mut executor = StackExecutor::new_with_inspector(
+				MemoryStackState::new(backend), &evm_config, (), &mut tracer,
+			);
+			let _ = executor.transact_call(CALLER, OUTER, U256::zero(), vec![0x42], 100_000, &[]);
+			executor.used_gas()
+		});
+
+		tracer.into_frame(100_000, used_gas).unwrap()
+	}
+
+	#[test]
+	fn test_call_tracer() {
+		let frame = run(CallTracerConfig { only_top_call: false, with_log: true });
+		assert_eq!(frame.kind, CallKind::Call);
+		assert_eq!((frame.from, frame.to), (CALLER, Some(OUTER)));
+		assert_eq!(frame.input, vec![0x42]);
+		assert_eq!(frame.value, Some(U256::zero()));
+		assert_eq!(frame.gas, 100_000);
+		assert_eq!((frame.output.as_ref(), frame.error.as_ref()), (None, None));
+		assert_eq!(frame.logs.len(), 1);
+		assert_eq!((frame.logs[0].address, frame.logs[0].position), (OUTER, 1));
+
+		assert_eq!(frame.calls.len(), 1);
+		let inner = &frame.calls[0];
+		assert_eq!(inner.kind, CallKind::Call);
+		assert_eq!((inner.from, inner.to), (OUTER, Some(INNER)));
+		assert_eq!(inner.error.as_deref(), Some("execution reverted"));
+		assert_eq!(inner.revert_reason.as_deref(), Some("no"));
+		assert_eq!(inner.output.as_ref().map(Vec::len), Some(100));
+		assert!(inner.gas_used > 0 && inner.gas_used < inner.gas);
+		assert!(inner.logs.is_empty());
+	}
+
+	#[test]
+	fn test_call_tracer_delegate_call() {
+		// DELEGATECALL(GAS, INNER, 0, 0, 0, 0); STOP
+		let frame = trace("600060006000600073bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb5af45000", CallTracerConfig::default());
+		assert_eq!(frame.calls.len(), 1);
+		let inner = &frame.calls[0];
+		assert_eq!(inner.kind, CallKind::DelegateCall);
+		assert_eq!((inner.from, inner.to), (OUTER, Some(INNER)));
+		assert_eq!(inner.value, Some(U256::zero()));
+	}
+
+	#[test]
+	fn test_call_tracer_only_top_call() {
+		let frame = run(CallTracerConfig { only_top_call: true, with_log: true });
+		assert!(frame.calls.is_empty());
+		assert_eq!(frame.logs.len(), 1);
+
+		let frame = run(CallTracerConfig::default());
+		assert_eq!(frame.calls.len(), 1);
+		assert!(frame.logs.is_empty());
+	}
+
+	#[cfg(feature = "with-serde")]
+	#[test]
+	fn test_call_tracer_json() {
+		let frame = run(CallTracerConfig { only_top_call: false, with_log: true });
+		let json = serde_json::to_value(&frame).unwrap();
+
+		assert_eq!(json["type"], "CALL");
+		assert_eq!(json["from"], "0xcacacacacacacacacacacacacacacacacacacaca");
+		assert_eq!(json["gas"], "0x186a0");
+		assert_eq!(json["input"], "0x42");
+		assert_eq!(json["value"], "0x0");
+		assert!(json.get("output").is_none());
+		assert_eq!(json["logs"][0], serde_json::json!({
+			"address": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa", "topics": [], "data": "0x", "position": "0x1",
+		}));
+		assert_eq!(json["calls"][0]["error"], "execution reverted");
+		assert_eq!(json["calls"][0]["revertReason"], "no");
+		assert!(json["calls"][0].get("logs").is_none());
+	}
+}
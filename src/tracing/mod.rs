@@ -0,0 +1,152 @@
+//! Tracers producing the output of geth's `debug_traceTransaction`. The
+//! prestate tracer wraps the executor's state, the others are `Inspector`s.
+
+mod call;
+mod prestate;
+mod struct_log;
+
+pub use self::call::{CallFrame, CallKind, CallLog, CallTracer, CallTracerConfig};
+pub use self::prestate::{AccountState, PrestateDiff, PrestateTracer};
+pub use self::struct_log::{StructLog, StructLogConfig, StructLogTrace, StructLogger};
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use crate::{ExitError, ExitReason, Opcode};
+
+/// Mnemonic geth uses for `opcode`.
+fn opcode_name(opcode: Opcode) -> String {
+	match opcode {
+		Opcode::SHA3 => "KECCAK256".to_string(),
+		Opcode::SUICIDE => "SELFDESTRUCT".to_string(),
+		opcode => opcode.name().map_or_else(
+			|| format!("opcode {:#x} not defined", opcode.as_u8()),
+			ToString::to_string,
+		),
+	}
+}
+
+/// Message geth reports for `error`.
+fn error_message(error: ExitError) -> String {
+	let message = match error {
+		ExitError::StackUnderflow => "stack underflow",
+		ExitError::StackOverflow => "stack overflow",
+		ExitError::InvalidJump => "invalid jump destination",
+		ExitError::InvalidRange => "gas uint64 overflow",
+		ExitError::DesignatedInvalid => "invalid opcode: INVALID",
+		ExitError::InvalidCode(opcode) => return format!("invalid opcode: {}", opcode_name(opcode)),
+		ExitError::CallTooDeep => "max call depth exceeded",
+		ExitError::CreateCollision => "contract address collision",
+		ExitError::CreateContractLimit => "max code size exceeded",
+		ExitError::OutOfOffset => "return data out of bounds",
+		ExitError::OutOfGas => "out of gas",
+		ExitError::OutOfFund => "insufficient balance for transfer",
+		ExitError::StaticModeViolation => "write protection",
+		error => return format!("{error:?}"),
+	};
+	message.to_string()
+}
+
+/// Error message of an exit, if it is an error or fatal exit.
+fn exit_error(reason: ExitReason) -> Option<String> {
+	match reason {
+		ExitReason::Error(error) => Some(error_message(error)),
+		ExitReason::Fatal(fatal) => Some(format!("{fatal:?}")),
+		ExitReason::StepLimitReached | ExitReason::Succeed(_) | ExitReason::Revert(_) => None,
+	}
+}
+
+/// Serializers writing values the way geth formats them.
+#[cfg(feature = "with-serde")]
+mod geth {
+	use alloc::collections::BTreeMap;
+	use alloc::format;
+	use alloc::string::String;
+	use alloc::vec::Vec;
+	use core::fmt::Write;
+	use serde::ser::{SerializeMap, SerializeSeq};
+	use serde::Serializer;
+	use crate::{Opcode, U256};
+	use super::opcode_name;
+
+	/// Lower-case hex of `bytes`, without prefix.
+	fn hex(bytes: &[u8]) -> String {
+		let mut s = String::with_capacity(bytes.len() * 2);
+		for byte in bytes {
+			write!(s, "{byte:02x}").expect("writing to a string cannot fail");
+		}
+		s
+	}
+
+	/// 32-byte big-endian hex of a word.
+	fn word(value: U256) -> String {
+		let mut bytes = [0_u8; 32];
+		value.to_big_endian(&mut bytes);
+		hex(&bytes)
+	}
+
+	#[allow(clippy::trivially_copy_pass_by_ref)]
+	pub const fn is_zero(value: &u64) -> bool {
+		*value == 0
+	}
+
+	#[allow(clippy::ref_option)]
+	pub fn is_unset(value: &Option<u64>) -> bool {
+		value.unwrap_or_default() == 0
+	}
+
+	#[allow(clippy::ref_option)]
+	pub fn is_unset_data(data: &Option<Vec<u8>>) -> bool {
+		data.as_ref().is_none_or(Vec::is_empty)
+	}
+
+	#[allow(clippy::trivially_copy_pass_by_ref)]
+	pub fn opcode<S: Serializer>(opcode: &Opcode, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&opcode_name(*opcode))
+	}
+
+	/// Bytes as hex without prefix.
+	pub fn bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&hex(bytes))
+	}
+
+	/// Number as hex with `0x` prefix.
+	#[allow(clippy::trivially_copy_pass_by_ref)]
+	pub fn quantity<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&format!("{value:#x}"))
+	}
+
+	/// Bytes as hex with `0x` prefix.
+	pub fn data<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&format!("0x{}", hex(data)))
+	}
+
+	/// Optional bytes as hex with `0x` prefix.
+	#[allow(clippy::ref_option)]
+	pub fn optional_data<S: Serializer>(data: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error> {
+		self::data(data.as_deref().unwrap_or_default(), serializer)
+	}
+
+	/// Memory as 32-byte words in hex without prefix.
+	#[allow(clippy::ref_option)]
+	pub fn memory<S: Serializer>(memory: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error> {
+		let memory = memory.as_deref().unwrap_or_default();
+		let mut seq = serializer.serialize_seq(Some(memory.len() / 32))?;
+		for chunk in memory.chunks(32) {
+			seq.serialize_element(&hex(chunk))?;
+		}
+		seq.end()
+	}
+
+	/// Storage slots as 32-byte words in hex without prefix.
+	#[allow(clippy::ref_option)]
+	pub fn storage<S: Serializer>(
+		storage: &Option<BTreeMap<U256, U256>>,
+		serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		let mut map = serializer.serialize_map(storage.as_ref().map(BTreeMap::len))?;
+		for (index, value) in storage.iter().flatten() {
+			map.serialize_entry(&word(*index), &word(*value))?;
+		}
+		map.end()
+	}
+}
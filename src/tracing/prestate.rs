@@ -0,0 +1,406 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use crate::backend::{Backend, Basic};
+use crate::executor::StackState;
+use crate::{ExitError, Transfer, H160, H256, U256};
+#[cfg(feature = "with-serde")]
+use super::geth;
+
+/// State of an account, as far as the transaction accessed it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize))]
+pub struct AccountState {
+	/// Balance of the account.
+	#[cfg_attr(feature = "with-serde", serde(skip_serializing_if = "Option::is_none"))]
+	pub balance: Option<U256>,
+	/// Nonce of the account.
+	#[cfg_attr(feature = "with-serde", serde(skip_serializing_if = "geth::is_unset"))]
+	pub nonce: Option<u64>,
+	/// Code of the account.
+	#[cfg_attr(feature = "with-serde", serde(
+		skip_serializing_if = "geth::is_unset_data",
+		serialize_with = "geth::optional_data",
+	))]
+	pub code: Option<Vec<u8>>,
+	/// Storage slots accessed.
+	#[cfg_attr(feature = "with-serde", serde(skip_serializing_if = "BTreeMap::is_empty"))]
+	pub storage: BTreeMap<H256, H256>,
+	/// Transient storage slots accessed. Left out of geth's output.
+	#[cfg_attr(feature = "with-serde", serde(skip))]
+	pub transient_storage: BTreeMap<H256, H256>,
+}
+
+impl AccountState {
+	/// Whether the account did not exist, with no balance, nonce or code.
+	fn is_empty(&self) -> bool {
+		self.balance.unwrap_or_default().is_zero()
+			&& self.nonce.unwrap_or_default() == 0
+			&& self.code.as_ref().is_none_or(Vec::is_empty)
+	}
+}
+
+/// State changed by the transaction, as in geth's `prestateTracer` output
+/// with `diffMode`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize))]
+pub struct PrestateDiff {
+	/// Changed accounts before the transaction.
+	pub pre: BTreeMap<H160, AccountState>,
+	/// Changed fields of the accounts after the transaction. Deleted
+	/// accounts are left out.
+	pub post: BTreeMap<H160, AccountState>,
+}
+
+/// State wrapper recording every account and slot the transaction reads or
+/// writes, with its value before the transaction, like geth's
+/// `prestateTracer`.
+///
+/// Accounts are read through the `StackState` rather than reported to an
+/// `Inspector`, so the tracer wraps the state the executor runs on.
+#[derive(Clone, Debug)]
+pub struct PrestateTracer<S> {
+	state: S,
+	accounts: RefCell<BTreeMap<H160, AccountState>>,
+}
+
+impl<S: StackState> PrestateTracer<S> {
+	/// Wrap `state`, before the transaction is executed.
+	pub const fn new(state: S) -> Self {
+		Self {
+			state,
+			accounts: RefCell::new(BTreeMap::new()),
+		}
+	}
+
+	/// The wrapped state.
+	pub const fn state(&self) -> &S {
+		&self.state
+	}
+
+	/// Consume the tracer and return the wrapped state.
+	pub fn into_state(self) -> S {
+		self.state
+	}
+
+	/// Accounts accessed by the transaction, with their state before it.
+	pub fn prestate(&self) -> BTreeMap<H160, AccountState> {
+		self.accounts.borrow().clone()
+	}
+
+	/// Accounts changed by the transaction, with their state before and
+	/// after it.
+	pub fn diff(&self) -> PrestateDiff {
+		let mut diff = PrestateDiff::default();
+
+		for (&address, account) in self.accounts.borrow().iter() {
+			let mut pre = account.clone();
+			pre.transient_storage.clear();
+
+			if self.state.deleted(address) {
+				diff.pre.insert(address, pre);
+				continue
+			}
+
+			let basic = self.state.basic(address);
+			let code = self.state.code(address);
+			let mut post = AccountState::default();
+			if pre.balance != Some(basic.balance) {
+				post.balance = Some(basic.balance);
+			}
+			if pre.nonce != Some(basic.nonce.low_u64()) {
+				post.nonce = Some(basic.nonce.low_u64());
+			}
+			if pre.code.as_ref() != Some(&code) {
+				post.code = Some(code);
+			}
+
+			let mut storage_changed = false;
+			pre.storage.retain(|index, value| {
+				let current = H256::from(self.state.storage(address, U256::from_big_endian(index.as_bytes())));
+				if current == *value {
+					return false
+				}
+				storage_changed = true;
+				if current != H256::zero() {
+					post.storage.insert(*index, current);
+				}
+				true
+			});
+
+			if post != AccountState::default() || storage_changed {
+				if !(self.state.created(address) && pre.is_empty()) {
+					diff.pre.insert(address, pre);
+				}
+				diff.post.insert(address, post);
+			}
+		}
+
+		diff
+	}
+
+	/// Record the state of an account on its first access.
+	fn record_account(&self, address: H160) {
+		if self.accounts.borrow().contains_key(&address) {
+			return
+		}
+
+		let basic = self.state.basic(address);
+		self.accounts.borrow_mut().insert(address, AccountState {
+			balance: Some(basic.balance),
+			nonce: Some(basic.nonce.low_u64()),
+			code: Some(self.state.code(address)),
+			..AccountState::default()
+		});
+	}
+
+	/// Record a storage slot on its first access.
+	fn record_storage(&self, address: H160, index: U256) {
+		self.record_account(address);
+		let value = self.state.storage(address, index);
+		if let Some(account) = self.accounts.borrow_mut().get_mut(&address) {
+			account.storage.entry(H256::from(index)).or_insert_with(|| H256::from(value));
+		}
+	}
+
+	/// Record a transient storage slot on its first access.
+	fn record_transient_storage(&self, address: H160, index: U256) {
+		self.record_account(address);
+		let value = self.state.transient_storage(address, index);
+		if let Some(account) = self.accounts.borrow_mut().get_mut(&address) {
+			account.transient_storage.entry(H256::from(index)).or_insert_with(|| H256::from(value));
+		}
+	}
+}
+
+impl<S: StackState> Backend for PrestateTracer<S> {
+	fn gas_price(&self) -> U256 { self.state.gas_price() }
+	fn origin(&self) -> H160 { self.state.origin() }
+	fn block_hash(&self, number: U256) -> H256 { self.state.block_hash(number) }
+	fn block_number(&self) -> U256 { self.state.block_number() }
+	fn block_coinbase(&self) -> H160 { self.state.block_coinbase() }
+	fn block_timestamp(&self) -> U256 { self.state.block_timestamp() }
+	fn block_difficulty(&self) -> U256 { self.state.block_difficulty() }
+	fn block_gas_limit(&self) -> U256 { self.state.block_gas_limit() }
+	fn block_base_fee_per_gas(&self) -> U256 { self.state.block_base_fee_per_gas() }
+	fn block_blob_base_fee(&self) -> U256 { self.state.block_blob_base_fee() }
+	fn blob_versioned_hash(&self, index: usize) -> Option<H256> { self.state.blob_versioned_hash(index) }
+	fn chain_id(&self) -> U256 { self.state.chain_id() }
+
+	fn exists(&self, address: H160) -> bool {
+		self.record_account(address);
+		self.state.exists(address)
+	}
+
+	fn basic(&self, address: H160) -> Basic {
+		self.record_account(address);
+		self.state.basic(address)
+	}
+
+	fn code(&self, address: H160) -> Vec<u8> {
+		self.record_account(address);
+		self.state.code(address)
+	}
+
+	fn storage(&self, address: H160, index: U256) -> U256 {
+		self.record_storage(address, index);
+		self.state.storage(address, index)
+	}
+
+	fn original_storage(&self, address: H160, index: U256) -> Option<U256> {
+		self.record_storage(address, index);
+		self.state.original_storage(address, index)
+	}
+}
+
+impl<S: StackState> StackState for PrestateTracer<S> {
+	fn transient_storage(&self, address: H160, index: U256) -> U256 {
+		self.record_transient_storage(address, index);
+		self.state.transient_storage(address, index)
+	}
+
+	fn deleted(&self, address: H160) -> bool {
+		self.state.deleted(address)
+	}
+
+	fn created(&self, address: H160) -> bool {
+		self.state.created(address)
+	}
+
+	fn is_cold(&self, address: H160, index: Option<U256>) -> bool {
+		self.state.is_cold(address, index)
+	}
+
+	fn begin_transaction(&mut self) {
+		self.state.begin_transaction();
+	}
+
+	fn checkpoint(&mut self) {
+		self.state.checkpoint();
+	}
+
+	fn commit(&mut self) {
+		self.state.commit();
+	}
+
+	fn revert(&mut self) {
+		self.state.revert();
+	}
+
+	fn mark_warm(&mut self, address: H160, index: Option<U256>) {
+		self.state.mark_warm(address, index);
+	}
+
+	fn inc_nonce(&mut self, address: H160) {
+		self.record_account(address);
+		self.state.inc_nonce(address);
+	}
+
+	fn set_storage(&mut self, address: H160, index: U256, value: U256) {
+		self.record_storage(address, index);
+		self.state.set_storage(address, index, value);
+	}
+
+	fn reset_storage(&mut self, address: H160) {
+		self.record_account(address);
+		self.state.reset_storage(address);
+	}
+
+	fn set_transient_storage(&mut self, address: H160, index: U256, value: U256) {
+		self.record_transient_storage(address, index);
+		self.state.set_transient_storage(address, index, value);
+	}
+
+	fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) {
+		self.state.log(address, topics, data);
+	}
+
+	fn set_deleted(&mut self, address: H160) {
+		self.record_account(address);
+		self.state.set_deleted(address);
+	}
+
+	fn set_created(&mut self, address: H160) {
+		self.record_account(address);
+		self.state.set_created(address);
+	}
+
+	fn set_code(&mut self, address: H160, code: Vec<u8>) {
+		self.record_account(address);
+		self.state.set_code(address, code);
+	}
+
+	fn transfer(&mut self, transfer: Transfer) -> Result<(), ExitError> {
+		self.record_account(transfer.source);
+		self.record_account(transfer.target);
+		self.state.transfer(transfer)
+	}
+
+	fn reset_balance(&mut self, address: H160) {
+		self.record_account(address);
+		self.state.reset_balance(address);
+	}
+
+	fn touch(&mut self, address: H160) {
+		self.record_account(address);
+		self.state.touch(address);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::collections::BTreeMap;
+	use crate::backend::{with_memory_backend, MemoryAccount, MemoryBackend};
+	use crate::executor::{MemoryStackState, StackExecutor};
+	use crate::{Config, ExitReason, ExitSucceed, H160, H256, U256};
+	use super::{PrestateDiff, PrestateTracer};
+
+	const CALLER: H160 = H160([0xca; 20]);
+	const TARGET: H160 = H160([0xaa; 20]);
+
+	fn word(value: u64) -> H256 {
+		H256::from(U256::from(value))
+	}
+
+	fn run(f: impl FnOnce(&PrestateTracer<MemoryStackState<'_, MemoryBackend<'_>>>)) {
+		let mut accounts = BTreeMap::new();
+		accounts.insert(CALLER, MemoryAccount {
+			balance: U256::from(100),
+			..MemoryAccount::default()
+		});
+		// POP(SLOAD(2)); SSTORE(0, SLOAD(1)); TSTORE(3, 1); STOP
+		accounts.insert(TARGET, MemoryAccount {
+			code: hex::decode("60025450600154600055600160035d00").unwrap(),
+			storage: vec![(U256::one(), U256::from(5)), (U256::from(2), U256::from(7))].into_iter().collect(),
+			..MemoryAccount::default()
+		});
+
+		let config = Config::cancun();
+		with_memory_backend(accounts, |backend| {
+			let mut executor = StackExecutor::new(PrestateTracer::new(MemoryStackState::new(backend)), &config);
+			let (reason, _) = executor.transact_call(CALLER, TARGET, U256::from(10), Vec::new(), 100_000, &[]);
+			assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+
+			f(executor.state());
+		});
+	}
+
+	#[test]
+	fn test_prestate() {
+		run(|tracer| {
+			let prestate = tracer.prestate();
+
+			let caller = &prestate[&CALLER];
+			assert_eq!((caller.balance, caller.nonce), (Some(U256::from(100)), Some(0)));
+			assert!(caller.storage.is_empty());
+
+			let target = &prestate[&TARGET];
+			assert_eq!(target.balance, Some(U256::zero()));
+			assert_eq!(target.code.as_ref().map(Vec::len), Some(16));
+			assert_eq!(target.storage, vec![
+				(word(0), word(0)), (word(1), word(5)), (word(2), word(7)),
+			].into_iter().collect());
+			assert_eq!(target.transient_storage, vec![(word(3), word(0))].into_iter().collect());
+		});
+	}
+
+	#[test]
+	fn test_prestate_diff() {
+		run(|tracer| {
+			let PrestateDiff { pre, post } = tracer.diff();
+			assert_eq!(pre.keys().collect::<Vec<_>>(), vec![&TARGET, &CALLER]);
+
+			assert_eq!(pre[&CALLER].balance, Some(U256::from(100)));
+			assert_eq!(post[&CALLER].balance, Some(U256::from(90)));
+			assert_eq!(post[&CALLER].nonce, Some(1));
+			assert_eq!(post[&CALLER].code, None);
+
+			assert_eq!(pre[&TARGET].storage, vec![(word(0), word(0))].into_iter().collect());
+			assert!(pre[&TARGET].transient_storage.is_empty());
+			assert_eq!(post[&TARGET].balance, Some(U256::from(10)));
+			assert_eq!(post[&TARGET].nonce, None);
+			assert_eq!(post[&TARGET].storage, vec![(word(0), word(5))].into_iter().collect());
+		});
+	}
+
+	#[cfg(feature = "with-serde")]
+	#[test]
+	fn test_prestate_json() {
+		run(|tracer| {
+			let target = "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+			let slot = |value: u64| format!("0x{value:064x}");
+
+			let json = serde_json::to_value(tracer.prestate()).unwrap();
+			assert_eq!(json["0xcacacacacacacacacacacacacacacacacacacaca"], serde_json::json!({ "balance": "0x64" }));
+			assert_eq!(json[target]["balance"], "0x0");
+			assert_eq!(json[target]["code"], "0x60025450600154600055600160035d00");
+			assert_eq!(json[target]["storage"][slot(1)], slot(5));
+			assert!(json[target].get("nonce").is_none());
+
+			let json = serde_json::to_value(tracer.diff()).unwrap();
+			assert_eq!(json["post"][target], serde_json::json!({
+				"balance": "0xa", "storage": { slot(0): slot(5) },
+			}));
+		});
+	}
+}
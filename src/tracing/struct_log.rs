@@ -0,0 +1,318 @@
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use crate::{
+	CallScheme, Context, CreateScheme, ExitReason, Inspector, Memory, Opcode, Stack, Step, Transfer,
+	H160, U256,
+};
+use super::exit_error;
+#[cfg(feature = "with-serde")]
+use super::geth;
+
+/// Options of the struct-log tracer, named after the ones of geth.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "with-serde", serde(rename_all = "camelCase", default))]
+pub struct StructLogConfig {
+	/// Leave out the stack of each step.
+	pub disable_stack: bool,
+	/// Leave out the memory of each step.
+	pub disable_memory: bool,
+	/// Leave out the storage read or written by `SLOAD` and `SSTORE`.
+	pub disable_storage: bool,
+	/// Include the return data of the last call in each step.
+	pub enable_return_data: bool,
+}
+
+/// A single executed opcode, as an entry of geth's `structLogs`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "with-serde", serde(rename_all = "camelCase"))]
+pub struct StructLog {
+	/// Program counter.
+	pub pc: usize,
+	/// Executed opcode.
+	#[cfg_attr(feature = "with-serde", serde(serialize_with = "geth::opcode"))]
+	pub op: Opcode,
+	/// Gas left before the opcode.
+	pub gas: u64,
+	/// Gas charged by the opcode, including the gas given to a call or
+	/// create it starts.
+	pub gas_cost: u64,
+	/// Call depth, starting at 1.
+	pub depth: usize,
+	/// Error the opcode failed with.
+	#[cfg_attr(feature = "with-serde", serde(skip_serializing_if = "Option::is_none"))]
+	pub error: Option<String>,
+	/// Stack before the opcode, from bottom to top.
+	#[cfg_attr(feature = "with-serde", serde(skip_serializing_if = "Option::is_none"))]
+	pub stack: Option<Vec<U256>>,
+	/// Return data of the last call made by the frame.
+	#[cfg_attr(feature = "with-serde", serde(
+		skip_serializing_if = "Option::is_none",
+		serialize_with = "geth::optional_data",
+	))]
+	pub return_data: Option<Vec<u8>>,
+	/// Memory before the opcode.
+	#[cfg_attr(feature = "with-serde", serde(
+		skip_serializing_if = "Option::is_none",
+		serialize_with = "geth::memory",
+	))]
+	pub memory: Option<Vec<u8>>,
+	/// Storage of the running account seen so far, only set for `SLOAD` and
+	/// `SSTORE`.
+	#[cfg_attr(feature = "with-serde", serde(
+		skip_serializing_if = "Option::is_none",
+		serialize_with = "geth::storage",
+	))]
+	pub storage: Option<BTreeMap<U256, U256>>,
+	/// Gas refunded so far in the transaction.
+	#[cfg_attr(feature = "with-serde", serde(skip_serializing_if = "geth::is_zero"))]
+	pub refund: u64,
+}
+
+/// Result of a traced transaction, as returned by geth's default tracer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "with-serde", serde(rename_all = "camelCase"))]
+pub struct StructLogTrace {
+	/// Gas used by the transaction.
+	pub gas: u64,
+	/// Whether the transaction failed or reverted.
+	pub failed: bool,
+	/// Return value of the transaction.
+	#[cfg_attr(feature = "with-serde", serde(serialize_with = "geth::bytes"))]
+	pub return_value: Vec<u8>,
+	/// Executed opcodes.
+	pub struct_logs: Vec<StructLog>,
+}
+
+/// Inspector recording every executed opcode, like geth's struct logger.
+#[derive(Clone, Debug, Default)]
+pub struct StructLogger {
+	config: StructLogConfig,
+	logs: Vec<StructLog>,
+	/// Steps waiting for their result, with the gas given back by the frames
+	/// they entered.
+	pending: Vec<(usize, u64)>,
+	storages: BTreeMap<H160, BTreeMap<U256, U256>>,
+	depth: usize,
+	failed: bool,
+	return_value: Vec<u8>,
+}
+
+impl StructLogger {
+	/// Create a new tracer with the given options.
+	#[must_use]
+	pub fn new(config: StructLogConfig) -> Self {
+		Self {
+			config,
+			..Self::default()
+		}
+	}
+
+	/// Opcodes recorded so far.
+	#[must_use]
+	pub fn logs(&self) -> &[StructLog] {
+		&self.logs
+	}
+
+	/// Consume the tracer into the trace of the transaction, given the gas
+	/// it used.
+	#[must_use]
+	pub fn into_trace(self, gas: u64) -> StructLogTrace {
+		StructLogTrace {
+			gas,
+			failed: self.failed,
+			return_value: self.return_value,
+			struct_logs: self.logs,
+		}
+	}
+
+	fn record_storage(&mut self, address: H160, index: U256, value: U256) {
+		let storage = self.storages.entry(address).or_default();
+		storage.insert(index, value);
+
+		if !self.config.disable_storage {
+			let storage = storage.clone();
+			if let Some(log) = self.logs.last_mut() {
+				log.storage = Some(storage);
+			}
+		}
+	}
+}
+
+impl Inspector for StructLogger {
+	fn step(&mut self, step: &Step<'_>) {
+		let stack = (!self.config.disable_stack).then(|| {
+			(0..step.stack.len()).rev()
+				.map(|i| step.stack.peek(i).expect("index is within the stack"))
+				.collect()
+		});
+		let memory = (!self.config.disable_memory && step.memory.effective_len() > 0)
+			.then(|| step.memory.get(0, step.memory.effective_len()));
+		let return_data = (self.config.enable_return_data && !step.return_data.is_empty())
+			.then(|| step.return_data.to_vec());
+
+		self.pending.push((self.logs.len(), 0));
+		self.logs.push(StructLog {
+			pc: step.position,
+			op: step.opcode,
+			gas: step.gas,
+			gas_cost: 0,
+			depth: step.depth + 1,
+			error: None,
+			stack,
+			return_data,
+			memory,
+			storage: None,
+			refund: u64::try_from(step.refund).unwrap_or_default(),
+		});
+	}
+
+	fn step_result(&mut self, exit: Option<&ExitReason>, gas: u64, _stack: &Stack, _memory: &Memory) {
+		if let Some((index, returned)) = self.pending.pop() {
+			let log = &mut self.logs[index];
+			log.gas_cost = (log.gas + returned).saturating_sub(gas);
+			log.error = exit.copied().and_then(exit_error);
+		}
+	}
+
+	fn sload(&mut self, address: H160, index: U256, value: U256) {
+		self.record_storage(address, index, value);
+	}
+
+	fn sstore(&mut self, address: H160, index: U256, value: U256) {
+		self.record_storage(address, index, value);
+	}
+
+	fn call(
+		&mut self,
+		_scheme: CallScheme,
+		_code_address: H160,
+		_transfer: Option<&Transfer>,
+		_input: &[u8],
+		_gas: u64,
+		_context: &Context,
+	) {
+		self.depth += 1;
+	}
+
+	fn create(
+		&mut self,
+		_scheme: CreateScheme,
+		_address: H160,
+		_value: U256,
+		_init_code: &[u8],
+		_gas: u64,
+	) {
+		self.depth += 1;
+	}
+
+	fn exit(&mut self, reason: &ExitReason, return_value: &[u8], gas_left: u64) {
+		self.depth = self.depth.saturating_sub(1);
+		if self.depth == 0 {
+			self.failed = !matches!(reason, ExitReason::Succeed(_));
+			self.return_value = return_value.to_vec();
+		} else if let Some((_, returned)) = self.pending.last_mut() {
+			*returned += gas_left;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::collections::BTreeMap;
+	use crate::backend::{with_memory_backend, MemoryAccount};
+	use crate::executor::{MemoryStackState, StackExecutor};
+	use crate::{Config, ExitError, ExitReason, ExitSucceed, Opcode, H160, H256, U256};
+	use super::{StructLogConfig, StructLogTrace, StructLogger};
+
+	fn run(code: &str, gas_limit: u64, config: StructLogConfig) -> (ExitReason, StructLogTrace) {
+		let caller = H160::repeat_byte(0xca);
+		let target = H160::repeat_byte(0xaa);
+		let mut accounts = BTreeMap::new();
+		accounts.insert(target, MemoryAccount {
+			code: hex::decode(code).unwrap(),
+			..MemoryAccount::default()
+		});
+
+		let evm_config = Config::istanbul();
+		let mut logger = StructLogger::new(config);
+		let (reason, used_gas) = with_memory_backend(accounts, |backend| {
+			let mut executor = StackExecutor::new_with_inspector(
+				MemoryStackState::new(backend), &evm_config, (), &mut logger,
+			);
+			let (reason, _) = executor.transact_call(caller, target, U256::zero(), Vec::new(), gas_limit, &[]);
+			(reason, executor.used_gas())
+		});
+
+		(reason, logger.into_trace(used_gas))
+	}
+
+	// SSTORE(0, 42); MSTORE(0, SLOAD(0)); RETURN(0, 32)
+	const CODE: &str = "602a60005560005460005260206000f3";
+
+	#[test]
+	fn test_struct_logs() {
+		let (reason, trace) = run(CODE, 100_000, StructLogConfig::default());
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Returned));
+		assert!(!trace.failed);
+		assert_eq!(trace.return_value, H256::from(U256::from(42)).as_bytes().to_vec());
+		assert_eq!(trace.gas, 21_000 + 20_000 + 800 + 6 * 3 + 6);
+
+		let logs = &trace.struct_logs;
+		assert_eq!(logs.len(), 10);
+		assert_eq!((logs[0].pc, logs[0].op, logs[0].gas, logs[0].gas_cost), (0, Opcode::PUSH1, 79_000, 3));
+		assert_eq!(logs[0].depth, 1);
+		assert_eq!(logs[0].stack, Some(Vec::new()));
+		assert_eq!(logs[0].memory, None);
+		assert_eq!((logs[2].op, logs[2].gas_cost), (Opcode::SSTORE, 20_000));
+		assert_eq!(logs[2].stack, Some(vec![U256::from(42), U256::zero()]));
+		assert_eq!(logs[2].storage, Some(vec![(U256::zero(), U256::from(42))].into_iter().collect()));
+		assert_eq!((logs[4].op, logs[4].gas_cost), (Opcode::SLOAD, 800));
+		assert_eq!(logs[5].storage, None);
+		assert_eq!(logs[9].op, Opcode::RETURN);
+		assert_eq!(logs[9].memory, Some(H256::from(U256::from(42)).as_bytes().to_vec()));
+		assert!(logs.iter().all(|log| log.error.is_none()));
+
+		let config = StructLogConfig {
+			disable_stack: true,
+			disable_memory: true,
+			disable_storage: true,
+			enable_return_data: false,
+		};
+		let (_, trace) = run(CODE, 100_000, config);
+		assert!(trace.struct_logs.iter().all(|log| {
+			log.stack.is_none() && log.memory.is_none() && log.storage.is_none()
+		}));
+	}
+
+	#[test]
+	fn test_struct_logs_error() {
+		let (reason, trace) = run(CODE, 30_000, StructLogConfig::default());
+		assert_eq!(reason, ExitReason::Error(ExitError::OutOfGas));
+		assert!(trace.failed);
+		assert_eq!(trace.struct_logs.len(), 3);
+		assert_eq!(trace.struct_logs[2].error.as_deref(), Some("out of gas"));
+	}
+
+	#[cfg(feature = "with-serde")]
+	#[test]
+	fn test_struct_logs_json() {
+		let (_, trace) = run(CODE, 100_000, StructLogConfig::default());
+		let json = serde_json::to_value(&trace).unwrap();
+		let zero = "0".repeat(64);
+
+		assert_eq!(json["failed"], false);
+		assert_eq!(json["returnValue"], format!("{}2a", "0".repeat(62)));
+		assert_eq!(json["structLogs"][0], serde_json::json!({
+			"pc": 0, "op": "PUSH1", "gas": 79_000, "gasCost": 3, "depth": 1, "stack": [],
+		}));
+		assert_eq!(json["structLogs"][2]["stack"], serde_json::json!(["0x2a", "0x0"]));
+		assert_eq!(json["structLogs"][2]["storage"][&zero], format!("{}2a", "0".repeat(62)));
+		assert_eq!(json["structLogs"][9]["memory"], serde_json::json!([format!("{}2a", "0".repeat(62))]));
+	}
+}